@@ -0,0 +1,469 @@
+use crate::backend::{
+    AllFilter, Backend, FilteredPage, NewEvent, ProjectionCommand, ReadDirection, ReadRequest,
+    VersionedStreamMetadata,
+};
+use eventstore::operations::{MemberInfo, ServerVersion, Stats, StatsOptions};
+use eventstore::{
+    AppendToStreamOptions, ClientSettings, DeleteStreamOptions, EventData, ExpectedRevision,
+    PersistentSubscriptionInfo, Position, ProjectionStatus, ReadAllOptions, ReadStreamOptions,
+    ResolvedEvent, RevisionOrPosition, StreamMetadata, StreamMetadataResult, StreamPosition,
+    SubscribeToAllOptions, SubscribeToStreamOptions, Subscription, SubscriptionFilter,
+    TombstoneStreamOptions, WriteResult,
+};
+use eventstore_extras::stats::{Statistics, StatisticsExt};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+// Filtered subscriptions don't say when they caught up, a read ends once nothing matched for that
+// long. The page is then flagged as partial, the server may still have been scanning.
+const FILTERED_READ_IDLE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Backend talking to a live EventStoreDB cluster.
+#[derive(Clone)]
+pub struct EsdbBackend {
+    client: eventstore::Client,
+    op_client: eventstore::operations::Client,
+    proj_client: eventstore::ProjectionClient,
+    stats: Arc<Mutex<Option<Stats>>>,
+}
+
+impl EsdbBackend {
+    /// Must be called from within a tokio runtime.
+    pub fn new(setts: ClientSettings) -> eventstore::Result<Self> {
+        let proj_client = eventstore::ProjectionClient::new(setts.clone());
+        let client = eventstore::Client::new(setts)?;
+        let op_client = eventstore::operations::Client::from(client.clone());
+
+        Ok(Self {
+            client,
+            op_client,
+            proj_client,
+            stats: Arc::new(Mutex::new(None)),
+        })
+    }
+}
+
+impl Backend for EsdbBackend {
+    fn read_stream(
+        &self,
+        stream_name: String,
+        request: ReadRequest<u64>,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<ResolvedEvent>>> {
+        let client = self.client.clone();
+
+        async move {
+            let mut options = ReadStreamOptions::default()
+                .position(request.position)
+                .max_count(request.max_count);
+
+            options = match request.direction {
+                ReadDirection::Forwards => options.forwards(),
+                ReadDirection::Backwards => options.backwards(),
+            };
+
+            if request.resolve_link_tos {
+                options = options.resolve_link_tos();
+            }
+
+            let mut stream = client.read_stream(stream_name.as_str(), &options).await?;
+            let mut events = Vec::new();
+
+            while let Some(event) = stream.next().await? {
+                events.push(event);
+            }
+
+            Ok(events)
+        }
+        .boxed()
+    }
+
+    fn read_all(
+        &self,
+        request: ReadRequest<Position>,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<ResolvedEvent>>> {
+        let client = self.client.clone();
+
+        async move {
+            let mut options = ReadAllOptions::default()
+                .position(request.position)
+                .max_count(request.max_count);
+
+            options = match request.direction {
+                ReadDirection::Forwards => options.forwards(),
+                ReadDirection::Backwards => options.backwards(),
+            };
+
+            if request.resolve_link_tos {
+                options = options.resolve_link_tos();
+            }
+
+            let mut stream = client.read_all(&options).await?;
+            let mut events = Vec::new();
+
+            while let Some(event) = stream.next().await? {
+                events.push(event);
+            }
+
+            Ok(events)
+        }
+        .boxed()
+    }
+
+    fn subscribe_to_stream(
+        &self,
+        stream_name: String,
+        from: StreamPosition<u64>,
+    ) -> BoxStream<'static, eventstore::Result<ResolvedEvent>> {
+        let client = self.client.clone();
+
+        async move {
+            let options = SubscribeToStreamOptions::default()
+                .start_from(from)
+                .resolve_link_tos();
+
+            into_stream(client.subscribe_to_stream(stream_name, &options).await)
+        }
+        .flatten_stream()
+        .boxed()
+    }
+
+    fn subscribe_to_all(
+        &self,
+        from: StreamPosition<Position>,
+        filter: Option<AllFilter>,
+    ) -> BoxStream<'static, eventstore::Result<ResolvedEvent>> {
+        let client = self.client.clone();
+
+        async move {
+            let mut options = SubscribeToAllOptions::default()
+                .position(from)
+                .resolve_link_tos();
+
+            if let Some(filter) = filter {
+                options = options.filter(into_subscription_filter(filter));
+            }
+
+            into_stream(client.subscribe_to_all(&options).await)
+        }
+        .flatten_stream()
+        .boxed()
+    }
+
+    fn read_all_filtered(
+        &self,
+        from: StreamPosition<Position>,
+        filter: Option<AllFilter>,
+        max_count: usize,
+    ) -> BoxFuture<'static, eventstore::Result<FilteredPage>> {
+        let client = self.client.clone();
+
+        async move {
+            let options = ReadAllOptions::default()
+                .position(StreamPosition::End)
+                .backwards()
+                .max_count(1);
+
+            let last = match client.read_all(&options).await?.next().await? {
+                Some(event) => event.get_original_event().position,
+                None => return Ok(FilteredPage::default()),
+            };
+
+            let mut options = SubscribeToAllOptions::default().position(from);
+
+            if let Some(filter) = filter {
+                options = options.filter(into_subscription_filter(filter));
+            }
+
+            let mut subscription = client.subscribe_to_all(&options).await;
+            let mut page = FilteredPage::default();
+
+            while page.events.len() < max_count {
+                let event =
+                    match tokio::time::timeout(FILTERED_READ_IDLE_TIMEOUT, subscription.next())
+                        .await
+                    {
+                        Ok(event) => event?,
+                        Err(_) => {
+                            page.partial = true;
+                            break;
+                        }
+                    };
+
+                let position = event.get_original_event().position;
+                page.events.push(event);
+
+                // Past that point, the subscription waits for new events.
+                if position.commit >= last.commit {
+                    break;
+                }
+            }
+
+            Ok(page)
+        }
+        .boxed()
+    }
+
+    fn append_to_stream(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+        events: Vec<NewEvent>,
+    ) -> BoxFuture<'static, eventstore::Result<WriteResult>> {
+        let client = self.client.clone();
+
+        async move {
+            let events = events
+                .into_iter()
+                .map(into_event_data)
+                .collect::<eventstore::Result<Vec<_>>>()?;
+
+            let options = AppendToStreamOptions::default().expected_revision(expected);
+
+            client.append_to_stream(stream_name, &options, events).await
+        }
+        .boxed()
+    }
+
+    fn get_stream_metadata(
+        &self,
+        stream_name: String,
+    ) -> BoxFuture<'static, eventstore::Result<Option<VersionedStreamMetadata>>> {
+        let client = self.client.clone();
+
+        async move {
+            let result = client
+                .get_stream_metadata(stream_name, &ReadStreamOptions::default())
+                .await?;
+
+            match result {
+                StreamMetadataResult::Success(versioned) => Ok(Some(VersionedStreamMetadata {
+                    revision: versioned.version(),
+                    metadata: versioned.metadata().clone(),
+                })),
+                StreamMetadataResult::NotFound { .. } => Ok(None),
+                StreamMetadataResult::Deleted { .. } => Err(eventstore::Error::ResourceDeleted),
+            }
+        }
+        .boxed()
+    }
+
+    fn set_stream_metadata(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+        metadata: StreamMetadata,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        let client = self.client.clone();
+
+        async move {
+            let options = AppendToStreamOptions::default().expected_revision(expected);
+
+            client
+                .set_stream_metadata(stream_name, &options, &metadata)
+                .await?;
+
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn delete_stream(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        let client = self.client.clone();
+
+        async move {
+            let options = DeleteStreamOptions::default().expected_revision(expected);
+
+            client.delete_stream(stream_name, &options).await?;
+
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn tombstone_stream(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        let client = self.client.clone();
+
+        async move {
+            let options = TombstoneStreamOptions::default().expected_revision(expected);
+
+            client.tombstone_stream(stream_name, &options).await?;
+
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn list_projections(&self) -> BoxFuture<'static, eventstore::Result<Vec<ProjectionStatus>>> {
+        let client = self.proj_client.clone();
+
+        async move {
+            client
+                .list(&Default::default())
+                .await?
+                .try_collect::<Vec<_>>()
+                .await
+        }
+        .boxed()
+    }
+
+    fn update_projection(
+        &self,
+        name: String,
+        command: ProjectionCommand,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        let client = self.proj_client.clone();
+
+        async move {
+            let options = Default::default();
+
+            match command {
+                ProjectionCommand::Enable => client.enable(name, &options).await,
+                ProjectionCommand::Disable => client.disable(name, &options).await,
+                ProjectionCommand::Reset => client.reset(name, &options).await,
+                ProjectionCommand::Abort => client.abort(name, &options).await,
+            }
+        }
+        .boxed()
+    }
+
+    fn projection_state(
+        &self,
+        name: String,
+    ) -> BoxFuture<'static, eventstore::Result<serde_json::Value>> {
+        let client = self.proj_client.clone();
+
+        async move { client.get_state(name, &Default::default()).await }.boxed()
+    }
+
+    fn projection_result(
+        &self,
+        name: String,
+    ) -> BoxFuture<'static, eventstore::Result<serde_json::Value>> {
+        let client = self.proj_client.clone();
+
+        async move { client.get_result(name, &Default::default()).await }.boxed()
+    }
+
+    fn list_persistent_subscriptions(
+        &self,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<PersistentSubscriptionInfo<RevisionOrPosition>>>>
+    {
+        let client = self.client.clone();
+
+        async move {
+            client
+                .list_all_persistent_subscriptions(&Default::default())
+                .await
+        }
+        .boxed()
+    }
+
+    fn read_gossip(&self) -> BoxFuture<'static, eventstore::Result<Vec<MemberInfo>>> {
+        let client = self.op_client.clone();
+
+        async move { client.read_gossip().await }.boxed()
+    }
+
+    fn server_version(&self) -> BoxFuture<'static, eventstore::Result<Option<ServerVersion>>> {
+        let client = self.op_client.clone();
+
+        async move {
+            let info = client.server_version().await?;
+
+            Ok(info.map(|i| i.version()))
+        }
+        .boxed()
+    }
+
+    fn stats(&self) -> BoxFuture<'static, eventstore::Result<Statistics>> {
+        let client = self.op_client.clone();
+        let state = self.stats.clone();
+
+        async move {
+            let mut state = state.lock().await;
+
+            if state.is_none() {
+                let options = StatsOptions::default().refresh_time(Duration::from_secs(2));
+                *state = Some(client.stats(&options).await?);
+            }
+
+            match state.as_mut().unwrap().next().await {
+                Ok(stats) => stats.parse_statistics(),
+                Err(e) => {
+                    // The subscription is dead, the next call starts a new one.
+                    *state = None;
+                    Err(e)
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+fn into_event_data(event: NewEvent) -> eventstore::Result<EventData> {
+    let mut data = if event.is_json {
+        let payload = serde_json::from_slice::<serde_json::Value>(event.data.as_slice())
+            .and_then(|payload| EventData::json(event.event_type.as_str(), payload));
+
+        payload.map_err(|e| eventstore::Error::InternalParsingError(e.to_string()))?
+    } else {
+        EventData::binary(event.event_type.as_str(), event.data.into())
+    };
+
+    if let Some(id) = event.id {
+        data = data.id(id);
+    }
+
+    if !event.metadata.is_empty() {
+        data = data.metadata(event.metadata.into());
+    }
+
+    Ok(data)
+}
+
+fn into_subscription_filter(filter: AllFilter) -> SubscriptionFilter {
+    let with_prefixes = |filter: SubscriptionFilter, prefixes: Vec<String>| {
+        prefixes
+            .into_iter()
+            .fold(filter, |filter, prefix| filter.add_prefix(prefix))
+    };
+
+    match filter {
+        AllFilter::ExcludeSystemEvents => SubscriptionFilter::exclude_system_events(),
+        AllFilter::StreamNamePrefixes(prefixes) => {
+            with_prefixes(SubscriptionFilter::on_stream_name(), prefixes)
+        }
+        AllFilter::StreamNameRegex(regex) => SubscriptionFilter::on_stream_name().regex(regex),
+        AllFilter::EventTypePrefixes(prefixes) => {
+            with_prefixes(SubscriptionFilter::on_event_type(), prefixes)
+        }
+        AllFilter::EventTypeRegex(regex) => SubscriptionFilter::on_event_type().regex(regex),
+    }
+}
+
+// Ends after the first error, subscriptions don't recover from them.
+fn into_stream(
+    subscription: Subscription,
+) -> BoxStream<'static, eventstore::Result<ResolvedEvent>> {
+    futures::stream::unfold(Some(subscription), |subscription| async move {
+        let mut subscription = subscription?;
+
+        match subscription.next().await {
+            Ok(event) => Some((Ok(event), Some(subscription))),
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+    .boxed()
+}
@@ -0,0 +1,786 @@
+use crate::backend::{
+    AllFilter, Backend, FilteredPage, NewEvent, ProjectionCommand, ReadDirection, ReadRequest,
+    VersionedStreamMetadata,
+};
+use chrono::{TimeZone, Utc};
+use eventstore::operations::{MemberInfo, ServerVersion, VNodeState};
+use eventstore::{
+    CurrentRevision, Endpoint, ExpectedRevision, PersistentSubscriptionInfo,
+    PersistentSubscriptionStats, Position, ProjectionStatus, RecordedEvent, ResolvedEvent,
+    RevisionOrPosition, StreamMetadata, StreamPosition, WriteResult,
+};
+use eventstore_extras::stats::Statistics;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+struct Subscriber {
+    // `None` for `$all`.
+    stream: Option<String>,
+    filter: Option<AllFilter>,
+    sender: UnboundedSender<ResolvedEvent>,
+}
+
+/// Whether `event` passes the server-side `filter` of `$all`. Regular expressions aren't
+/// supported, they keep nothing.
+fn is_kept(filter: Option<&AllFilter>, event: &RecordedEvent) -> bool {
+    let prefixed = |value: &str, prefixes: &[String]| {
+        prefixes
+            .iter()
+            .any(|prefix| value.starts_with(prefix.as_str()))
+    };
+
+    match filter {
+        None => true,
+        Some(AllFilter::ExcludeSystemEvents) => !event.event_type.starts_with('$'),
+        Some(AllFilter::StreamNamePrefixes(prefixes)) => {
+            prefixed(event.stream_id.as_str(), prefixes)
+        }
+        Some(AllFilter::EventTypePrefixes(prefixes)) => {
+            prefixed(event.event_type.as_str(), prefixes)
+        }
+        Some(_) => false,
+    }
+}
+
+/// Fake cluster kept in memory, seeded with streams, projections, persistent subscriptions and
+/// gossip members. Everything is deterministic (ids, positions and dates) so rendered views can
+/// be compared against snapshots.
+#[derive(Clone, Default)]
+pub struct InMemoryBackend {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    log: Vec<RecordedEvent>,
+    streams: BTreeMap<String, Vec<usize>>,
+    projections: Vec<ProjectionStatus>,
+    // State and result by projection name.
+    projection_outputs: HashMap<String, (serde_json::Value, serde_json::Value)>,
+    subscriptions: Vec<PersistentSubscriptionInfo<RevisionOrPosition>>,
+    members: Vec<MemberInfo>,
+    offline: bool,
+    // Filtered reads of `$all` give up after their first event.
+    slow_scans: bool,
+    subscribers: Vec<Subscriber>,
+    stream_metadata: HashMap<String, VersionedStreamMetadata>,
+    tombstoned: HashSet<String>,
+}
+
+impl State {
+    fn projection_output(
+        &self,
+        name: &str,
+    ) -> eventstore::Result<(serde_json::Value, serde_json::Value)> {
+        if !self.projections.iter().any(|p| p.name == name) {
+            return Err(eventstore::Error::ResourceNotFound);
+        }
+
+        Ok(self
+            .projection_outputs
+            .get(name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn push(&mut self, stream_id: &str, event: NewEvent) -> u64 {
+        let seq = self.log.len();
+        let indexes = self.streams.entry(stream_id.to_string()).or_default();
+        let revision = indexes.len() as u64;
+        let content_type = if event.is_json {
+            "application/json"
+        } else {
+            "application/octet-stream"
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("type".to_string(), event.event_type.clone());
+        metadata.insert("content-type".to_string(), content_type.to_string());
+
+        indexes.push(seq);
+        self.log.push(RecordedEvent {
+            stream_id: stream_id.to_string(),
+            id: event.id.unwrap_or_else(|| Uuid::from_u128(seq as u128 + 1)),
+            revision,
+            event_type: event.event_type,
+            data: event.data.into(),
+            metadata,
+            custom_metadata: event.metadata.into(),
+            is_json: event.is_json,
+            position: Position {
+                commit: seq as u64 * 100,
+                prepare: seq as u64 * 100,
+            },
+            created: Utc.timestamp_opt(1_650_000_000 + seq as i64, 0).unwrap(),
+        });
+
+        let event = self.log[seq].clone();
+        let subscribers = std::mem::take(&mut self.subscribers);
+
+        self.subscribers = subscribers
+            .into_iter()
+            .filter(|subscriber| match subscriber.stream.as_ref() {
+                Some(stream) if *stream != event.stream_id => true,
+                None if !is_kept(subscriber.filter.as_ref(), &event) => true,
+                Some(_) => subscriber
+                    .sender
+                    .unbounded_send(self.resolve(&event, true))
+                    .is_ok(),
+                None => subscriber
+                    .sender
+                    .unbounded_send(self.resolve(&event, false))
+                    .is_ok(),
+            })
+            .collect();
+
+        if revision == 0 && !stream_id.starts_with('$') {
+            let link = format!("0@{}", stream_id);
+            let link = NewEvent {
+                id: None,
+                event_type: "$>".to_string(),
+                data: link.into_bytes(),
+                metadata: Vec::new(),
+                is_json: false,
+            };
+
+            self.push("$streams", link);
+        }
+
+        revision
+    }
+
+    fn current_revision(&self, stream_id: &str) -> Option<u64> {
+        self.streams
+            .get(stream_id)
+            .and_then(|indexes| indexes.len().checked_sub(1))
+            .map(|revision| revision as u64)
+    }
+
+    // Soft deleted streams have their `$tb` moved past their last event.
+    fn truncate_before(&self, stream_id: &str) -> u64 {
+        self.stream_metadata
+            .get(stream_id)
+            .and_then(|m| m.metadata.truncate_before)
+            .unwrap_or_default()
+    }
+
+    fn check_not_tombstoned(&self, stream_id: &str) -> eventstore::Result<()> {
+        if self.tombstoned.contains(stream_id) {
+            return Err(eventstore::Error::ResourceDeleted);
+        }
+
+        Ok(())
+    }
+
+    fn set_metadata(&mut self, stream_id: &str, metadata: StreamMetadata) {
+        let revision = self
+            .stream_metadata
+            .get(stream_id)
+            .map_or(0, |m| m.revision + 1);
+
+        self.stream_metadata.insert(
+            stream_id.to_string(),
+            VersionedStreamMetadata { revision, metadata },
+        );
+    }
+
+    fn resolve(&self, event: &RecordedEvent, resolve_link_tos: bool) -> ResolvedEvent {
+        if !resolve_link_tos || event.event_type != "$>" {
+            return ResolvedEvent {
+                event: Some(event.clone()),
+                link: None,
+                commit_position: Some(event.position.commit),
+            };
+        }
+
+        let target = std::str::from_utf8(event.data.as_ref())
+            .ok()
+            .and_then(|link| link.split_once('@'))
+            .and_then(|(rev, stream)| {
+                let rev = rev.parse::<usize>().ok()?;
+                let idx = *self.streams.get(stream)?.get(rev)?;
+
+                self.log.get(idx).cloned()
+            });
+
+        ResolvedEvent {
+            event: target,
+            link: Some(event.clone()),
+            commit_position: Some(event.position.commit),
+        }
+    }
+}
+
+fn check_expected(expected: ExpectedRevision, current: Option<u64>) -> eventstore::Result<()> {
+    let accepted = match expected {
+        ExpectedRevision::Any => true,
+        ExpectedRevision::NoStream => current.is_none(),
+        ExpectedRevision::StreamExists => current.is_some(),
+        ExpectedRevision::Exact(revision) => current == Some(revision),
+    };
+
+    if !accepted {
+        return Err(eventstore::Error::WrongExpectedVersion {
+            expected,
+            current: current.map_or(CurrentRevision::NoStream, CurrentRevision::Current),
+        });
+    }
+
+    Ok(())
+}
+
+impl InMemoryBackend {
+    pub fn with_event(self, stream_id: &str, event_type: &str, data: serde_json::Value) -> Self {
+        self.append(stream_id, event_type, data);
+        self
+    }
+
+    pub fn with_projection(self, status: ProjectionStatus, query: &str) -> Self {
+        let stream_id = format!("$projections-{}", status.name);
+        let details = serde_json::json!({ "query": query });
+
+        self.append(stream_id.as_str(), "$ProjectionUpdated", details);
+        self.state.lock().unwrap().projections.push(status);
+        self
+    }
+
+    pub fn with_projection_output(
+        self,
+        name: &str,
+        state: serde_json::Value,
+        result: serde_json::Value,
+    ) -> Self {
+        self.state
+            .lock()
+            .unwrap()
+            .projection_outputs
+            .insert(name.to_string(), (state, result));
+        self
+    }
+
+    pub fn with_persistent_subscription(
+        self,
+        info: PersistentSubscriptionInfo<RevisionOrPosition>,
+    ) -> Self {
+        self.state.lock().unwrap().subscriptions.push(info);
+        self
+    }
+
+    /// Appends a `$>` link, `target` being like `0@orders-1`. Its event doesn't have to exist.
+    pub fn with_link(self, stream_id: &str, target: &[u8]) -> Self {
+        let link = NewEvent {
+            id: None,
+            event_type: "$>".to_string(),
+            data: target.to_vec(),
+            metadata: Vec::new(),
+            is_json: false,
+        };
+
+        self.state.lock().unwrap().push(stream_id, link);
+        self
+    }
+
+    pub fn with_member(self, member: MemberInfo) -> Self {
+        self.state.lock().unwrap().members.push(member);
+        self
+    }
+
+    /// Appends a JSON event and returns its revision.
+    pub fn append(&self, stream_id: &str, event_type: &str, data: serde_json::Value) -> u64 {
+        self.append_with_metadata(stream_id, event_type, data, serde_json::Value::Null)
+    }
+
+    /// Same as `append`, `Null` metadata is left empty.
+    pub fn append_with_metadata(
+        &self,
+        stream_id: &str,
+        event_type: &str,
+        data: serde_json::Value,
+        metadata: serde_json::Value,
+    ) -> u64 {
+        let event = NewEvent {
+            id: None,
+            event_type: event_type.to_string(),
+            data: serde_json::to_vec(&data).unwrap(),
+            metadata: if metadata.is_null() {
+                Vec::new()
+            } else {
+                serde_json::to_vec(&metadata).unwrap()
+            },
+            is_json: true,
+        };
+
+        self.state.lock().unwrap().push(stream_id, event)
+    }
+}
+
+pub fn projection(name: &str, status: &str) -> ProjectionStatus {
+    ProjectionStatus {
+        core_processing_time: 0,
+        version: 1,
+        epoch: 0,
+        effective_name: name.to_string(),
+        writes_in_progress: 0,
+        reads_in_progress: 0,
+        partitions_cached: 1,
+        status: status.to_string(),
+        state_reason: String::new(),
+        name: name.to_string(),
+        mode: "Continuous".to_string(),
+        position: "C:0/P:0".to_string(),
+        progress: 100.0,
+        last_checkpoint: "C:0/P:0".to_string(),
+        events_processed_after_restart: 0,
+        checkpoint_status: String::new(),
+        buffered_events: 0,
+        write_pending_events_before_checkpoint: 0,
+        write_pending_events_after_checkpoint: 0,
+    }
+}
+
+pub fn persistent_subscription(
+    stream_id: &str,
+    group_name: &str,
+) -> PersistentSubscriptionInfo<RevisionOrPosition> {
+    PersistentSubscriptionInfo {
+        event_source: stream_id.to_string(),
+        group_name: group_name.to_string(),
+        status: "Live".to_string(),
+        connections: Vec::new(),
+        settings: None,
+        stats: PersistentSubscriptionStats {
+            average_per_second: 0.0,
+            total_items: 0,
+            count_since_last_measurement: 0,
+            last_checkpointed_event_revision: None,
+            last_known_event_revision: None,
+            last_checkpointed_position: None,
+            last_known_position: None,
+            read_buffer_count: 0,
+            live_buffer_count: 0,
+            retry_buffer_count: 0,
+            total_in_flight_messages: 0,
+            outstanding_messages_count: 0,
+            parked_message_count: 0,
+        },
+    }
+}
+
+pub fn member(port: u32, state: VNodeState) -> MemberInfo {
+    MemberInfo {
+        instance_id: Uuid::from_u128(port as u128),
+        time_stamp: 0,
+        state,
+        is_alive: true,
+        http_end_point: Endpoint {
+            host: "127.0.0.1".to_string(),
+            port,
+        },
+        last_commit_position: 0,
+        writer_checkpoint: 0,
+        chaser_checkpoint: 0,
+        epoch_position: 0,
+        epoch_number: 0,
+        epoch_id: Uuid::nil(),
+        node_priority: 0,
+        is_read_only_replica: false,
+    }
+}
+
+fn select<'a, A, F>(
+    events: Vec<&'a RecordedEvent>,
+    key: F,
+    request: &ReadRequest<A>,
+) -> Vec<&'a RecordedEvent>
+where
+    A: PartialOrd + Copy,
+    F: Fn(&RecordedEvent) -> A,
+{
+    let selected: Vec<&'a RecordedEvent> = match request.direction {
+        ReadDirection::Forwards => match request.position {
+            StreamPosition::Start => events,
+            StreamPosition::End => Vec::new(),
+            StreamPosition::Position(from) => {
+                events.into_iter().filter(|e| key(e) >= from).collect()
+            }
+        },
+
+        ReadDirection::Backwards => match request.position {
+            StreamPosition::Start => events.into_iter().take(1).collect(),
+            StreamPosition::End => events.into_iter().rev().collect(),
+            StreamPosition::Position(from) => events
+                .into_iter()
+                .rev()
+                .filter(|e| key(e) <= from)
+                .collect(),
+        },
+    };
+
+    selected.into_iter().take(request.max_count).collect()
+}
+
+impl InMemoryBackend {
+    /// Simulates the cluster going down: every call fails until it's brought back online.
+    pub fn set_offline(&self, offline: bool) {
+        self.state.lock().unwrap().offline = offline;
+    }
+
+    /// Simulates a server too slow to find matches: filtered reads of `$all` stop after their
+    /// first event and are flagged as partial.
+    pub fn set_slow_scans(&self, slow: bool) {
+        self.state.lock().unwrap().slow_scans = slow;
+    }
+
+    fn reply<A, F>(&self, f: F) -> BoxFuture<'static, eventstore::Result<A>>
+    where
+        A: Send + 'static,
+        F: FnOnce(&mut State) -> eventstore::Result<A>,
+    {
+        let mut state = self.state.lock().unwrap();
+        let result = if state.offline {
+            Err(eventstore::Error::DeadlineExceeded)
+        } else {
+            f(&mut state)
+        };
+
+        futures::future::ready(result).boxed()
+    }
+
+    /// Subscription starting with the events already written for which `missed` is `true`.
+    /// `filter` only applies to `$all`.
+    fn subscribe<F>(
+        &self,
+        stream: Option<String>,
+        filter: Option<AllFilter>,
+        missed: F,
+    ) -> BoxStream<'static, eventstore::Result<ResolvedEvent>>
+    where
+        F: Fn(&RecordedEvent) -> bool,
+    {
+        let mut state = self.state.lock().unwrap();
+
+        if state.offline {
+            return futures::stream::once(async { Err(eventstore::Error::DeadlineExceeded) })
+                .boxed();
+        }
+
+        let (sender, receiver) = unbounded();
+
+        for event in state.log.iter() {
+            let on_stream = match stream.as_ref() {
+                Some(stream) => *stream == event.stream_id,
+                None => is_kept(filter.as_ref(), event),
+            };
+
+            if on_stream && missed(event) {
+                let _ = sender.unbounded_send(state.resolve(event, stream.is_some()));
+            }
+        }
+
+        state.subscribers.push(Subscriber {
+            stream,
+            filter,
+            sender,
+        });
+
+        receiver.map(Ok).boxed()
+    }
+}
+
+impl Backend for InMemoryBackend {
+    fn read_stream(
+        &self,
+        stream_name: String,
+        request: ReadRequest<u64>,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<ResolvedEvent>>> {
+        self.reply(|state| {
+            state.check_not_tombstoned(stream_name.as_str())?;
+
+            let truncate_before = state.truncate_before(stream_name.as_str());
+            let events = state
+                .streams
+                .get(stream_name.as_str())
+                .into_iter()
+                .flatten()
+                .map(|idx| &state.log[*idx])
+                .filter(|e| e.revision >= truncate_before)
+                .collect::<Vec<_>>();
+
+            if events.is_empty() {
+                return Err(eventstore::Error::ResourceNotFound);
+            }
+
+            Ok(select(events, |e| e.revision, &request)
+                .into_iter()
+                .map(|e| state.resolve(e, request.resolve_link_tos))
+                .collect())
+        })
+    }
+
+    fn read_all(
+        &self,
+        request: ReadRequest<Position>,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<ResolvedEvent>>> {
+        self.reply(|state| {
+            Ok(select(state.log.iter().collect(), |e| e.position, &request)
+                .into_iter()
+                .map(|e| state.resolve(e, request.resolve_link_tos))
+                .collect())
+        })
+    }
+
+    fn subscribe_to_stream(
+        &self,
+        stream_name: String,
+        from: StreamPosition<u64>,
+    ) -> BoxStream<'static, eventstore::Result<ResolvedEvent>> {
+        self.subscribe(Some(stream_name), None, move |event| match from {
+            StreamPosition::Start => true,
+            StreamPosition::Position(revision) => event.revision > revision,
+            StreamPosition::End => false,
+        })
+    }
+
+    fn subscribe_to_all(
+        &self,
+        from: StreamPosition<Position>,
+        filter: Option<AllFilter>,
+    ) -> BoxStream<'static, eventstore::Result<ResolvedEvent>> {
+        // Regular expressions are left to the server.
+        if let Some(AllFilter::StreamNameRegex(_) | AllFilter::EventTypeRegex(_)) = filter {
+            return futures::stream::once(async { Err(eventstore::Error::UnsupportedFeature) })
+                .boxed();
+        }
+
+        self.subscribe(None, filter, move |event| match from {
+            StreamPosition::Start => true,
+            StreamPosition::Position(position) => event.position.commit > position.commit,
+            StreamPosition::End => false,
+        })
+    }
+
+    fn read_all_filtered(
+        &self,
+        from: StreamPosition<Position>,
+        filter: Option<AllFilter>,
+        max_count: usize,
+    ) -> BoxFuture<'static, eventstore::Result<FilteredPage>> {
+        self.reply(move |state| {
+            // Regular expressions are left to the server.
+            if let Some(AllFilter::StreamNameRegex(_) | AllFilter::EventTypeRegex(_)) = filter {
+                return Err(eventstore::Error::UnsupportedFeature);
+            }
+
+            let after = match from {
+                StreamPosition::Start => None,
+                StreamPosition::Position(position) => Some(position.commit),
+                StreamPosition::End => return Ok(FilteredPage::default()),
+            };
+
+            let mut events = state
+                .log
+                .iter()
+                .filter(|e| after.map_or(true, |after| e.position.commit > after))
+                .filter(|e| is_kept(filter.as_ref(), e))
+                .take(max_count)
+                .map(|e| state.resolve(e, false))
+                .collect::<Vec<_>>();
+
+            let partial = state.slow_scans && events.len() > 1;
+            if partial {
+                events.truncate(1);
+            }
+
+            Ok(FilteredPage { events, partial })
+        })
+    }
+
+    fn append_to_stream(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+        events: Vec<NewEvent>,
+    ) -> BoxFuture<'static, eventstore::Result<WriteResult>> {
+        self.reply(move |state| {
+            state.check_not_tombstoned(stream_name.as_str())?;
+
+            let current = state.current_revision(stream_name.as_str());
+            check_expected(expected, current)?;
+
+            // Like the server, events whose ids were already written aren't written again.
+            let written = state
+                .streams
+                .get(stream_name.as_str())
+                .into_iter()
+                .flatten()
+                .map(|idx| state.log[*idx].id)
+                .collect::<HashSet<_>>();
+
+            let retried = !events.is_empty()
+                && events
+                    .iter()
+                    .all(|event| matches!(event.id, Some(id) if written.contains(&id)));
+
+            let mut revision = current.unwrap_or_default();
+            if !retried {
+                for event in events {
+                    revision = state.push(stream_name.as_str(), event);
+                }
+            }
+
+            let position = state
+                .log
+                .last()
+                .map(|e| e.position)
+                .unwrap_or_else(Position::start);
+
+            Ok(WriteResult {
+                next_expected_version: revision,
+                position,
+            })
+        })
+    }
+
+    fn get_stream_metadata(
+        &self,
+        stream_name: String,
+    ) -> BoxFuture<'static, eventstore::Result<Option<VersionedStreamMetadata>>> {
+        self.reply(move |state| {
+            state.check_not_tombstoned(stream_name.as_str())?;
+
+            Ok(state.stream_metadata.get(&stream_name).cloned())
+        })
+    }
+
+    fn set_stream_metadata(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+        metadata: StreamMetadata,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        self.reply(move |state| {
+            state.check_not_tombstoned(stream_name.as_str())?;
+
+            let current = state.stream_metadata.get(&stream_name).map(|m| m.revision);
+            check_expected(expected, current)?;
+            state.set_metadata(stream_name.as_str(), metadata);
+
+            Ok(())
+        })
+    }
+
+    fn delete_stream(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        self.reply(move |state| {
+            state.check_not_tombstoned(stream_name.as_str())?;
+
+            let current = state.current_revision(stream_name.as_str());
+            check_expected(expected, current)?;
+
+            let mut metadata = state
+                .stream_metadata
+                .get(&stream_name)
+                .map(|m| m.metadata.clone())
+                .unwrap_or_default();
+
+            metadata.truncate_before = Some(current.map_or(0, |r| r + 1));
+            state.set_metadata(stream_name.as_str(), metadata);
+
+            Ok(())
+        })
+    }
+
+    fn tombstone_stream(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        self.reply(move |state| {
+            state.check_not_tombstoned(stream_name.as_str())?;
+
+            let current = state.current_revision(stream_name.as_str());
+            check_expected(expected, current)?;
+
+            let event = NewEvent {
+                id: None,
+                event_type: "$streamDeleted".to_string(),
+                data: Vec::new(),
+                metadata: Vec::new(),
+                is_json: false,
+            };
+
+            state.push(stream_name.as_str(), event);
+            state.tombstoned.insert(stream_name);
+
+            Ok(())
+        })
+    }
+
+    fn list_projections(&self) -> BoxFuture<'static, eventstore::Result<Vec<ProjectionStatus>>> {
+        self.reply(|state| Ok(state.projections.clone()))
+    }
+
+    fn update_projection(
+        &self,
+        name: String,
+        command: ProjectionCommand,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        self.reply(move |state| {
+            let projection = state
+                .projections
+                .iter_mut()
+                .find(|p| p.name == name)
+                .ok_or(eventstore::Error::ResourceNotFound)?;
+
+            match command {
+                ProjectionCommand::Enable => projection.status = "Running".to_string(),
+                ProjectionCommand::Disable => projection.status = "Stopped".to_string(),
+                ProjectionCommand::Reset => projection.progress = 0.0,
+                ProjectionCommand::Abort => projection.status = "Aborted".to_string(),
+            }
+
+            Ok(())
+        })
+    }
+
+    fn projection_state(
+        &self,
+        name: String,
+    ) -> BoxFuture<'static, eventstore::Result<serde_json::Value>> {
+        self.reply(move |state| state.projection_output(name.as_str()).map(|(s, _)| s))
+    }
+
+    fn projection_result(
+        &self,
+        name: String,
+    ) -> BoxFuture<'static, eventstore::Result<serde_json::Value>> {
+        self.reply(move |state| state.projection_output(name.as_str()).map(|(_, r)| r))
+    }
+
+    fn list_persistent_subscriptions(
+        &self,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<PersistentSubscriptionInfo<RevisionOrPosition>>>>
+    {
+        self.reply(|state| Ok(state.subscriptions.clone()))
+    }
+
+    fn read_gossip(&self) -> BoxFuture<'static, eventstore::Result<Vec<MemberInfo>>> {
+        self.reply(|state| Ok(state.members.clone()))
+    }
+
+    fn server_version(&self) -> BoxFuture<'static, eventstore::Result<Option<ServerVersion>>> {
+        self.reply(|_| Ok(None))
+    }
+
+    fn stats(&self) -> BoxFuture<'static, eventstore::Result<Statistics>> {
+        self.reply(|_| Ok(Statistics::default()))
+    }
+}
@@ -0,0 +1,234 @@
+use eventstore::operations::{MemberInfo, ServerVersion};
+use eventstore::{
+    ExpectedRevision, PersistentSubscriptionInfo, Position, ProjectionStatus, ResolvedEvent,
+    RevisionOrPosition, StreamMetadata, StreamPosition, WriteResult,
+};
+use eventstore_extras::stats::Statistics;
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use uuid::Uuid;
+
+mod esdb;
+#[cfg(test)]
+pub mod in_memory;
+mod unavailable;
+
+pub use esdb::EsdbBackend;
+pub use unavailable::UnavailableBackend;
+
+/// Everything the views need from an EventStoreDB cluster. Returned futures own their data so
+/// they can be driven from anywhere in the runtime.
+pub trait Backend: Send + Sync {
+    fn read_stream(
+        &self,
+        stream_name: String,
+        request: ReadRequest<u64>,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<ResolvedEvent>>>;
+
+    fn read_all(
+        &self,
+        request: ReadRequest<Position>,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<ResolvedEvent>>>;
+
+    /// Events appended to `stream_name` after `from`, the ones already written first. Links are
+    /// resolved.
+    fn subscribe_to_stream(
+        &self,
+        stream_name: String,
+        from: StreamPosition<u64>,
+    ) -> BoxStream<'static, eventstore::Result<ResolvedEvent>>;
+
+    /// Events written to `$all` after `from`, the ones already written first. Only the ones
+    /// matching `filter` are kept, server-side.
+    fn subscribe_to_all(
+        &self,
+        from: StreamPosition<Position>,
+        filter: Option<AllFilter>,
+    ) -> BoxStream<'static, eventstore::Result<ResolvedEvent>>;
+
+    /// Reads `$all` forwards, after `from`, keeping the events matching `filter` server-side.
+    /// Stops after `max_count` events, once the events written so far are all read, or when the
+    /// server takes too long to find the next match.
+    fn read_all_filtered(
+        &self,
+        from: StreamPosition<Position>,
+        filter: Option<AllFilter>,
+        max_count: usize,
+    ) -> BoxFuture<'static, eventstore::Result<FilteredPage>>;
+
+    fn append_to_stream(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+        events: Vec<NewEvent>,
+    ) -> BoxFuture<'static, eventstore::Result<WriteResult>>;
+
+    /// `None` when no metadata was ever set. Fails with `ResourceDeleted` on deleted streams.
+    fn get_stream_metadata(
+        &self,
+        stream_name: String,
+    ) -> BoxFuture<'static, eventstore::Result<Option<VersionedStreamMetadata>>>;
+
+    /// Fails with `WrongExpectedVersion` when the metadata changed since it was read.
+    fn set_stream_metadata(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+        metadata: StreamMetadata,
+    ) -> BoxFuture<'static, eventstore::Result<()>>;
+
+    /// Soft delete, the stream can be recreated by appending to it again.
+    fn delete_stream(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+    ) -> BoxFuture<'static, eventstore::Result<()>>;
+
+    /// Hard delete, the stream can never be written to again.
+    fn tombstone_stream(
+        &self,
+        stream_name: String,
+        expected: ExpectedRevision,
+    ) -> BoxFuture<'static, eventstore::Result<()>>;
+
+    fn list_projections(&self) -> BoxFuture<'static, eventstore::Result<Vec<ProjectionStatus>>>;
+
+    fn update_projection(
+        &self,
+        name: String,
+        command: ProjectionCommand,
+    ) -> BoxFuture<'static, eventstore::Result<()>>;
+
+    /// State of a projection, `Null` until it processed an event.
+    fn projection_state(
+        &self,
+        name: String,
+    ) -> BoxFuture<'static, eventstore::Result<serde_json::Value>>;
+
+    /// Result of a projection, `Null` until it processed an event.
+    fn projection_result(
+        &self,
+        name: String,
+    ) -> BoxFuture<'static, eventstore::Result<serde_json::Value>>;
+
+    fn list_persistent_subscriptions(
+        &self,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<PersistentSubscriptionInfo<RevisionOrPosition>>>>;
+
+    fn read_gossip(&self) -> BoxFuture<'static, eventstore::Result<Vec<MemberInfo>>>;
+
+    fn server_version(&self) -> BoxFuture<'static, eventstore::Result<Option<ServerVersion>>>;
+
+    /// Waits for the next statistics sample.
+    fn stats(&self) -> BoxFuture<'static, eventstore::Result<Statistics>>;
+}
+
+/// Errors meaning the cluster couldn't be reached, as opposed to a request it refused.
+pub fn is_connection_error(error: &eventstore::Error) -> bool {
+    matches!(
+        error,
+        eventstore::Error::GrpcConnectionError(_)
+            | eventstore::Error::DeadlineExceeded
+            | eventstore::Error::InitializationError(_)
+    )
+}
+
+/// Event to append. The id is generated by the client when missing.
+#[derive(Clone, Debug)]
+pub struct NewEvent {
+    pub id: Option<Uuid>,
+    pub event_type: String,
+    pub data: Vec<u8>,
+    pub metadata: Vec<u8>,
+    pub is_json: bool,
+}
+
+/// Server-side filter of `$all`, prefixes are alternatives.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AllFilter {
+    ExcludeSystemEvents,
+    StreamNamePrefixes(Vec<String>),
+    StreamNameRegex(String),
+    EventTypePrefixes(Vec<String>),
+    EventTypeRegex(String),
+}
+
+/// Events of a filtered read of `$all`.
+#[derive(Debug, Default)]
+pub struct FilteredPage {
+    pub events: Vec<ResolvedEvent>,
+    /// The read stopped waiting for the server before reaching the last event of `$all`, later
+    /// events may match too.
+    pub partial: bool,
+}
+
+/// Metadata of a stream, along with the revision of the metadata stream it was read at.
+#[derive(Clone, Debug)]
+pub struct VersionedStreamMetadata {
+    pub revision: u64,
+    pub metadata: StreamMetadata,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ProjectionCommand {
+    Enable,
+    Disable,
+    Reset,
+    Abort,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ReadDirection {
+    Forwards,
+    Backwards,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReadRequest<A> {
+    pub position: StreamPosition<A>,
+    pub direction: ReadDirection,
+    pub max_count: usize,
+    pub resolve_link_tos: bool,
+}
+
+impl<A> Default for ReadRequest<A> {
+    fn default() -> Self {
+        Self {
+            position: StreamPosition::Start,
+            direction: ReadDirection::Forwards,
+            max_count: usize::MAX,
+            resolve_link_tos: false,
+        }
+    }
+}
+
+impl<A> ReadRequest<A> {
+    pub fn position(self, position: StreamPosition<A>) -> Self {
+        Self { position, ..self }
+    }
+
+    pub fn max_count(self, max_count: usize) -> Self {
+        Self { max_count, ..self }
+    }
+
+    pub fn forwards(self) -> Self {
+        Self {
+            direction: ReadDirection::Forwards,
+            ..self
+        }
+    }
+
+    pub fn backwards(self) -> Self {
+        Self {
+            direction: ReadDirection::Backwards,
+            ..self
+        }
+    }
+
+    pub fn resolve_link_tos(self) -> Self {
+        Self {
+            resolve_link_tos: true,
+            ..self
+        }
+    }
+}
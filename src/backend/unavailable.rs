@@ -0,0 +1,160 @@
+use crate::backend::{
+    AllFilter, Backend, FilteredPage, NewEvent, ProjectionCommand, ReadRequest,
+    VersionedStreamMetadata,
+};
+use eventstore::operations::{MemberInfo, ServerVersion};
+use eventstore::{
+    ExpectedRevision, PersistentSubscriptionInfo, Position, ProjectionStatus, ResolvedEvent,
+    RevisionOrPosition, StreamMetadata, StreamPosition, WriteResult,
+};
+use eventstore_extras::stats::Statistics;
+use futures::future::{self, BoxFuture};
+use futures::stream::{self, BoxStream};
+use futures::{FutureExt, StreamExt};
+
+/// Stands in for a cluster whose client couldn't be created. Every call fails with the reason,
+/// which lets the UI start and retry instead of exiting.
+pub struct UnavailableBackend {
+    reason: String,
+}
+
+impl UnavailableBackend {
+    pub fn new(reason: String) -> Self {
+        Self { reason }
+    }
+
+    fn fail<A: Send + 'static>(&self) -> BoxFuture<'static, eventstore::Result<A>> {
+        future::ready(Err(eventstore::Error::InitializationError(
+            self.reason.clone(),
+        )))
+        .boxed()
+    }
+}
+
+impl Backend for UnavailableBackend {
+    fn read_stream(
+        &self,
+        _stream_name: String,
+        _request: ReadRequest<u64>,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<ResolvedEvent>>> {
+        self.fail()
+    }
+
+    fn read_all(
+        &self,
+        _request: ReadRequest<Position>,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<ResolvedEvent>>> {
+        self.fail()
+    }
+
+    fn subscribe_to_stream(
+        &self,
+        _stream_name: String,
+        _from: StreamPosition<u64>,
+    ) -> BoxStream<'static, eventstore::Result<ResolvedEvent>> {
+        stream::once(self.fail()).boxed()
+    }
+
+    fn subscribe_to_all(
+        &self,
+        _from: StreamPosition<Position>,
+        _filter: Option<AllFilter>,
+    ) -> BoxStream<'static, eventstore::Result<ResolvedEvent>> {
+        stream::once(self.fail()).boxed()
+    }
+
+    fn read_all_filtered(
+        &self,
+        _from: StreamPosition<Position>,
+        _filter: Option<AllFilter>,
+        _max_count: usize,
+    ) -> BoxFuture<'static, eventstore::Result<FilteredPage>> {
+        self.fail()
+    }
+
+    fn append_to_stream(
+        &self,
+        _stream_name: String,
+        _expected: ExpectedRevision,
+        _events: Vec<NewEvent>,
+    ) -> BoxFuture<'static, eventstore::Result<WriteResult>> {
+        self.fail()
+    }
+
+    fn get_stream_metadata(
+        &self,
+        _stream_name: String,
+    ) -> BoxFuture<'static, eventstore::Result<Option<VersionedStreamMetadata>>> {
+        self.fail()
+    }
+
+    fn set_stream_metadata(
+        &self,
+        _stream_name: String,
+        _expected: ExpectedRevision,
+        _metadata: StreamMetadata,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        self.fail()
+    }
+
+    fn delete_stream(
+        &self,
+        _stream_name: String,
+        _expected: ExpectedRevision,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        self.fail()
+    }
+
+    fn tombstone_stream(
+        &self,
+        _stream_name: String,
+        _expected: ExpectedRevision,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        self.fail()
+    }
+
+    fn list_projections(&self) -> BoxFuture<'static, eventstore::Result<Vec<ProjectionStatus>>> {
+        self.fail()
+    }
+
+    fn update_projection(
+        &self,
+        _name: String,
+        _command: ProjectionCommand,
+    ) -> BoxFuture<'static, eventstore::Result<()>> {
+        self.fail()
+    }
+
+    fn projection_state(
+        &self,
+        _name: String,
+    ) -> BoxFuture<'static, eventstore::Result<serde_json::Value>> {
+        self.fail()
+    }
+
+    fn projection_result(
+        &self,
+        _name: String,
+    ) -> BoxFuture<'static, eventstore::Result<serde_json::Value>> {
+        self.fail()
+    }
+
+    fn list_persistent_subscriptions(
+        &self,
+    ) -> BoxFuture<'static, eventstore::Result<Vec<PersistentSubscriptionInfo<RevisionOrPosition>>>>
+    {
+        self.fail()
+    }
+
+    fn read_gossip(&self) -> BoxFuture<'static, eventstore::Result<Vec<MemberInfo>>> {
+        self.fail()
+    }
+
+    fn server_version(&self) -> BoxFuture<'static, eventstore::Result<Option<ServerVersion>>> {
+        self.fail()
+    }
+
+    fn stats(&self) -> BoxFuture<'static, eventstore::Result<Statistics>> {
+        self.fail()
+    }
+}
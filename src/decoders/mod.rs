@@ -0,0 +1,237 @@
+mod protobuf;
+#[cfg(test)]
+mod tests;
+
+pub use protobuf::DescriptorPool;
+
+use crate::config::DecodersConfig;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Bytes per line of a hex dump.
+const HEX_LINE: usize = 16;
+
+/// How the payloads of an event type are turned into text, see `[decoders]` in the
+/// configuration file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decoder {
+    Json,
+    Text,
+    Hex,
+    /// Fully qualified name of the message, looked up in the descriptor sets.
+    Protobuf(String),
+}
+
+impl Decoder {
+    /// Parses `json`, `text`, `hex` or `protobuf:<message>`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        match input.trim() {
+            "json" => Ok(Decoder::Json),
+            "text" => Ok(Decoder::Text),
+            "hex" => Ok(Decoder::Hex),
+            input => match input.strip_prefix("protobuf:") {
+                Some(message) if !message.trim().is_empty() => {
+                    Ok(Decoder::Protobuf(message.trim().to_string()))
+                }
+                _ => Err(format!(
+                    "Unknown decoder '{}', expected json, text, hex or protobuf:<message>",
+                    input
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Decoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decoder::Json => write!(f, "JSON"),
+            Decoder::Text => write!(f, "UTF-8 text"),
+            Decoder::Hex => write!(f, "hex dump"),
+            Decoder::Protobuf(message) => write!(f, "protobuf '{}'", message),
+        }
+    }
+}
+
+/// A payload made readable.
+pub struct Decoded {
+    /// `None` for an empty payload.
+    pub decoder: Option<Decoder>,
+    pub content: Content,
+    /// Why the expected decoder wasn't used.
+    pub fallback: Option<String>,
+}
+
+pub enum Content {
+    /// Protobuf messages are turned into JSON too.
+    Json(Value),
+    Text(String),
+}
+
+/// Decoders registered by event type. Payloads of other types are decoded as JSON when flagged
+/// so and valid, as text when they're printable UTF-8, and hex dumped otherwise.
+#[derive(Default)]
+pub struct Decoders {
+    event_types: BTreeMap<String, Decoder>,
+    descriptors: DescriptorPool,
+}
+
+impl Decoders {
+    /// Loads the descriptor sets, every protobuf message must be found in them.
+    pub fn load(config: &DecodersConfig) -> Result<Self, String> {
+        let mut descriptors = DescriptorPool::default();
+
+        for path in config.descriptor_sets.iter() {
+            descriptors.add_file(path)?;
+        }
+
+        Self::new(config.event_types.clone(), descriptors)
+    }
+
+    pub fn new(
+        event_types: BTreeMap<String, Decoder>,
+        descriptors: DescriptorPool,
+    ) -> Result<Self, String> {
+        for (event_type, decoder) in event_types.iter() {
+            if let Decoder::Protobuf(message) = decoder {
+                if !descriptors.contains(message.as_str()) {
+                    return Err(format!(
+                        "Protobuf message '{}' of event type '{}' isn't in any descriptor set",
+                        message, event_type
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            event_types,
+            descriptors,
+        })
+    }
+
+    /// Data of an event of type `event_type`.
+    pub fn decode(&self, event_type: &str, payload: &[u8], is_json: bool) -> Decoded {
+        let decoder = match self.event_types.get(event_type) {
+            Some(decoder) if !payload.is_empty() => decoder,
+            _ => return sniff(payload, is_json),
+        };
+
+        match self.decode_with(decoder, payload) {
+            Ok(content) => Decoded {
+                decoder: Some(decoder.clone()),
+                content,
+                fallback: None,
+            },
+            Err(e) => Decoded {
+                fallback: Some(format!("Not decoded as {}: {}", decoder, e)),
+                ..sniff(payload, false)
+            },
+        }
+    }
+
+    fn decode_with(&self, decoder: &Decoder, payload: &[u8]) -> Result<Content, String> {
+        match decoder {
+            Decoder::Json => parse_json(payload).map(Content::Json),
+            Decoder::Text => format_text(payload)
+                .map(Content::Text)
+                .ok_or_else(|| "Not valid UTF-8".to_string()),
+            Decoder::Hex => Ok(Content::Text(hex_dump(payload))),
+            Decoder::Protobuf(message) => self
+                .descriptors
+                .decode(message.as_str(), payload)
+                .map(Content::Json),
+        }
+    }
+}
+
+/// Picks a decoder from the payload itself. Metadata goes through this too.
+pub fn sniff(payload: &[u8], is_json: bool) -> Decoded {
+    if payload.is_empty() {
+        return Decoded {
+            decoder: None,
+            content: Content::Text("<EMPTY>".to_string()),
+            fallback: None,
+        };
+    }
+
+    let mut fallback = None;
+
+    if is_json {
+        match parse_json(payload) {
+            Ok(json) => {
+                return Decoded {
+                    decoder: Some(Decoder::Json),
+                    content: Content::Json(json),
+                    fallback: None,
+                }
+            }
+            Err(e) => fallback = Some(format!("Flagged as JSON but isn't valid: {}", e)),
+        }
+    }
+
+    let (decoder, content) = match format_text(payload) {
+        Some(content) => (Decoder::Text, content),
+        None => (Decoder::Hex, hex_dump(payload)),
+    };
+
+    Decoded {
+        decoder: Some(decoder),
+        content: Content::Text(content),
+        fallback,
+    }
+}
+
+fn parse_json(payload: &[u8]) -> Result<Value, String> {
+    serde_json::from_slice::<Value>(payload).map_err(|e| e.to_string())
+}
+
+/// `None` when `payload` isn't UTF-8, or holds control characters other than whitespace.
+fn format_text(payload: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(payload).ok()?;
+    let printable = text
+        .chars()
+        .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'));
+
+    if !printable {
+        return None;
+    }
+
+    Some(text.replace("\r\n", "\n").replace('\t', "    "))
+}
+
+/// Offsets, bytes and their ASCII rendering, like `hexdump -C`.
+pub fn hex_dump(payload: &[u8]) -> String {
+    let mut lines = Vec::new();
+
+    for (idx, chunk) in payload.chunks(HEX_LINE).enumerate() {
+        let mut line = format!("{:08x} ", idx * HEX_LINE);
+
+        for col in 0..HEX_LINE {
+            if col % 8 == 0 {
+                line.push(' ');
+            }
+
+            match chunk.get(col) {
+                Some(byte) => line.push_str(format!("{:02x} ", byte).as_str()),
+                None => line.push_str("   "),
+            }
+        }
+
+        let ascii = chunk
+            .iter()
+            .map(|byte| {
+                if byte.is_ascii_graphic() || *byte == b' ' {
+                    *byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+
+        line.push_str(format!(" |{}|", ascii).as_str());
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
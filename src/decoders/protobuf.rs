@@ -0,0 +1,357 @@
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorSet};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+// Guards against payloads nesting messages forever.
+pub(super) const MAX_DEPTH: usize = 64;
+
+/// Messages and enums of descriptor sets, as written by `protoc --include_imports
+/// --descriptor_set_out`, indexed by their fully qualified name like `shop.orders.OrderPlaced`.
+#[derive(Default)]
+pub struct DescriptorPool {
+    messages: HashMap<String, DescriptorProto>,
+    enums: HashMap<String, EnumDescriptorProto>,
+}
+
+impl DescriptorPool {
+    pub fn add_file(&mut self, path: &Path) -> Result<(), String> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| format!("Can't read descriptor set '{}': {}", path.display(), e))?;
+
+        let set = FileDescriptorSet::decode(bytes.as_slice())
+            .map_err(|e| format!("Invalid descriptor set '{}': {}", path.display(), e))?;
+
+        self.add_set(set);
+
+        Ok(())
+    }
+
+    pub fn add_set(&mut self, set: FileDescriptorSet) {
+        for file in set.file {
+            let scope = file.package().to_string();
+
+            for message in file.message_type {
+                self.add_message(scope.as_str(), message);
+            }
+
+            for descriptor in file.enum_type {
+                self.enums
+                    .insert(qualify(scope.as_str(), descriptor.name()), descriptor);
+            }
+        }
+    }
+
+    fn add_message(&mut self, scope: &str, mut message: DescriptorProto) {
+        let name = qualify(scope, message.name());
+
+        for nested in std::mem::take(&mut message.nested_type) {
+            self.add_message(name.as_str(), nested);
+        }
+
+        for descriptor in std::mem::take(&mut message.enum_type) {
+            self.enums
+                .insert(qualify(name.as_str(), descriptor.name()), descriptor);
+        }
+
+        self.messages.insert(name, message);
+    }
+
+    pub fn contains(&self, message_name: &str) -> bool {
+        self.messages.contains_key(message_name)
+    }
+
+    /// Decodes `payload` as a `message_name` message, following the JSON mapping of protobuf:
+    /// bytes are base64 encoded and enums are named. Unknown fields are keyed by their number.
+    pub fn decode(&self, message_name: &str, payload: &[u8]) -> Result<Value, String> {
+        self.decode_message(message_name, payload, 0)
+    }
+
+    fn decode_message(
+        &self,
+        message_name: &str,
+        payload: &[u8],
+        depth: usize,
+    ) -> Result<Value, String> {
+        if depth > MAX_DEPTH {
+            return Err("Messages are nested too deeply".to_string());
+        }
+
+        let message = self
+            .messages
+            .get(message_name)
+            .ok_or_else(|| format!("Unknown message type '{}'", message_name))?;
+
+        let mut reader = Reader::new(payload);
+        let mut object = Map::new();
+
+        while !reader.is_empty() {
+            let key = reader.varint()?;
+            let number = (key >> 3) as i32;
+            let wire_type = (key & 7) as u8;
+
+            let field = match message.field.iter().find(|f| f.number() == number) {
+                Some(field) => field,
+                None => {
+                    let value = reader.unknown(wire_type)?;
+                    push(&mut object, number.to_string(), value, true);
+                    continue;
+                }
+            };
+
+            let repeated = field.label() == Label::Repeated;
+
+            // Packed repeated scalars.
+            if repeated && wire_type == 2 && is_packable(field.r#type()) {
+                let mut packed = Reader::new(reader.bytes()?);
+
+                while !packed.is_empty() {
+                    let value =
+                        self.scalar(field, &mut packed, scalar_wire_type(field.r#type()))?;
+                    push(&mut object, field.name().to_string(), value, true);
+                }
+
+                continue;
+            }
+
+            let value = match field.r#type() {
+                Type::Message => {
+                    let bytes = reader.bytes()?;
+                    self.decode_message(type_name(field), bytes, depth + 1)?
+                }
+                Type::Group => return Err("Groups aren't supported".to_string()),
+                _ => self.scalar(field, &mut reader, wire_type)?,
+            };
+
+            push(&mut object, field.name().to_string(), value, repeated);
+        }
+
+        if is_map_entry(message) {
+            return Ok(Value::Object(object));
+        }
+
+        // Map fields are repeated entries on the wire, they're displayed as objects.
+        for field in message.field.iter() {
+            match self.messages.get(type_name(field)) {
+                Some(entry) if field.r#type() == Type::Message && is_map_entry(entry) => {}
+                _ => continue,
+            }
+
+            if let Some(Value::Array(entries)) = object.remove(field.name()) {
+                let map = entries
+                    .into_iter()
+                    .map(|mut entry| {
+                        let key = match entry.get_mut("key").map(Value::take) {
+                            Some(Value::String(key)) => key,
+                            Some(key) => key.to_string(),
+                            None => String::new(),
+                        };
+                        let value = entry.get_mut("value").map(Value::take).unwrap_or_default();
+
+                        (key, value)
+                    })
+                    .collect();
+
+                object.insert(field.name().to_string(), Value::Object(map));
+            }
+        }
+
+        Ok(Value::Object(object))
+    }
+
+    fn scalar(
+        &self,
+        field: &FieldDescriptorProto,
+        reader: &mut Reader,
+        wire_type: u8,
+    ) -> Result<Value, String> {
+        let expected = scalar_wire_type(field.r#type());
+
+        if wire_type != expected {
+            return Err(format!(
+                "Field '{}' has wire type {}, expected {}",
+                field.name(),
+                wire_type,
+                expected
+            ));
+        }
+
+        let value = match field.r#type() {
+            Type::Double => float(f64::from_bits(reader.fixed64()?)),
+            Type::Float => float(f32::from_bits(reader.fixed32()?) as f64),
+            Type::Int64 => Value::from(reader.varint()? as i64),
+            Type::Uint64 => Value::from(reader.varint()?),
+            Type::Int32 => Value::from(reader.varint()? as i32),
+            Type::Fixed64 => Value::from(reader.fixed64()?),
+            Type::Fixed32 => Value::from(reader.fixed32()?),
+            Type::Bool => Value::Bool(reader.varint()? != 0),
+            Type::String => {
+                let bytes = reader.bytes()?;
+                let value = std::str::from_utf8(bytes)
+                    .map_err(|_| format!("Field '{}' isn't valid UTF-8", field.name()))?;
+
+                Value::String(value.to_string())
+            }
+            Type::Bytes => Value::String(base64::encode(reader.bytes()?)),
+            Type::Uint32 => Value::from(reader.varint()? as u32),
+            Type::Enum => {
+                let number = reader.varint()? as i32;
+                let name = self
+                    .enums
+                    .get(type_name(field))
+                    .and_then(|e| e.value.iter().find(|v| v.number() == number))
+                    .map(|v| v.name().to_string());
+
+                match name {
+                    Some(name) => Value::String(name),
+                    None => Value::from(number),
+                }
+            }
+            Type::Sfixed32 => Value::from(reader.fixed32()? as i32),
+            Type::Sfixed64 => Value::from(reader.fixed64()? as i64),
+            Type::Sint32 => {
+                let value = reader.varint()? as u32;
+                Value::from((value >> 1) as i32 ^ -((value & 1) as i32))
+            }
+            Type::Sint64 => {
+                let value = reader.varint()?;
+                Value::from((value >> 1) as i64 ^ -((value & 1) as i64))
+            }
+            Type::Message | Type::Group => unreachable!("not a scalar"),
+        };
+
+        Ok(value)
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+fn is_map_entry(message: &DescriptorProto) -> bool {
+    message
+        .options
+        .as_ref()
+        .map(|options| options.map_entry())
+        .unwrap_or_default()
+}
+
+/// Type names of fields are fully qualified with a leading dot.
+fn type_name(field: &FieldDescriptorProto) -> &str {
+    field.type_name().trim_start_matches('.')
+}
+
+fn is_packable(kind: Type) -> bool {
+    !matches!(
+        kind,
+        Type::String | Type::Bytes | Type::Message | Type::Group
+    )
+}
+
+fn scalar_wire_type(kind: Type) -> u8 {
+    match kind {
+        Type::Double | Type::Fixed64 | Type::Sfixed64 => 1,
+        Type::String | Type::Bytes | Type::Message => 2,
+        Type::Group => 3,
+        Type::Float | Type::Fixed32 | Type::Sfixed32 => 5,
+        _ => 0,
+    }
+}
+
+/// NaN and infinities have no JSON number.
+fn float(value: f64) -> Value {
+    Number::from_f64(value)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+fn push(object: &mut Map<String, Value>, name: String, value: Value, repeated: bool) {
+    if !repeated {
+        object.insert(name, value);
+        return;
+    }
+
+    match object
+        .entry(name)
+        .or_insert_with(|| Value::Array(Vec::new()))
+    {
+        Value::Array(values) => values.push(value),
+        other => *other = Value::Array(vec![other.take(), value]),
+    }
+}
+
+struct Reader<'a> {
+    payload: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(payload: &'a [u8]) -> Self {
+        Self { payload }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.payload.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.payload.len() {
+            return Err("Truncated payload".to_string());
+        }
+
+        let (taken, rest) = self.payload.split_at(len);
+        self.payload = rest;
+
+        Ok(taken)
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err("Invalid varint".to_string())
+    }
+
+    fn fixed32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn fixed64(&mut self) -> Result<u64, String> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], String> {
+        let len = self.varint()?;
+
+        self.take(len as usize)
+    }
+
+    /// Field missing from the descriptor, length-delimited ones are shown in base64.
+    fn unknown(&mut self, wire_type: u8) -> Result<Value, String> {
+        match wire_type {
+            0 => Ok(Value::from(self.varint()?)),
+            1 => Ok(Value::from(self.fixed64()?)),
+            2 => Ok(Value::String(base64::encode(self.bytes()?))),
+            5 => Ok(Value::from(self.fixed32()?)),
+            _ => Err(format!("Unsupported wire type {}", wire_type)),
+        }
+    }
+}
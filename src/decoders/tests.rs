@@ -0,0 +1,382 @@
+use super::protobuf::MAX_DEPTH;
+use super::*;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+    FileDescriptorProto, FileDescriptorSet, MessageOptions,
+};
+use serde_json::json;
+
+fn field(name: &str, number: i32, kind: Type, label: Label) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_string()),
+        number: Some(number),
+        label: Some(label as i32),
+        r#type: Some(kind as i32),
+        ..Default::default()
+    }
+}
+
+fn typed_field(name: &str, number: i32, kind: Type, type_name: &str) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        type_name: Some(type_name.to_string()),
+        ..field(name, number, kind, Label::Optional)
+    }
+}
+
+fn message(name: &str, field: Vec<FieldDescriptorProto>) -> DescriptorProto {
+    DescriptorProto {
+        name: Some(name.to_string()),
+        field,
+        ..Default::default()
+    }
+}
+
+/// `test.Scalars`, with a field of every scalar type, `test.Node` holding another node, and
+/// `test.Status`.
+fn pool() -> DescriptorPool {
+    let labels = DescriptorProto {
+        options: Some(MessageOptions {
+            map_entry: Some(true),
+            ..Default::default()
+        }),
+        ..message(
+            "LabelsEntry",
+            vec![
+                field("key", 1, Type::String, Label::Optional),
+                field("value", 2, Type::Int32, Label::Optional),
+            ],
+        )
+    };
+
+    let scalars = DescriptorProto {
+        nested_type: vec![labels],
+        ..message(
+            "Scalars",
+            vec![
+                field("name", 1, Type::String, Label::Optional),
+                field("int32", 2, Type::Int32, Label::Optional),
+                field("sint32", 3, Type::Sint32, Label::Optional),
+                field("sint64", 4, Type::Sint64, Label::Optional),
+                field("fixed32", 5, Type::Fixed32, Label::Optional),
+                field("fixed64", 6, Type::Fixed64, Label::Optional),
+                field("sfixed32", 7, Type::Sfixed32, Label::Optional),
+                field("double", 8, Type::Double, Label::Optional),
+                field("bytes", 9, Type::Bytes, Label::Optional),
+                field("flag", 10, Type::Bool, Label::Optional),
+                field("values", 11, Type::Int32, Label::Repeated),
+                field("tags", 12, Type::String, Label::Repeated),
+                typed_field("status", 13, Type::Enum, ".test.Status"),
+                FieldDescriptorProto {
+                    label: Some(Label::Repeated as i32),
+                    ..typed_field("labels", 14, Type::Message, ".test.Scalars.LabelsEntry")
+                },
+            ],
+        )
+    };
+
+    let node = message(
+        "Node",
+        vec![
+            typed_field("child", 1, Type::Message, ".test.Node"),
+            field("value", 2, Type::Int32, Label::Optional),
+        ],
+    );
+
+    let status = EnumDescriptorProto {
+        name: Some("Status".to_string()),
+        value: ["PENDING", "PAID"]
+            .iter()
+            .enumerate()
+            .map(|(number, name)| EnumValueDescriptorProto {
+                name: Some(name.to_string()),
+                number: Some(number as i32),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let mut pool = DescriptorPool::default();
+    pool.add_set(FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("test.proto".to_string()),
+            package: Some("test".to_string()),
+            message_type: vec![scalars, node],
+            enum_type: vec![status],
+            ..Default::default()
+        }],
+    });
+
+    pool
+}
+
+fn varint(mut value: u64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+    bytes
+}
+
+fn key(number: u64, wire_type: u64) -> Vec<u8> {
+    varint(number << 3 | wire_type)
+}
+
+fn varint_field(number: u64, value: u64) -> Vec<u8> {
+    [key(number, 0), varint(value)].concat()
+}
+
+fn len_field(number: u64, payload: &[u8]) -> Vec<u8> {
+    [
+        key(number, 2),
+        varint(payload.len() as u64),
+        payload.to_vec(),
+    ]
+    .concat()
+}
+
+#[test]
+fn nested_messages_are_decoded() {
+    let child = [varint_field(2, 7)].concat();
+    let payload = [len_field(1, &child), varint_field(2, 1)].concat();
+
+    assert_eq!(
+        pool().decode("test.Node", &payload),
+        Ok(json!({ "child": { "value": 7 }, "value": 1 }))
+    );
+}
+
+#[test]
+fn nesting_is_limited() {
+    let nested = |depth: usize| (0..depth).fold(Vec::new(), |inner, _| len_field(1, &inner));
+
+    assert!(pool().decode("test.Node", &nested(MAX_DEPTH)).is_ok());
+    assert_eq!(
+        pool().decode("test.Node", &nested(MAX_DEPTH + 1)),
+        Err("Messages are nested too deeply".to_string())
+    );
+}
+
+#[test]
+fn repeated_fields_are_decoded_packed_or_not() {
+    let packed = [varint(1), varint(2), varint(300)].concat();
+    let payload = [
+        len_field(11, &packed),
+        varint_field(11, 4),
+        len_field(12, b"a"),
+        len_field(12, b"b"),
+    ]
+    .concat();
+
+    assert_eq!(
+        pool().decode("test.Scalars", &payload),
+        Ok(json!({ "values": [1, 2, 300, 4], "tags": ["a", "b"] }))
+    );
+}
+
+#[test]
+fn signed_and_fixed_width_scalars_are_decoded() {
+    let payload = [
+        // Negative int32 are sign extended to 10 bytes.
+        varint_field(2, -5i64 as u64),
+        varint_field(3, 5),
+        varint_field(4, 1),
+        [key(5, 5), 0xdead_beefu32.to_le_bytes().to_vec()].concat(),
+        [key(6, 1), u64::MAX.to_le_bytes().to_vec()].concat(),
+        [key(7, 5), (-2i32).to_le_bytes().to_vec()].concat(),
+        [key(8, 1), 1.5f64.to_le_bytes().to_vec()].concat(),
+        varint_field(10, 1),
+    ]
+    .concat();
+
+    assert_eq!(
+        pool().decode("test.Scalars", &payload),
+        Ok(json!({
+            "int32": -5,
+            "sint32": -3,
+            "sint64": -1,
+            "fixed32": 0xdead_beefu32,
+            "fixed64": u64::MAX,
+            "sfixed32": -2,
+            "double": 1.5,
+            "flag": true,
+        }))
+    );
+}
+
+#[test]
+fn bytes_enums_and_maps_follow_the_json_mapping() {
+    let entry = [len_field(1, b"x"), varint_field(2, 3)].concat();
+    let payload = [
+        len_field(9, b"hi"),
+        varint_field(13, 1),
+        len_field(14, &entry),
+    ]
+    .concat();
+
+    assert_eq!(
+        pool().decode("test.Scalars", &payload),
+        Ok(json!({ "bytes": "aGk=", "status": "PAID", "labels": { "x": 3 } }))
+    );
+
+    // Numbers missing from the enum are kept as is.
+    assert_eq!(
+        pool().decode("test.Scalars", &varint_field(13, 9)),
+        Ok(json!({ "status": 9 }))
+    );
+}
+
+#[test]
+fn unknown_fields_are_keyed_by_number() {
+    let payload = [
+        varint_field(99, 5),
+        len_field(98, b"hi"),
+        [key(97, 5), 1u32.to_le_bytes().to_vec()].concat(),
+        varint_field(99, 6),
+    ]
+    .concat();
+
+    assert_eq!(
+        pool().decode("test.Scalars", &payload),
+        Ok(json!({ "99": [5, 6], "98": ["aGk="], "97": [1] }))
+    );
+
+    assert_eq!(
+        pool().decode("test.Scalars", &key(96, 3)),
+        Err("Unsupported wire type 3".to_string())
+    );
+}
+
+#[test]
+fn truncated_payloads_are_refused() {
+    let truncated = |payload: &[u8]| pool().decode("test.Scalars", payload);
+    let error = Err("Truncated payload".to_string());
+
+    assert_eq!(truncated(&[0x0a, 0x09, b'a']), error);
+    assert_eq!(truncated(&[0x10, 0x80]), error);
+    assert_eq!(truncated(&[0x2d, 0x01, 0x02]), error);
+    assert_eq!(truncated(&[0x31, 0x01]), error);
+    assert_eq!(truncated(&[0x5a, 0x02, 0x01]), error);
+    assert_eq!(truncated(&[0x10; 11]), error);
+    assert_eq!(truncated(&[0x90; 11]), Err("Invalid varint".to_string()));
+}
+
+#[test]
+fn mismatched_wire_types_and_types_are_refused() {
+    assert_eq!(
+        pool().decode("test.Scalars", &len_field(2, b"x")),
+        Err("Field 'int32' has wire type 2, expected 0".to_string())
+    );
+    assert_eq!(
+        pool().decode("test.Scalars", &len_field(1, &[0xff])),
+        Err("Field 'name' isn't valid UTF-8".to_string())
+    );
+    assert_eq!(
+        pool().decode("test.Missing", &[]),
+        Err("Unknown message type 'test.Missing'".to_string())
+    );
+}
+
+#[test]
+fn decoders_are_parsed() {
+    assert_eq!(Decoder::parse(" json "), Ok(Decoder::Json));
+    assert_eq!(Decoder::parse("hex"), Ok(Decoder::Hex));
+    assert_eq!(
+        Decoder::parse("protobuf: shop.Paid"),
+        Ok(Decoder::Protobuf("shop.Paid".to_string()))
+    );
+    assert!(Decoder::parse("protobuf:").is_err());
+    assert!(Decoder::parse("xml").is_err());
+}
+
+#[test]
+fn protobuf_messages_must_be_known() {
+    let event_types = BTreeMap::from([(
+        "Paid".to_string(),
+        Decoder::Protobuf("test.Missing".to_string()),
+    )]);
+
+    let error = Decoders::new(event_types, pool()).err().unwrap();
+    assert!(error.contains("isn't in any descriptor set"));
+}
+
+fn text(decoded: &Decoded) -> &str {
+    match &decoded.content {
+        Content::Text(text) => text.as_str(),
+        Content::Json(_) => panic!("decoded as JSON"),
+    }
+}
+
+#[test]
+fn payloads_are_sniffed() {
+    let empty = sniff(b"", true);
+    assert_eq!(empty.decoder, None);
+    assert_eq!(text(&empty), "<EMPTY>");
+
+    let json = sniff(b"{ \"id\": 1 }", true);
+    assert_eq!(json.decoder, Some(Decoder::Json));
+    assert!(matches!(json.content, Content::Json(value) if value == json!({ "id": 1 })));
+
+    // Not flagged as JSON, it's text.
+    assert_eq!(sniff(b"{ \"id\": 1 }", false).decoder, Some(Decoder::Text));
+
+    let broken = sniff(b"{ \"id\": ", true);
+    assert_eq!(broken.decoder, Some(Decoder::Text));
+    assert!(broken
+        .fallback
+        .unwrap()
+        .starts_with("Flagged as JSON but isn't valid"));
+
+    let text_payload = sniff(b"a\r\n\tb", false);
+    assert_eq!(text(&text_payload), "a\n    b");
+
+    assert_eq!(sniff(b"a\x00b", false).decoder, Some(Decoder::Hex));
+    assert_eq!(sniff(&[0xff, 0xfe], false).decoder, Some(Decoder::Hex));
+}
+
+#[test]
+fn hex_dumps_show_offsets_bytes_and_ascii() {
+    assert_eq!(
+        hex_dump(b"%PDF-1.4\n\x00\x01abcdefgh"),
+        "00000000  25 50 44 46 2d 31 2e 34  0a 00 01 61 62 63 64 65  |%PDF-1.4...abcde|\n\
+         00000010  66 67 68                                          |fgh|"
+    );
+}
+
+#[test]
+fn registered_decoders_fall_back_on_unreadable_payloads() {
+    let event_types = BTreeMap::from([
+        (
+            "Paid".to_string(),
+            Decoder::Protobuf("test.Node".to_string()),
+        ),
+        ("Receipt".to_string(), Decoder::Hex),
+    ]);
+    let decoders = Decoders::new(event_types, pool()).unwrap();
+
+    let paid = decoders.decode("Paid", &varint_field(2, 7), false);
+    assert_eq!(
+        paid.decoder,
+        Some(Decoder::Protobuf("test.Node".to_string()))
+    );
+    assert!(matches!(paid.content, Content::Json(value) if value == json!({ "value": 7 })));
+
+    let broken = decoders.decode("Paid", &[0x0a, 0x09, 0xff], false);
+    assert_eq!(broken.decoder, Some(Decoder::Hex));
+    assert_eq!(
+        broken.fallback.as_deref(),
+        Some("Not decoded as protobuf 'test.Node': Truncated payload")
+    );
+
+    assert_eq!(
+        decoders.decode("Receipt", b"ok", true).decoder,
+        Some(Decoder::Hex)
+    );
+    // Empty payloads aren't handed to the decoder.
+    assert_eq!(decoders.decode("Paid", b"", false).decoder, None);
+}
@@ -1,10 +1,17 @@
+mod backend;
+mod config;
+mod decoders;
 mod models;
+mod transfer;
 mod views;
 
 #[macro_use]
 extern crate log;
 
-use crate::views::{Context, Request, View, B};
+use crate::backend::EsdbBackend;
+use crate::config::{Config, Connection};
+use crate::transfer::{import_events, ImportOptions, ImportProgress};
+use crate::views::{Context, Request, TermBackend, View, B};
 use crossterm::{
     event::Event,
     execute,
@@ -13,10 +20,13 @@ use crossterm::{
     },
 };
 use eventstore::ClientSettings;
+use futures::StreamExt;
 use log::LevelFilter;
 use log4rs::config::{Appender, Logger, Root};
 use std::{
     io,
+    path::PathBuf,
+    sync::Arc,
     time::{Duration, Instant},
 };
 use structopt::StructOpt;
@@ -24,37 +34,98 @@ use tui::{backend::CrosstermBackend, Terminal};
 
 #[derive(StructOpt, Debug)]
 struct Args {
-    #[structopt(short = "c",  long = "connection-string", default_value = "esdb://localhost:2113", parse(try_from_str = parse_connection_string))]
-    conn_setts: eventstore::ClientSettings,
+    #[structopt(short = "c",  long = "connection-string", parse(try_from_str = parse_connection_string))]
+    connection: Option<Connection>,
+
+    /// Connection profile to use, as defined in the configuration file.
+    #[structopt(short = "p", long = "profile")]
+    profile: Option<String>,
+
+    /// Configuration file, defaults to `$XDG_CONFIG_HOME/esdb-tui/config.toml`.
+    #[structopt(long = "config", parse(from_os_str))]
+    config: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Appends the events of an NDJSON export to a stream, without starting the UI.
+    Import {
+        /// File written by an export.
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+
+        /// Stream the events are appended to.
+        #[structopt(short = "s", long = "stream")]
+        stream: String,
+
+        /// Only checks the file, nothing is appended.
+        #[structopt(long = "dry-run")]
+        dry_run: bool,
+
+        /// Events appended per request.
+        #[structopt(long = "batch-size", default_value = "100")]
+        batch_size: usize,
+    },
 }
 
 fn parse_connection_string(
     input: &str,
-) -> Result<ClientSettings, eventstore::ClientSettingsParseError> {
-    ClientSettings::parse_str(input)
+) -> Result<Connection, eventstore::ClientSettingsParseError> {
+    ClientSettings::parse_str(input)?;
+
+    Ok(Connection::ad_hoc(input))
 }
 
 fn main() -> Result<(), io::Error> {
     let args = Args::from_args();
-
-    let file = log4rs::append::file::FileAppender::builder().build("esdb.log")?;
-    let config = log4rs::config::Config::builder()
+    let config = Config::load(args.config.as_deref())?;
+    let connection = match args.connection {
+        Some(connection) => connection,
+        None => config.connection(args.profile.as_deref())?,
+    };
+
+    let file = log4rs::append::file::FileAppender::builder().build(&config.log.file)?;
+    let log_config = log4rs::config::Config::builder()
         .appender(Appender::builder().build("file", Box::new(file)))
-        .logger(Logger::builder().build("esdb-tui", LevelFilter::Debug))
+        .logger(Logger::builder().build("esdb-tui", config.log.level))
         .build(Root::builder().appender("file").build(LevelFilter::Warn))
         .unwrap();
 
-    let _ = log4rs::init_config(config).unwrap();
+    let _ = log4rs::init_config(log_config).unwrap();
+
+    if let Some(Command::Import {
+        file,
+        stream,
+        dry_run,
+        batch_size,
+    }) = args.command
+    {
+        let options = ImportOptions {
+            stream_name: stream,
+            batch_size,
+            dry_run,
+        };
+
+        if let Err(e) = import(connection, file, options) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, SetTitle("esdb-tui"))?;
-    let backend = CrosstermBackend::new(stdout);
+    let backend = TermBackend::Crossterm(CrosstermBackend::new(stdout));
     let mut terminal = Terminal::new(backend)?;
-    let res = run_app(&mut terminal, args.conn_setts);
+    let res = run_app(&mut terminal, connection, config);
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen,)?;
+    execute!(io::stdout(), LeaveAlternateScreen,)?;
     terminal.show_cursor()?;
 
     if let Err(err) = res {
@@ -64,12 +135,49 @@ fn main() -> Result<(), io::Error> {
     Ok(())
 }
 
-fn run_app(terminal: &mut Terminal<B>, setts: ClientSettings) -> io::Result<()> {
-    let tick_rate = Duration::from_millis(250);
-    let refresh_rate = Duration::from_secs(2);
+/// Runs an import from the command line, its progress is printed as it goes.
+fn import(connection: Connection, file: PathBuf, options: ImportOptions) -> Result<(), String> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let setts = ClientSettings::parse_str(connection.connection_string.as_str())
+        .map_err(|e| e.to_string())?;
+
+    // The client spawns its connection tasks, so it has to be created within the runtime.
+    let backend = runtime
+        .block_on(async move { EsdbBackend::new(setts) })
+        .map_err(|e| e.to_string())?;
+
+    let stream_name = options.stream_name.clone();
+    let dry_run = options.dry_run;
+    let mut progress = Box::pin(import_events(Arc::new(backend), file, options));
+
+    runtime.block_on(async move {
+        while let Some(progress) = progress.next().await {
+            match progress {
+                ImportProgress::Validated(count) => println!("Checked {} events", count),
+                ImportProgress::Imported(count) => println!("Appended {} events", count),
+                ImportProgress::Done(count) if dry_run => {
+                    println!("Dry run: {} valid events, nothing was written", count)
+                }
+                ImportProgress::Done(count) => {
+                    println!("Imported {} events into '{}'", count, stream_name)
+                }
+                ImportProgress::Failed(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    })
+}
+
+fn run_app(terminal: &mut Terminal<B>, connection: Connection, config: Config) -> io::Result<()> {
     let mut last_tick = Instant::now();
     let mut last_refresh = Instant::now();
-    let mut ctx = Context::new(setts)?;
+    let mut ctx = Context::new(connection, config)?;
+    let tick_rate = ctx.tick_rate();
 
     ctx.init();
 
@@ -95,11 +203,14 @@ fn run_app(terminal: &mut Terminal<B>, setts: ClientSettings) -> io::Result<()>
 
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
+            ctx.on_tick();
         }
 
-        if last_refresh.elapsed() >= refresh_rate {
+        if last_refresh.elapsed() >= ctx.refresh_rate() {
             last_refresh = Instant::now();
-            ctx.refresh();
+            ctx.refresh_if_idle();
         }
+
+        ctx.process_responses();
     }
 }
@@ -0,0 +1,124 @@
+use crate::backend::NewEvent;
+use eventstore::RecordedEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// One line of an NDJSON export, read back by imports. Payloads that are valid JSON are embedded
+/// as JSON, anything else is base64 encoded in the `_base64` field instead. Empty payloads are
+/// left out.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportedEvent {
+    pub id: String,
+    pub stream: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub revision: u64,
+    pub position: ExportedPosition,
+    /// RFC 3339, in UTC.
+    pub created: String,
+    pub content_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_base64: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_base64: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportedPosition {
+    pub commit: u64,
+    pub prepare: u64,
+}
+
+impl From<&RecordedEvent> for ExportedEvent {
+    fn from(event: &RecordedEvent) -> Self {
+        let (data, data_base64) = encode_payload(event.data.as_ref(), event.is_json);
+        let (metadata, metadata_base64) = encode_payload(event.custom_metadata.as_ref(), true);
+
+        let content_type = if event.is_json {
+            "application/json"
+        } else {
+            "application/octet-stream"
+        };
+
+        Self {
+            id: event.id.to_string(),
+            stream: event.stream_id.clone(),
+            event_type: event.event_type.clone(),
+            revision: event.revision,
+            position: ExportedPosition {
+                commit: event.position.commit,
+                prepare: event.position.prepare,
+            },
+            created: event.created.to_rfc3339(),
+            content_type: content_type.to_string(),
+            metadata,
+            metadata_base64,
+            data,
+            data_base64,
+        }
+    }
+}
+
+impl ExportedEvent {
+    /// Same id, type and payloads, the rest is decided by the stream it's appended to.
+    pub fn into_new_event(self) -> Result<NewEvent, String> {
+        let id = Uuid::parse_str(self.id.as_str())
+            .map_err(|_| format!("Invalid event id '{}'", self.id))?;
+
+        if self.event_type.is_empty() {
+            return Err("The event type is empty".to_string());
+        }
+
+        let data = decode_payload("data", self.data, self.data_base64)?;
+        let metadata = decode_payload("metadata", self.metadata, self.metadata_base64)?;
+
+        // There's no empty JSON document.
+        let is_json = self.content_type == "application/json" && !data.is_empty();
+
+        if is_json && serde_json::from_slice::<Value>(data.as_slice()).is_err() {
+            return Err(
+                "The data isn't JSON, yet its content type is application/json".to_string(),
+            );
+        }
+
+        Ok(NewEvent {
+            id: Some(id),
+            event_type: self.event_type,
+            data,
+            metadata,
+            is_json,
+        })
+    }
+}
+
+fn decode_payload(
+    name: &str,
+    json: Option<Value>,
+    encoded: Option<String>,
+) -> Result<Vec<u8>, String> {
+    match (json, encoded) {
+        (Some(_), Some(_)) => Err(format!("Both {} and {}_base64 are set", name, name)),
+        (Some(json), None) => serde_json::to_vec(&json).map_err(|e| e.to_string()),
+        (None, Some(encoded)) => {
+            base64::decode(encoded.as_str()).map_err(|e| format!("Invalid {}_base64: {}", name, e))
+        }
+        (None, None) => Ok(Vec::new()),
+    }
+}
+
+/// JSON when `is_json` and the payload parses, base64 otherwise.
+fn encode_payload(payload: &[u8], is_json: bool) -> (Option<Value>, Option<String>) {
+    if payload.is_empty() {
+        return (None, None);
+    }
+
+    match serde_json::from_slice::<Value>(payload) {
+        Ok(json) if is_json => (Some(json), None),
+        _ => (None, Some(base64::encode(payload))),
+    }
+}
@@ -1,8 +1,10 @@
+mod exported_event;
 mod monitoring;
 mod persistent_subscriptions;
 mod projections;
 mod stats;
 
+pub use exported_event::*;
 pub use monitoring::*;
 pub use persistent_subscriptions::*;
 pub use projections::*;
@@ -15,8 +15,8 @@ pub struct Projection {
     pub checkpoint_status: String,
     pub position: String,
     pub last_checkpoint: String,
-    pub result: String,
-    pub state: String,
+    pub result: Option<serde_json::Value>,
+    pub state: Option<serde_json::Value>,
     pub query: String,
     pub buffered_events: i64,
     pub status: String,
@@ -77,12 +77,8 @@ impl Projections {
             .map(|(_, p)| p)
     }
 
-    pub fn by_idx_mut(&mut self, idx: usize) -> Option<&mut Projection> {
-        self.inner
-            .values_mut()
-            .enumerate()
-            .find(|(i, _)| *i == idx)
-            .map(|(_, p)| p)
+    pub fn by_name_mut(&mut self, name: &str) -> Option<&mut Projection> {
+        self.inner.get_mut(name)
     }
 
     pub fn count(&self) -> usize {
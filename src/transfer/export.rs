@@ -0,0 +1,199 @@
+use crate::backend::{Backend, ReadRequest};
+use crate::models::ExportedEvent;
+use chrono::{DateTime, Utc};
+use eventstore::{Position, RecordedEvent, StreamPosition};
+use futures::Stream;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// Events read per request, the progress is reported after each of them.
+const BATCH_SIZE: usize = 500;
+
+/// Events to export. Bounds are inclusive, missing ones are open.
+#[derive(Clone, Debug)]
+pub enum ExportRange {
+    Revisions(Option<u64>, Option<u64>),
+    /// Compared by commit position, for `$all`.
+    Positions(Option<Position>, Option<Position>),
+    Time(Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+}
+
+enum Bound {
+    Before,
+    Within,
+    After,
+}
+
+impl ExportRange {
+    fn locate(&self, event: &RecordedEvent) -> Bound {
+        fn locate<A: PartialOrd>(value: A, from: Option<A>, to: Option<A>) -> Bound {
+            if matches!(from, Some(from) if value < from) {
+                Bound::Before
+            } else if matches!(to, Some(to) if value > to) {
+                Bound::After
+            } else {
+                Bound::Within
+            }
+        }
+
+        match self {
+            ExportRange::Revisions(from, to) => locate(event.revision, *from, *to),
+            ExportRange::Positions(from, to) => locate(
+                event.position.commit,
+                from.map(|p| p.commit),
+                to.map(|p| p.commit),
+            ),
+            ExportRange::Time(from, to) => locate(event.created, *from, *to),
+        }
+    }
+
+    /// `true` when `from` is past `to`.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            ExportRange::Revisions(Some(from), Some(to)) => from > to,
+            ExportRange::Positions(Some(from), Some(to)) => from.commit > to.commit,
+            ExportRange::Time(Some(from), Some(to)) => from > to,
+            _ => false,
+        }
+    }
+}
+
+pub enum ExportProgress {
+    /// Events written so far.
+    Exporting(usize),
+    Done(usize),
+    Failed(String),
+}
+
+/// Where the next batch is read from.
+enum Cursor {
+    Stream(StreamPosition<u64>),
+    All(StreamPosition<Position>),
+}
+
+struct Export {
+    backend: Arc<dyn Backend>,
+    stream_name: String,
+    range: ExportRange,
+    path: PathBuf,
+    file: Option<BufWriter<File>>,
+    cursor: Cursor,
+    // Reading `$all` from a position includes the event at that position, it was exported
+    // with the previous batch.
+    last_position: Option<Position>,
+    written: usize,
+}
+
+impl Export {
+    /// Returns `true` once every event of the range is written.
+    async fn export_batch(&mut self) -> Result<bool, String> {
+        if self.file.is_none() {
+            let file = File::create(&self.path)
+                .map_err(|e| format!("Can't create '{}': {}", self.path.display(), e))?;
+
+            self.file = Some(BufWriter::new(file));
+        }
+
+        let events = match self.cursor {
+            Cursor::Stream(position) => {
+                let request = ReadRequest::default()
+                    .position(position)
+                    .max_count(BATCH_SIZE);
+
+                self.backend
+                    .read_stream(self.stream_name.clone(), request)
+                    .await
+            }
+            Cursor::All(position) => {
+                let request = ReadRequest::default()
+                    .position(position)
+                    .max_count(BATCH_SIZE);
+
+                self.backend.read_all(request).await
+            }
+        }
+        .map_err(|e| e.to_string())?;
+
+        let mut finished = events.len() < BATCH_SIZE;
+        let file = self.file.as_mut().expect("created above");
+        let write_error =
+            |e: std::io::Error| format!("Can't write '{}': {}", self.path.display(), e);
+
+        for event in events.iter() {
+            let event = event.get_original_event();
+
+            if let Cursor::All(_) = self.cursor {
+                if self.last_position == Some(event.position) {
+                    continue;
+                }
+
+                self.last_position = Some(event.position);
+                self.cursor = Cursor::All(StreamPosition::Position(event.position));
+            } else {
+                self.cursor = Cursor::Stream(StreamPosition::Position(event.revision + 1));
+            }
+
+            match self.range.locate(event) {
+                Bound::Before => continue,
+                Bound::After => {
+                    finished = true;
+                    break;
+                }
+                Bound::Within => {}
+            }
+
+            let mut line =
+                serde_json::to_vec(&ExportedEvent::from(event)).map_err(|e| e.to_string())?;
+            line.push(b'\n');
+
+            file.write_all(line.as_slice()).map_err(write_error)?;
+
+            self.written += 1;
+        }
+
+        if finished {
+            file.flush().map_err(write_error)?;
+        }
+
+        Ok(finished)
+    }
+}
+
+/// Writes the events of `stream_name` (or `$all`) within `range` to `path`, one JSON object per
+/// line. Progress is reported after every batch, dropping the stream stops the export.
+pub fn export_events(
+    backend: Arc<dyn Backend>,
+    stream_name: String,
+    range: ExportRange,
+    path: PathBuf,
+) -> impl Stream<Item = ExportProgress> + Send + 'static {
+    let cursor = match &range {
+        ExportRange::Positions(Some(from), _) => Cursor::All(StreamPosition::Position(*from)),
+        _ if stream_name == "$all" => Cursor::All(StreamPosition::Start),
+        ExportRange::Revisions(Some(from), _) => Cursor::Stream(StreamPosition::Position(*from)),
+        _ => Cursor::Stream(StreamPosition::Start),
+    };
+
+    let export = Export {
+        backend,
+        stream_name,
+        range,
+        path,
+        file: None,
+        cursor,
+        last_position: None,
+        written: 0,
+    };
+
+    futures::stream::unfold(Some(export), |export| async move {
+        let mut export = export?;
+
+        match export.export_batch().await {
+            Ok(true) => Some((ExportProgress::Done(export.written), None)),
+            Ok(false) => Some((ExportProgress::Exporting(export.written), Some(export))),
+            Err(e) => Some((ExportProgress::Failed(e), None)),
+        }
+    })
+}
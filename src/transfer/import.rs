@@ -0,0 +1,159 @@
+use crate::backend::{Backend, NewEvent};
+use crate::models::ExportedEvent;
+use eventstore::ExpectedRevision;
+use futures::Stream;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+#[derive(Clone, Debug)]
+pub struct ImportOptions {
+    pub stream_name: String,
+    /// Events appended per request.
+    pub batch_size: usize,
+    /// Only checks the file.
+    pub dry_run: bool,
+}
+
+pub enum ImportProgress {
+    /// Events checked so far, nothing is written until the whole file is.
+    Validated(usize),
+    /// Events appended so far.
+    Imported(usize),
+    Done(usize),
+    Failed(String),
+}
+
+struct Import {
+    backend: Arc<dyn Backend>,
+    path: PathBuf,
+    options: ImportOptions,
+    lines: Option<Lines<BufReader<File>>>,
+    line_number: usize,
+    validated: bool,
+    count: usize,
+}
+
+impl Import {
+    fn open(&mut self) -> Result<(), String> {
+        let file = File::open(&self.path)
+            .map_err(|e| format!("Can't open '{}': {}", self.path.display(), e))?;
+
+        self.lines = Some(BufReader::new(file).lines());
+        self.line_number = 0;
+        self.count = 0;
+
+        Ok(())
+    }
+
+    /// Up to `batch_size` events of the file, none once it's all read.
+    fn read_batch(&mut self) -> Result<Vec<NewEvent>, String> {
+        if self.lines.is_none() {
+            self.open()?;
+        }
+
+        let lines = self.lines.as_mut().expect("opened above");
+        let mut events = Vec::new();
+
+        while events.len() < self.options.batch_size.max(1) {
+            let line = match lines.next() {
+                Some(line) => {
+                    line.map_err(|e| format!("Can't read '{}': {}", self.path.display(), e))?
+                }
+                None => break,
+            };
+
+            self.line_number += 1;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let event = serde_json::from_str::<ExportedEvent>(line.as_str())
+                .map_err(|e| e.to_string())
+                .and_then(ExportedEvent::into_new_event)
+                .map_err(|e| format!("Line {}: {}", self.line_number, e))?;
+
+            events.push(event);
+        }
+
+        Ok(events)
+    }
+
+    /// Checks the whole file first, then appends it batch by batch from its start.
+    async fn import_batch(&mut self) -> Result<ImportProgress, String> {
+        let events = self.read_batch()?;
+
+        if !self.validated {
+            self.count += events.len();
+
+            if !events.is_empty() {
+                return Ok(ImportProgress::Validated(self.count));
+            }
+
+            if self.count == 0 {
+                return Err(format!("'{}' has no events", self.path.display()));
+            }
+
+            if self.options.dry_run {
+                return Ok(ImportProgress::Done(self.count));
+            }
+
+            self.validated = true;
+            self.open()?;
+
+            return Ok(ImportProgress::Imported(0));
+        }
+
+        if events.is_empty() {
+            return Ok(ImportProgress::Done(self.count));
+        }
+
+        let count = events.len();
+
+        // Ids are kept, so importing the same file again doesn't duplicate its events.
+        self.backend
+            .append_to_stream(
+                self.options.stream_name.clone(),
+                ExpectedRevision::Any,
+                events,
+            )
+            .await
+            .map_err(|e| format!("Appending after {} events failed: {}", self.count, e))?;
+
+        self.count += count;
+
+        Ok(ImportProgress::Imported(self.count))
+    }
+}
+
+/// Appends the events of the NDJSON file at `path`, as written by the export, to a stream.
+/// Progress is reported after every batch, dropping the stream stops the import.
+pub fn import_events(
+    backend: Arc<dyn Backend>,
+    path: PathBuf,
+    options: ImportOptions,
+) -> impl Stream<Item = ImportProgress> + Send + 'static {
+    let import = Import {
+        backend,
+        path,
+        options,
+        lines: None,
+        line_number: 0,
+        validated: false,
+        count: 0,
+    };
+
+    futures::stream::unfold(Some(import), |import| async move {
+        let mut import = import?;
+
+        match import.import_batch().await {
+            Ok(ImportProgress::Done(count)) => Some((ImportProgress::Done(count), None)),
+            Ok(progress) => Some((progress, Some(import))),
+            Err(e) => Some((ImportProgress::Failed(e), None)),
+        }
+    })
+}
@@ -0,0 +1,250 @@
+use crate::backend::AllFilter;
+use crate::views::input::{Form, LineInput};
+use crate::views::{centered_rect, ViewCtx, B};
+use crossterm::event::KeyCode;
+use eventstore::{Position, StreamPosition};
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::widgets::{Block, Borders, Clear, Paragraph};
+use tui::Frame;
+
+static FILTER_KINDS: &[&str] = &[
+    "none",
+    "exclude system events",
+    "stream name prefix",
+    "stream name regex",
+    "event type prefix",
+    "event type regex",
+];
+
+/// Filtered read of `$all`, paged forwards from `from`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllQuery {
+    pub filter: Option<AllFilter>,
+    pub from: StreamPosition<Position>,
+}
+
+impl AllQuery {
+    /// Like `stream name prefix 'orders-' | after C:100/P:100`.
+    pub fn describe(&self) -> String {
+        let filter = match self.filter.as_ref() {
+            None => "no filter".to_string(),
+            Some(AllFilter::ExcludeSystemEvents) => FILTER_KINDS[1].to_string(),
+            Some(AllFilter::StreamNamePrefixes(prefixes)) => {
+                format!("{} '{}'", FILTER_KINDS[2], prefixes.join(", "))
+            }
+            Some(AllFilter::StreamNameRegex(regex)) => format!("{} '{}'", FILTER_KINDS[3], regex),
+            Some(AllFilter::EventTypePrefixes(prefixes)) => {
+                format!("{} '{}'", FILTER_KINDS[4], prefixes.join(", "))
+            }
+            Some(AllFilter::EventTypeRegex(regex)) => format!("{} '{}'", FILTER_KINDS[5], regex),
+        };
+
+        match self.from {
+            StreamPosition::Position(position) => {
+                format!("{} | after {}", filter, format_position(position))
+            }
+            _ => format!("{} | from start", filter),
+        }
+    }
+}
+
+pub fn format_position(position: Position) -> String {
+    format!("C:{}/P:{}", position.commit, position.prepare)
+}
+
+/// Parses `C:100/P:100`, `100/100` or a commit position alone. Empty means the start of `$all`.
+pub fn parse_position(input: &str) -> Result<StreamPosition<Position>, String> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Ok(StreamPosition::Start);
+    }
+
+    let invalid = || {
+        format!(
+            "Invalid position '{}', expected C:<commit>/P:<prepare>",
+            input
+        )
+    };
+    let number = |value: &str, prefix: &str| {
+        let value = value.trim();
+        let value = value
+            .strip_prefix(prefix)
+            .or_else(|| value.strip_prefix(prefix.to_lowercase().as_str()))
+            .unwrap_or(value);
+
+        value.parse::<u64>().map_err(|_| invalid())
+    };
+
+    let (commit, prepare) = match input.split_once('/') {
+        Some((commit, prepare)) => (number(commit, "C:")?, number(prepare, "P:")?),
+        None => {
+            let commit = number(input, "C:")?;
+            (commit, commit)
+        }
+    };
+
+    Ok(StreamPosition::Position(Position { commit, prepare }))
+}
+
+/// Popup picking the server-side filter of `$all` and where to read it from.
+pub struct AllFilterForm {
+    // Index in `FILTER_KINDS`.
+    kind: usize,
+    value: LineInput,
+    from: LineInput,
+    // Focus is 0 for the kind, 1 for the value and 2 for the position.
+    form: Form,
+}
+
+impl AllFilterForm {
+    pub fn new(query: Option<&AllQuery>) -> Self {
+        let mut value = LineInput::default();
+        let mut from = LineInput::default();
+
+        let kind = match query.and_then(|q| q.filter.as_ref()) {
+            None => 0,
+            Some(AllFilter::ExcludeSystemEvents) => 1,
+            Some(AllFilter::StreamNamePrefixes(prefixes)) => {
+                value.set(prefixes.join(", "));
+                2
+            }
+            Some(AllFilter::StreamNameRegex(regex)) => {
+                value.set(regex.clone());
+                3
+            }
+            Some(AllFilter::EventTypePrefixes(prefixes)) => {
+                value.set(prefixes.join(", "));
+                4
+            }
+            Some(AllFilter::EventTypeRegex(regex)) => {
+                value.set(regex.clone());
+                5
+            }
+        };
+
+        if let Some(StreamPosition::Position(position)) = query.map(|q| q.from) {
+            from.set(format_position(position));
+        }
+
+        Self {
+            kind,
+            value,
+            from,
+            form: Form::new(3),
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.form.previous();
+    }
+
+    pub fn next(&mut self) {
+        self.form.next();
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.form.set_outcome(Err(error));
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode) {
+        let edited = match (self.form.focus(), key) {
+            (0, KeyCode::Left) => {
+                self.kind = (self.kind + FILTER_KINDS.len() - 1) % FILTER_KINDS.len();
+                true
+            }
+            (0, KeyCode::Right) => {
+                self.kind = (self.kind + 1) % FILTER_KINDS.len();
+                true
+            }
+            (0, _) => false,
+            (1, key) => self.value.on_key_pressed(key),
+            (_, key) => self.from.on_key_pressed(key),
+        };
+
+        if edited {
+            self.form.clear_outcome();
+        }
+    }
+
+    pub fn build(&self) -> Result<AllQuery, String> {
+        let value = self.value.value().trim();
+        let prefixes = || {
+            let prefixes = value
+                .split(',')
+                .map(str::trim)
+                .filter(|prefix| !prefix.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+
+            if prefixes.is_empty() {
+                return Err("At least one prefix is required".to_string());
+            }
+
+            Ok(prefixes)
+        };
+
+        let regex = || {
+            if value.is_empty() {
+                return Err("The regular expression is required".to_string());
+            }
+
+            Ok(value.to_string())
+        };
+
+        let filter = match self.kind {
+            0 => None,
+            1 => Some(AllFilter::ExcludeSystemEvents),
+            2 => Some(AllFilter::StreamNamePrefixes(prefixes()?)),
+            3 => Some(AllFilter::StreamNameRegex(regex()?)),
+            4 => Some(AllFilter::EventTypePrefixes(prefixes()?)),
+            _ => Some(AllFilter::EventTypeRegex(regex()?)),
+        };
+
+        Ok(AllQuery {
+            filter,
+            from: parse_position(self.from.value())?,
+        })
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>) {
+        let block = Block::default()
+            .title("Browse $all")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.popup);
+        let area = centered_rect(60, 30, frame.size());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .direction(Direction::Vertical)
+            .split(area);
+
+        let rows = ["Filter", "Prefixes or regex", "After position"];
+
+        for (idx, (rect, label)) in rects.iter().zip(rows).enumerate() {
+            let area = self.form.draw_label(ctx, frame, *rect, idx, label);
+
+            match idx {
+                0 => {
+                    let kind = format!("< {} >", FILTER_KINDS[self.kind]);
+                    frame.render_widget(Paragraph::new(kind).style(ctx.theme.text), area);
+                }
+                1 => self.value.draw(ctx, frame, area, ""),
+                _ => self.from.draw(ctx, frame, area, ""),
+            }
+        }
+
+        self.form.draw_outcome(ctx, frame, rects[4]);
+    }
+}
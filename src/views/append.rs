@@ -0,0 +1,199 @@
+use crate::backend::NewEvent;
+use crate::views::input::{ExpectedRevisionInput, Form, LineInput, TextArea};
+use crate::views::{centered_rect, ViewCtx, B};
+use crossterm::event::KeyCode;
+use eventstore::ExpectedRevision;
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::widgets::{Block, Borders, Clear};
+use tui::Frame;
+use uuid::Uuid;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Field {
+    EventType,
+    EventId,
+    Expected,
+    Data,
+    Metadata,
+}
+
+static FIELDS: &[Field] = &[
+    Field::EventType,
+    Field::EventId,
+    Field::Expected,
+    Field::Data,
+    Field::Metadata,
+];
+
+/// Popup appending a single JSON event. Payloads are validated before anything gets sent.
+pub struct AppendForm {
+    event_type: LineInput,
+    event_id: LineInput,
+    expected: ExpectedRevisionInput,
+    data: TextArea,
+    metadata: TextArea,
+    // Focus indexes `FIELDS`.
+    form: Form,
+    submitted: Option<(ExpectedRevision, NewEvent)>,
+}
+
+impl Default for AppendForm {
+    fn default() -> Self {
+        Self {
+            event_type: LineInput::default(),
+            event_id: LineInput::default(),
+            expected: ExpectedRevisionInput::default(),
+            data: TextArea::default(),
+            metadata: TextArea::default(),
+            form: Form::new(FIELDS.len()),
+            submitted: None,
+        }
+    }
+}
+
+impl AppendForm {
+    pub fn previous(&mut self) {
+        self.form.previous();
+    }
+
+    pub fn next(&mut self) {
+        self.form.next();
+    }
+
+    pub fn set_outcome(&mut self, outcome: Result<String, String>) {
+        self.form.set_outcome(outcome);
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode) {
+        let edited = match FIELDS[self.form.focus()] {
+            Field::EventType | Field::EventId | Field::Expected if key == KeyCode::Enter => {
+                self.next();
+                false
+            }
+            Field::EventType => self.event_type.on_key_pressed(key),
+            Field::EventId => self.event_id.on_key_pressed(key),
+            Field::Expected => self.expected.on_key_pressed(key),
+            Field::Data => self.data.on_key_pressed(key),
+            Field::Metadata => self.metadata.on_key_pressed(key),
+        };
+
+        if edited {
+            self.form.clear_outcome();
+        }
+    }
+
+    /// Validates the fields. The event is then handed out once by `take_submitted`.
+    pub fn submit(&mut self) {
+        match self.build() {
+            Ok(submitted) => {
+                self.submitted = Some(submitted);
+                self.form.clear_outcome();
+            }
+            Err(e) => self.form.set_outcome(Err(e)),
+        }
+    }
+
+    pub fn take_submitted(&mut self) -> Option<(ExpectedRevision, NewEvent)> {
+        self.submitted.take()
+    }
+
+    fn build(&self) -> Result<(ExpectedRevision, NewEvent), String> {
+        let event_type = self.event_type.value().trim();
+
+        if event_type.is_empty() {
+            return Err("The event type is required".to_string());
+        }
+
+        let event_id = self.event_id.value().trim();
+        let id = if event_id.is_empty() {
+            None
+        } else {
+            let id = Uuid::parse_str(event_id)
+                .map_err(|_| format!("Invalid event id '{}'", event_id))?;
+
+            Some(id)
+        };
+
+        let expected = self.expected.value()?;
+
+        let data = serde_json::from_str::<serde_json::Value>(self.data.value())
+            .map_err(|e| format!("Data isn't valid JSON: {}", e))?;
+
+        let metadata = if self.metadata.value().trim().is_empty() {
+            Vec::new()
+        } else {
+            let metadata = serde_json::from_str::<serde_json::Value>(self.metadata.value())
+                .map_err(|e| format!("Metadata isn't valid JSON: {}", e))?;
+
+            metadata.to_string().into_bytes()
+        };
+
+        let event = NewEvent {
+            id,
+            event_type: event_type.to_string(),
+            data: data.to_string().into_bytes(),
+            metadata,
+            is_json: true,
+        };
+
+        Ok((expected, event))
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>, stream_name: &str) {
+        let block = Block::default()
+            .title(format!("Append event to '{}'", stream_name))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.popup);
+        let area = centered_rect(70, 70, frame.size());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(4),
+                Constraint::Length(6),
+                Constraint::Length(1),
+            ])
+            .direction(Direction::Vertical)
+            .split(area);
+
+        // `FIELDS` lists the fields in declaration order.
+        let style = |field: Field| self.form.label_style(ctx, field as usize);
+
+        let rows = [
+            (Field::EventType, "Event type"),
+            (Field::EventId, "Event id"),
+            (Field::Expected, "Expected revision"),
+        ];
+
+        for (rect, (field, label)) in rects.iter().zip(rows) {
+            let area = self
+                .form
+                .draw_label(ctx, frame, *rect, field as usize, label);
+
+            match field {
+                Field::EventType => self.event_type.draw(ctx, frame, area, ""),
+                Field::EventId => self.event_id.draw(ctx, frame, area, ""),
+                _ => self.expected.draw(ctx, frame, area),
+            }
+        }
+
+        self.data
+            .draw(ctx, frame, rects[3], "Data (JSON)", style(Field::Data));
+        self.metadata.draw(
+            ctx,
+            frame,
+            rects[4],
+            "Metadata (JSON, optional)",
+            style(Field::Metadata),
+        );
+
+        self.form.draw_outcome(ctx, frame, rects[5]);
+    }
+}
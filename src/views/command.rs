@@ -0,0 +1,288 @@
+use crate::backend::ProjectionCommand;
+use crate::config::{parse_duration, Config};
+use crate::views::input::LineInput;
+use crate::views::projections::ProjectionsMsg;
+use crate::views::stream_browser::StreamsMsg;
+use crate::views::{centered_rect, Msg, Tab, ViewCtx, B, TABS};
+use crossterm::event::KeyCode;
+use std::collections::BTreeSet;
+use std::time::Duration;
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::text::Spans;
+use tui::widgets::{Block, Borders, Clear, Paragraph};
+use tui::Frame;
+
+static COMMANDS: &[(&str, &str)] = &[
+    ("stream", "stream <name>"),
+    ("sub", "sub <stream>/<group>"),
+    ("proj", "proj enable|disable|reset|abort <name>"),
+    ("tab", "tab <name>"),
+    ("refresh", "refresh [interval]"),
+    ("connect", "connect <profile or connection string>"),
+    ("quit", "quit"),
+];
+
+static PROJECTION_COMMANDS: &[(ProjectionCommand, &str)] = &[
+    (ProjectionCommand::Enable, "enable"),
+    (ProjectionCommand::Disable, "disable"),
+    (ProjectionCommand::Reset, "reset"),
+    (ProjectionCommand::Abort, "abort"),
+];
+
+pub enum Command {
+    Stream(String),
+    /// Keyed by `stream/group`.
+    Subscription(String),
+    Projection(ProjectionCommand, String),
+    Tab(Tab),
+    /// Refreshes the view right away, or changes how often it gets refreshed.
+    Refresh(Option<Duration>),
+    /// Profile name or connection string.
+    Connect(String),
+    Quit,
+}
+
+impl Command {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let words = input.split_whitespace().collect::<Vec<_>>();
+
+        let command = match words.as_slice() {
+            [] => return Err("Empty command".to_string()),
+            ["stream", name] => Command::Stream(name.to_string()),
+            ["sub", name] => Command::Subscription(name.to_string()),
+            ["proj", command, name] => {
+                let command = PROJECTION_COMMANDS
+                    .iter()
+                    .find(|(_, n)| n == command)
+                    .map(|(c, _)| *c)
+                    .ok_or_else(|| format!("Unknown projection command '{}'", command))?;
+
+                Command::Projection(command, name.to_string())
+            }
+            ["tab", name] => {
+                Command::Tab(Tab::from_name(name).ok_or_else(|| format!("Unknown tab '{}'", name))?)
+            }
+            ["refresh"] => Command::Refresh(None),
+            ["refresh", interval] => {
+                let interval = parse_duration(interval)?;
+
+                if interval.is_zero() {
+                    return Err("Refresh interval can't be zero".to_string());
+                }
+
+                Command::Refresh(Some(interval))
+            }
+            ["connect", name] => Command::Connect(name.to_string()),
+            ["quit"] | ["q"] => Command::Quit,
+            [name, ..] => {
+                return match COMMANDS.iter().find(|(n, _)| n == name) {
+                    Some((_, usage)) => Err(format!("Usage: {}", usage)),
+                    None => Err(format!("Unknown command '{}'", name)),
+                };
+            }
+        };
+
+        Ok(command)
+    }
+}
+
+/// Names seen in the responses of the views, offered as completions.
+#[derive(Default)]
+pub struct Completions {
+    streams: BTreeSet<String>,
+    projections: BTreeSet<String>,
+    subscriptions: BTreeSet<String>,
+    profiles: BTreeSet<String>,
+}
+
+impl Completions {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            profiles: config.profiles.keys().cloned().collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn observe(&mut self, msg: &Msg) {
+        match msg {
+            Msg::Streams(StreamsMsg::Streams {
+                last_created,
+                recently_changed,
+                ..
+            }) => {
+                let names = last_created.iter().chain(recently_changed.iter());
+                self.streams
+                    .extend(names.filter(|name| !name.is_empty()).cloned());
+            }
+
+            Msg::Streams(StreamsMsg::Events(name, _, Ok(_))) => {
+                self.streams.insert(name.clone());
+            }
+
+            Msg::Streams(StreamsMsg::StreamNames(names)) => {
+                self.streams.extend(names.iter().cloned());
+            }
+
+            Msg::Projections(ProjectionsMsg::List(projections)) => {
+                self.projections
+                    .extend(projections.iter().map(|p| p.name.clone()));
+            }
+
+            Msg::PersistentSubscriptions(subs) => {
+                self.subscriptions.extend(
+                    subs.iter()
+                        .map(|s| format!("{}/{}", s.event_source, s.group_name)),
+                );
+            }
+
+            _ => {}
+        }
+    }
+}
+
+pub enum CommandRequest {
+    Noop,
+    Close,
+    Run(Command),
+}
+
+/// Popup where commands like `stream orders-1` or `tab monitoring` are typed.
+#[derive(Default)]
+pub struct CommandLine {
+    input: LineInput,
+    error: Option<String>,
+}
+
+impl CommandLine {
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode, completions: &Completions) -> CommandRequest {
+        match key {
+            KeyCode::Esc => return CommandRequest::Close,
+
+            KeyCode::Enter => match Command::parse(self.input.value()) {
+                Ok(command) => return CommandRequest::Run(command),
+                Err(e) => self.error = Some(e),
+            },
+
+            KeyCode::Tab => self.complete(completions),
+
+            key => {
+                if self.input.on_key_pressed(key) {
+                    self.error = None;
+                }
+            }
+        }
+
+        CommandRequest::Noop
+    }
+
+    /// Where the word being typed starts, along with what it could be completed to.
+    fn suggestions(&self, completions: &Completions) -> (usize, Vec<String>) {
+        let input = self.input.value();
+        let start = input
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map(|(idx, c)| idx + c.len_utf8())
+            .unwrap_or_default();
+        let (previous, word) = input.split_at(start);
+        let previous = previous.split_whitespace().collect::<Vec<_>>();
+
+        let candidates = match previous.as_slice() {
+            [] => COMMANDS.iter().map(|(name, _)| name.to_string()).collect(),
+            ["stream"] => completions.streams.iter().cloned().collect(),
+            ["sub"] => completions.subscriptions.iter().cloned().collect(),
+            ["proj"] => PROJECTION_COMMANDS
+                .iter()
+                .map(|(_, name)| name.to_string())
+                .collect(),
+            ["proj", _] => completions.projections.iter().cloned().collect(),
+            ["tab"] => TABS.iter().map(|tab| tab.name().to_string()).collect(),
+            ["connect"] => completions.profiles.iter().cloned().collect(),
+            _ => Vec::new(),
+        };
+
+        let matches = candidates
+            .into_iter()
+            .filter(|candidate: &String| candidate.starts_with(word))
+            .collect();
+
+        (start, matches)
+    }
+
+    /// Completes the word being typed up to where the candidates diverge.
+    fn complete(&mut self, completions: &Completions) {
+        let (start, matches) = self.suggestions(completions);
+
+        let mut common = match matches.first() {
+            Some(first) => first.clone(),
+            None => return,
+        };
+
+        for candidate in matches.iter().skip(1) {
+            let len = common
+                .chars()
+                .zip(candidate.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a.len_utf8())
+                .sum();
+
+            common.truncate(len);
+        }
+
+        let mut value = self.input.value()[..start].to_string();
+        value.push_str(common.as_str());
+
+        if matches.len() == 1 {
+            value.push(' ');
+        }
+
+        self.input.set(value);
+        self.error = None;
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>, completions: &Completions) {
+        let block = Block::default()
+            .title("Command")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.popup);
+        let area = centered_rect(50, 40, frame.size());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .direction(Direction::Vertical)
+            .split(area);
+
+        self.input.draw(ctx, frame, rects[0], ":");
+
+        if let Some(error) = self.error.as_ref() {
+            let label = Paragraph::new(error.as_str()).style(ctx.theme.error);
+            frame.render_widget(label, rects[1]);
+        }
+
+        let (start, matches) = self.suggestions(completions);
+
+        // Commands are listed along with their arguments.
+        let lines = matches
+            .iter()
+            .map(|name| match COMMANDS.iter().find(|(n, _)| *n == name) {
+                Some((_, usage)) if start == 0 => Spans::from(*usage),
+                _ => Spans::from(name.as_str()),
+            })
+            .collect::<Vec<_>>();
+
+        frame.render_widget(Paragraph::new(lines).style(ctx.theme.text), rects[2]);
+    }
+}
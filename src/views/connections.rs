@@ -0,0 +1,137 @@
+use crate::config::{Config, Connection};
+use crate::views::input::LineInput;
+use crate::views::{centered_rect, ViewCtx, B};
+use crossterm::event::KeyCode;
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState};
+use tui::Frame;
+
+pub enum SwitcherRequest {
+    Noop,
+    Close,
+    Connect(Connection),
+}
+
+/// Popup listing the configured profiles. Typing a connection string connects to it instead of
+/// the selected profile.
+pub struct ConnectionSwitcher {
+    profiles: Vec<Connection>,
+    selected: usize,
+    table_state: TableState,
+    input: LineInput,
+    error: Option<String>,
+}
+
+impl ConnectionSwitcher {
+    pub fn new(config: &Config, current: &str) -> Self {
+        let profiles = config
+            .profiles
+            .iter()
+            .map(|(name, profile)| Connection {
+                name: name.clone(),
+                connection_string: profile.connection_string.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let selected = profiles
+            .iter()
+            .position(|p| p.name == current)
+            .unwrap_or_default();
+
+        Self {
+            profiles,
+            selected,
+            table_state: TableState::default(),
+            input: LineInput::default(),
+            error: None,
+        }
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode) -> SwitcherRequest {
+        match key {
+            KeyCode::Esc => return SwitcherRequest::Close,
+
+            KeyCode::Up => {
+                if self.selected > 0 {
+                    self.selected -= 1;
+                }
+            }
+
+            KeyCode::Down => {
+                if self.selected + 1 < self.profiles.len() {
+                    self.selected += 1;
+                }
+            }
+
+            KeyCode::Enter => {
+                if !self.input.is_empty() {
+                    return SwitcherRequest::Connect(Connection::ad_hoc(self.input.value()));
+                }
+
+                if let Some(profile) = self.profiles.get(self.selected) {
+                    return SwitcherRequest::Connect(profile.clone());
+                }
+            }
+
+            key => {
+                if self.input.on_key_pressed(key) {
+                    self.error = None;
+                }
+            }
+        }
+
+        SwitcherRequest::Noop
+    }
+
+    pub fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>) {
+        let block = Block::default()
+            .title("Connect")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.popup);
+        let area = centered_rect(60, 40, frame.size());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .direction(Direction::Vertical)
+            .split(area);
+
+        let rows = self.profiles.iter().map(|profile| {
+            Row::new(vec![
+                Cell::from(profile.name.as_str()),
+                Cell::from(profile.connection_string.as_str()),
+            ])
+        });
+
+        let table = Table::new(rows)
+            .highlight_style(ctx.theme.highlight)
+            .widths(&[Constraint::Percentage(30), Constraint::Percentage(70)]);
+
+        if self.profiles.is_empty() {
+            let label = Paragraph::new("No profile configured").style(ctx.theme.text);
+            frame.render_widget(label, rects[0]);
+        } else {
+            self.table_state.select(Some(self.selected));
+            frame.render_stateful_widget(table, rects[0], &mut self.table_state);
+        }
+
+        self.input.draw(ctx, frame, rects[1], "Connection string: ");
+
+        if let Some(error) = self.error.as_ref() {
+            let label = Paragraph::new(error.as_str()).style(ctx.theme.error);
+            frame.render_widget(label, rects[2]);
+        }
+    }
+}
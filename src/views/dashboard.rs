@@ -1,15 +1,15 @@
-use crate::views::{Env, Request, View, ViewCtx, B};
-use crossterm::event::KeyCode;
-use eventstore::operations::Stats;
-use eventstore_extras::stats::{Statistics, StatisticsExt};
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::RwLock;
+use crate::views::keymap::Action;
+use crate::views::{Env, Msg, Request, StageActions, View, ViewCtx, B};
+use eventstore_extras::stats::Statistics;
 use tui::layout::{Constraint, Direction, Layout, Rect};
-use tui::style::{Color, Style};
 use tui::widgets::{Block, Borders, Cell, Row, Table, TableState};
 use tui::Frame;
 
+static STAGES: &[StageActions] = &[StageActions {
+    name: "Dashboard",
+    actions: &[(Action::Up, "Scroll up"), (Action::Down, "Scroll down")],
+}];
+
 static HEADERS: &[&'static str] = &[
     "Queue Name",
     "Length (Current | Peak)",
@@ -22,7 +22,6 @@ static HEADERS: &[&'static str] = &[
 pub struct DashboardView {
     table_state: TableState,
     model: Statistics,
-    stats: Arc<RwLock<Option<Stats>>>,
     scroll: u16,
 }
 
@@ -31,7 +30,6 @@ impl Default for DashboardView {
         Self {
             table_state: TableState::default(),
             model: Default::default(),
-            stats: Arc::new(RwLock::new(None)),
             scroll: 0,
         }
     }
@@ -45,24 +43,23 @@ impl View for DashboardView {
     fn unload(&mut self, _env: &Env) {}
 
     fn refresh(&mut self, env: &Env) -> eventstore::Result<()> {
-        let client = env.op_client.clone();
-        let state = self.stats.clone();
-
-        self.model = env.handle.block_on(async move {
-            let mut state = state.write().await;
-            if state.is_none() {
-                let options = eventstore::operations::StatsOptions::default()
-                    .refresh_time(Duration::from_secs(2));
+        let backend = env.backend.clone();
 
-                *state = Some(client.stats(&options).await?);
-            }
+        env.spawn(async move {
+            let stats = backend.stats().await?;
 
-            state.as_mut().unwrap().next().await?.parse_statistics()
-        })?;
+            Ok(Msg::Dashboard(Box::new(stats)))
+        });
 
         Ok(())
     }
 
+    fn on_msg(&mut self, msg: Msg) {
+        if let Msg::Dashboard(stats) = msg {
+            self.model = *stats;
+        }
+    }
+
     fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
         let rect = Layout::default()
             .constraints([Constraint::Min(0)].as_ref())
@@ -72,7 +69,7 @@ impl View for DashboardView {
 
         let header_cells = HEADERS
             .iter()
-            .map(|h| Cell::from(*h).style(Style::default().fg(Color::Green)));
+            .map(|h| Cell::from(*h).style(ctx.theme.header));
 
         // 4 is the height taken by borders.
         if rect.height >= self.model.es.queues.len() as u16 + 4 {
@@ -120,7 +117,7 @@ impl View for DashboardView {
         }
 
         let header = Row::new(header_cells)
-            .style(ctx.normal_style)
+            .style(ctx.theme.normal)
             .height(1)
             .bottom_margin(1);
 
@@ -132,7 +129,7 @@ impl View for DashboardView {
                     .title("Dashboard")
                     .title_alignment(tui::layout::Alignment::Right),
             )
-            .highlight_style(ctx.selected_style)
+            .highlight_style(ctx.theme.selected)
             .widths(&[
                 Constraint::Percentage(15),
                 Constraint::Percentage(15),
@@ -145,15 +142,14 @@ impl View for DashboardView {
         frame.render_stateful_widget(table, rect, &mut self.table_state)
     }
 
-    fn on_key_pressed(&mut self, key: KeyCode) -> Request {
-        match key {
-            KeyCode::Char('q' | 'Q') => return Request::Exit,
-            KeyCode::Up => {
+    fn on_action(&mut self, action: Action) -> Request {
+        match action {
+            Action::Up => {
                 if self.scroll > 0 {
                     self.scroll -= 1;
                 }
             }
-            KeyCode::Down => {
+            Action::Down => {
                 self.scroll += 1;
             }
             _ => {}
@@ -162,7 +158,7 @@ impl View for DashboardView {
         Request::Noop
     }
 
-    fn keybindings(&self) -> &[(&str, &str)] {
-        &[("↑", "Scroll up"), ("↓", "Scroll down")]
+    fn stages(&self) -> &'static [StageActions] {
+        STAGES
     }
 }
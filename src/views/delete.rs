@@ -0,0 +1,144 @@
+use crate::views::input::{ExpectedRevisionInput, Form, LineInput};
+use crate::views::{centered_rect, ViewCtx, B};
+use crossterm::event::KeyCode;
+use eventstore::ExpectedRevision;
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use tui::Frame;
+
+/// Popup confirming the deletion of a stream, its name has to be typed again.
+pub struct DeleteDialog {
+    stream_name: String,
+    tombstone: bool,
+    confirmation: LineInput,
+    expected: ExpectedRevisionInput,
+    // Focus is 0 for the confirmation and 1 for the expected revision.
+    form: Form,
+    submitted: Option<ExpectedRevision>,
+    deleted: bool,
+}
+
+impl DeleteDialog {
+    pub fn new(stream_name: String, tombstone: bool) -> Self {
+        Self {
+            stream_name,
+            tombstone,
+            confirmation: LineInput::default(),
+            expected: ExpectedRevisionInput::default(),
+            form: Form::new(2),
+            submitted: None,
+            deleted: false,
+        }
+    }
+
+    pub fn is_tombstone(&self) -> bool {
+        self.tombstone
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.form.next();
+    }
+
+    pub fn set_outcome(&mut self, outcome: Result<String, String>) {
+        self.deleted = outcome.is_ok();
+        self.form.set_outcome(outcome);
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode) {
+        if self.deleted {
+            return;
+        }
+
+        let edited = if self.form.focus() == 1 {
+            self.expected.on_key_pressed(key)
+        } else {
+            self.confirmation.on_key_pressed(key)
+        };
+
+        if edited {
+            self.form.clear_outcome();
+        }
+    }
+
+    /// Checks the typed name. The expected revision is then handed out once by
+    /// `take_submitted`.
+    pub fn submit(&mut self) {
+        if self.deleted {
+            return;
+        }
+
+        if self.confirmation.value() != self.stream_name {
+            self.form
+                .set_outcome(Err("The typed name doesn't match the stream".to_string()));
+            return;
+        }
+
+        match self.expected.value() {
+            Ok(expected) => {
+                self.submitted = Some(expected);
+                self.form.clear_outcome();
+            }
+            Err(e) => self.form.set_outcome(Err(e)),
+        }
+    }
+
+    pub fn take_submitted(&mut self) -> Option<ExpectedRevision> {
+        self.submitted.take()
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>) {
+        let (title, warning) = if self.tombstone {
+            (
+                "Tombstone stream",
+                "Tombstoned streams can never be recreated, nor written to again.",
+            )
+        } else {
+            (
+                "Delete stream",
+                "Soft deleted streams can be recreated by appending to them again.",
+            )
+        };
+
+        let block = Block::default()
+            .title(format!("{} '{}'", title, self.stream_name))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.popup);
+        let area = centered_rect(60, 40, frame.size());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .direction(Direction::Vertical)
+            .split(area);
+
+        let warning = Paragraph::new(warning)
+            .style(ctx.theme.warning)
+            .wrap(Wrap { trim: true });
+
+        frame.render_widget(warning, rects[0]);
+
+        let area = self.form.draw_label(ctx, frame, rects[1], 0, "Stream name");
+        self.confirmation.draw(ctx, frame, area, "");
+
+        let area = self
+            .form
+            .draw_label(ctx, frame, rects[2], 1, "Expected revision");
+        self.expected.draw(ctx, frame, area);
+
+        self.form.draw_outcome(ctx, frame, rects[4]);
+    }
+}
@@ -0,0 +1,455 @@
+use crate::decoders::{Content, Decoders};
+use crate::views::{ViewCtx, B};
+use eventstore::{RecordedEvent, ResolvedEvent};
+use serde_json::Value;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::Style;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, Tabs};
+use tui::Frame;
+
+static PANES: &[&str] = &["Changes", "Side by side"];
+
+// Columns moved per horizontal scroll.
+const SCROLL_STEP: u16 = 8;
+
+// Above that many lines on both sides, the differing middles aren't aligned line by line.
+const MAX_ALIGNED_LINES: usize = 2000;
+
+/// Difference at a JSON path, like `$.items[0].sku`.
+enum Change {
+    Added(String, Value),
+    Removed(String, Value),
+    Changed(String, Value, Value),
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Kind {
+    Same,
+    Removed,
+    Added,
+}
+
+/// Line of the side-by-side diff, with its line numbers. A side is `None` where the other one
+/// has lines it lacks.
+struct Row {
+    left: Option<(usize, String)>,
+    right: Option<(usize, String)>,
+    same: bool,
+}
+
+/// Data of two events compared: the JSON paths that differ, and the lines of both side by side.
+/// The older event is on the left.
+pub struct EventDiff {
+    left_name: String,
+    right_name: String,
+    // `None` unless both payloads are JSON.
+    changes: Option<Vec<Change>>,
+    rows: Vec<Row>,
+    // Index in `PANES`.
+    pane: usize,
+    scroll: u16,
+    column: u16,
+    // Lines displayed by the last draw, paging moves by that much.
+    height: u16,
+}
+
+impl EventDiff {
+    pub fn new(decoders: &Decoders, left: &ResolvedEvent, right: &ResolvedEvent) -> Self {
+        let (mut left, mut right) = (target(left), target(right));
+
+        if (left.position.commit, left.revision) > (right.position.commit, right.revision) {
+            std::mem::swap(&mut left, &mut right);
+        }
+
+        let decode = |event: &RecordedEvent| {
+            decoders
+                .decode(
+                    event.event_type.as_str(),
+                    event.data.as_ref(),
+                    event.is_json,
+                )
+                .content
+        };
+
+        let (left_content, right_content) = (decode(left), decode(right));
+
+        let changes = match (&left_content, &right_content) {
+            (Content::Json(a), Content::Json(b)) => {
+                let mut changes = Vec::new();
+                diff_values("$".to_string(), a, b, &mut changes);
+                Some(changes)
+            }
+            _ => None,
+        };
+
+        let (left_text, right_text) = (content_text(left_content), content_text(right_content));
+        let rows = side_by_side(
+            left_text.lines().collect::<Vec<_>>().as_slice(),
+            right_text.lines().collect::<Vec<_>>().as_slice(),
+        );
+
+        Self {
+            left_name: format!("{}@{}", left.revision, left.stream_id),
+            right_name: format!("{}@{}", right.revision, right.stream_id),
+            changes,
+            rows,
+            pane: 0,
+            scroll: 0,
+            column: 0,
+            height: 0,
+        }
+    }
+
+    pub fn next_pane(&mut self) {
+        self.pane = (self.pane + 1) % PANES.len();
+        self.scroll = 0;
+        self.column = 0;
+    }
+
+    pub fn previous_pane(&mut self) {
+        self.pane = (self.pane + PANES.len() - 1) % PANES.len();
+        self.scroll = 0;
+        self.column = 0;
+    }
+
+    pub fn scroll_up(&mut self, lines: u16) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    pub fn scroll_down(&mut self, lines: u16) {
+        let count = if self.pane == 0 {
+            self.changes.as_ref().map(|c| c.len() + 2).unwrap_or(1)
+        } else {
+            self.rows.len()
+        };
+
+        self.scroll = self
+            .scroll
+            .saturating_add(lines)
+            .min(count.saturating_sub(1) as u16);
+    }
+
+    pub fn page(&self) -> u16 {
+        self.height.max(1)
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.column = self.column.saturating_sub(SCROLL_STEP);
+    }
+
+    pub fn scroll_right(&mut self) {
+        self.column = self.column.saturating_add(SCROLL_STEP);
+    }
+
+    pub fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
+        let rects = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+            .margin(2)
+            .split(area);
+
+        // Less the top and bottom borders.
+        self.height = rects[1].height.saturating_sub(2);
+
+        let titles = PANES
+            .iter()
+            .map(|t| Spans::from(Span::styled(*t, ctx.theme.tab)))
+            .collect();
+
+        let tabs = Tabs::new(titles)
+            .select(self.pane)
+            .style(ctx.theme.text)
+            .highlight_style(ctx.theme.selected_tab);
+
+        frame.render_widget(tabs, rects[0]);
+
+        if self.pane == 0 {
+            let block = Block::default()
+                .borders(Borders::TOP | Borders::BOTTOM)
+                .title(format!("'{}' → '{}'", self.left_name, self.right_name))
+                .title_alignment(tui::layout::Alignment::Right);
+
+            let changes = Paragraph::new(self.change_lines(ctx))
+                .block(block)
+                .scroll((self.scroll, self.column));
+
+            frame.render_widget(changes, rects[1]);
+
+            return;
+        }
+
+        let sides = Layout::default()
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .direction(Direction::Horizontal)
+            .split(rects[1]);
+
+        let width = self.rows.len().to_string().len();
+
+        for (idx, name) in [&self.left_name, &self.right_name].iter().enumerate() {
+            let lines = self
+                .rows
+                .iter()
+                .map(|row| {
+                    let (side, kind) = if idx == 0 {
+                        (row.left.as_ref(), Kind::Removed)
+                    } else {
+                        (row.right.as_ref(), Kind::Added)
+                    };
+                    let kind = if row.same { Kind::Same } else { kind };
+
+                    match side {
+                        Some((number, line)) => Spans::from(vec![
+                            Span::styled(format!("{:>w$} | ", number, w = width), ctx.theme.label),
+                            Span::styled(line.clone(), kind_style(ctx, kind)),
+                        ]),
+                        None => Spans::from(""),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let borders = if idx == 0 {
+                Borders::TOP | Borders::BOTTOM | Borders::RIGHT
+            } else {
+                Borders::TOP | Borders::BOTTOM
+            };
+
+            let side = Paragraph::new(lines)
+                .block(Block::default().borders(borders).title(name.as_str()))
+                .scroll((self.scroll, self.column));
+
+            frame.render_widget(side, sides[idx]);
+        }
+    }
+
+    fn change_lines(&self, ctx: ViewCtx) -> Vec<Spans<'static>> {
+        let changes = match self.changes.as_ref() {
+            Some(changes) => changes,
+            None => {
+                return vec![Spans::from(Span::styled(
+                    "Payloads aren't both JSON, see the side-by-side pane",
+                    ctx.theme.warning,
+                ))]
+            }
+        };
+
+        if changes.is_empty() {
+            return vec![Spans::from(Span::styled("Same data", ctx.theme.label))];
+        }
+
+        let count = |f: fn(&Change) -> bool| changes.iter().filter(|c| f(c)).count();
+        let summary = format!(
+            "{} added, {} removed, {} changed",
+            count(|c| matches!(c, Change::Added(..))),
+            count(|c| matches!(c, Change::Removed(..))),
+            count(|c| matches!(c, Change::Changed(..))),
+        );
+
+        let mut lines = vec![
+            Spans::from(Span::styled(summary, ctx.theme.label)),
+            Spans::from(""),
+        ];
+
+        for change in changes {
+            let (text, style) = match change {
+                Change::Added(path, value) => {
+                    (format!("+ {}: {}", path, value), ctx.theme.diff_added)
+                }
+                Change::Removed(path, value) => {
+                    (format!("- {}: {}", path, value), ctx.theme.diff_removed)
+                }
+                Change::Changed(path, before, after) => (
+                    format!("~ {}: {} → {}", path, before, after),
+                    ctx.theme.warning,
+                ),
+            };
+
+            lines.push(Spans::from(Span::styled(text, style)));
+        }
+
+        lines
+    }
+}
+
+fn target(event: &ResolvedEvent) -> &RecordedEvent {
+    event.event.as_ref().unwrap_or(event.get_original_event())
+}
+
+fn content_text(content: Content) -> String {
+    match content {
+        Content::Json(value) => serde_json::to_string_pretty(&value).unwrap_or_default(),
+        Content::Text(text) => text,
+    }
+}
+
+fn kind_style(ctx: ViewCtx, kind: Kind) -> Style {
+    match kind {
+        Kind::Same => ctx.theme.text,
+        Kind::Removed => ctx.theme.diff_removed,
+        Kind::Added => ctx.theme.diff_added,
+    }
+}
+
+/// Object members are compared by key, array items by index.
+fn diff_values(path: String, left: &Value, right: &Value, changes: &mut Vec<Change>) {
+    match (left, right) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in a.iter() {
+                let path = member_path(path.as_str(), key);
+
+                match b.get(key) {
+                    Some(other) => diff_values(path, value, other, changes),
+                    None => changes.push(Change::Removed(path, value.clone())),
+                }
+            }
+
+            for (key, value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                changes.push(Change::Added(
+                    member_path(path.as_str(), key),
+                    value.clone(),
+                ));
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for idx in 0..a.len().max(b.len()) {
+                let path = format!("{}[{}]", path, idx);
+
+                match (a.get(idx), b.get(idx)) {
+                    (Some(value), Some(other)) => diff_values(path, value, other, changes),
+                    (Some(value), None) => changes.push(Change::Removed(path, value.clone())),
+                    (None, Some(value)) => changes.push(Change::Added(path, value.clone())),
+                    (None, None) => {}
+                }
+            }
+        }
+        (a, b) if a != b => changes.push(Change::Changed(path, a.clone(), b.clone())),
+        _ => {}
+    }
+}
+
+/// Same syntax as the event filter, keys that aren't identifiers are quoted.
+fn member_path(parent: &str, key: &str) -> String {
+    let identifier = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    if identifier {
+        format!("{}.{}", parent, key)
+    } else {
+        format!("{}[{}]", parent, Value::from(key))
+    }
+}
+
+/// Aligns both sides on their longest common subsequence of lines. Removed and added lines in
+/// between are paired up, so a changed line stays on the same row.
+fn side_by_side(left: &[&str], right: &[&str]) -> Vec<Row> {
+    let prefix = left
+        .iter()
+        .zip(right.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let middle_left = &left[prefix..left.len() - suffix];
+    let middle_right = &right[prefix..right.len() - suffix];
+
+    let mut kinds = vec![Kind::Same; prefix];
+    kinds.extend(align(middle_left, middle_right));
+    kinds.extend(vec![Kind::Same; suffix]);
+
+    let mut rows = Vec::new();
+    let (mut l, mut r) = (0, 0);
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+
+    for kind in kinds {
+        match kind {
+            Kind::Removed => {
+                removed.push((l + 1, left[l].to_string()));
+                l += 1;
+            }
+            Kind::Added => {
+                added.push((r + 1, right[r].to_string()));
+                r += 1;
+            }
+            Kind::Same => {
+                pair_up(&mut rows, &mut removed, &mut added);
+                rows.push(Row {
+                    left: Some((l + 1, left[l].to_string())),
+                    right: Some((r + 1, right[r].to_string())),
+                    same: true,
+                });
+                l += 1;
+                r += 1;
+            }
+        }
+    }
+
+    pair_up(&mut rows, &mut removed, &mut added);
+
+    rows
+}
+
+type NumberedLines = Vec<(usize, String)>;
+
+fn pair_up(rows: &mut Vec<Row>, removed: &mut NumberedLines, added: &mut NumberedLines) {
+    let count = removed.len().max(added.len());
+    let mut removed = removed.drain(..);
+    let mut added = added.drain(..);
+
+    for _ in 0..count {
+        rows.push(Row {
+            left: removed.next(),
+            right: added.next(),
+            same: false,
+        });
+    }
+}
+
+/// Edit script turning `left` into `right`.
+fn align(left: &[&str], right: &[&str]) -> Vec<Kind> {
+    if left.len() > MAX_ALIGNED_LINES || right.len() > MAX_ALIGNED_LINES {
+        let mut kinds = vec![Kind::Removed; left.len()];
+        kinds.extend(vec![Kind::Added; right.len()]);
+        return kinds;
+    }
+
+    // Length of the longest common subsequence of `left[i..]` and `right[j..]`.
+    let width = right.len() + 1;
+    let mut lengths = vec![0u16; (left.len() + 1) * width];
+
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lengths[i * width + j] = if left[i] == right[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut kinds = Vec::new();
+
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            kinds.push(Kind::Same);
+            i += 1;
+            j += 1;
+        } else if j < right.len()
+            && (i == left.len() || lengths[i * width + j + 1] >= lengths[(i + 1) * width + j])
+        {
+            kinds.push(Kind::Added);
+            j += 1;
+        } else {
+            kinds.push(Kind::Removed);
+            i += 1;
+        }
+    }
+
+    kinds
+}
@@ -0,0 +1,221 @@
+use eventstore::ResolvedEvent;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Narrows the events of a stream, parsed from what's typed in the filter bar:
+/// - `type:OrderCancelled` matches event types, `*` and `?` being wildcards like in `type:Order*`.
+/// - `$.customer.id == "42"` compares a JSON path of the data with a JSON value. `!=`, `<`, `<=`,
+///   `>` and `>=` are supported too, the path alone matches the events where it's set.
+/// - Anything else is searched in the data and metadata, ignoring case.
+pub struct EventFilter {
+    input: String,
+    predicate: Predicate,
+}
+
+enum Predicate {
+    Type(String),
+    Path(Vec<Segment>, Option<(Operator, Value)>),
+    Text(String),
+}
+
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Copy, Clone)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+// Longest first, so `<=` isn't read as `<`.
+static OPERATORS: &[(&str, Operator)] = &[
+    ("==", Operator::Equal),
+    ("!=", Operator::NotEqual),
+    ("<=", Operator::LessOrEqual),
+    (">=", Operator::GreaterOrEqual),
+    ("<", Operator::Less),
+    (">", Operator::Greater),
+];
+
+impl EventFilter {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+
+        let predicate = if let Some(pattern) = input.strip_prefix("type:") {
+            let pattern = pattern.trim();
+
+            if pattern.is_empty() {
+                return Err("The event type is required".to_string());
+            }
+
+            Predicate::Type(pattern.to_string())
+        } else if let Some(path) = input.strip_prefix('$') {
+            let (segments, rest) = parse_path(path)?;
+            let rest = rest.trim();
+
+            let comparison = if rest.is_empty() {
+                None
+            } else {
+                let (symbol, operator) = OPERATORS
+                    .iter()
+                    .find(|(symbol, _)| rest.starts_with(symbol))
+                    .ok_or_else(|| format!("Unknown operator in '{}'", rest))?;
+
+                let value = rest[symbol.len()..].trim();
+                let value = serde_json::from_str::<Value>(value)
+                    .map_err(|_| format!("Invalid value '{}', strings are quoted", value))?;
+
+                Some((*operator, value))
+            };
+
+            Predicate::Path(segments, comparison)
+        } else {
+            Predicate::Text(input.to_lowercase())
+        };
+
+        Ok(Self {
+            input: input.to_string(),
+            predicate,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.input.as_str()
+    }
+
+    /// Unresolved links never match.
+    pub fn matches(&self, event: &ResolvedEvent) -> bool {
+        let event = match event.event.as_ref() {
+            Some(event) => event,
+            None => return false,
+        };
+
+        match &self.predicate {
+            Predicate::Type(pattern) => glob_matches(pattern, event.event_type.as_str()),
+
+            Predicate::Path(segments, comparison) => {
+                if !event.is_json {
+                    return false;
+                }
+
+                let data = match serde_json::from_slice::<Value>(event.data.as_ref()) {
+                    Ok(data) => data,
+                    Err(_) => return false,
+                };
+
+                let value = segments
+                    .iter()
+                    .try_fold(&data, |value, segment| match segment {
+                        Segment::Key(key) => value.get(key.as_str()),
+                        Segment::Index(idx) => value.get(*idx),
+                    });
+
+                match (value, comparison) {
+                    (None, _) => false,
+                    (Some(_), None) => true,
+                    (Some(value), Some((operator, expected))) => {
+                        compare(value, *operator, expected)
+                    }
+                }
+            }
+
+            Predicate::Text(text) => [event.data.as_ref(), event.custom_metadata.as_ref()]
+                .iter()
+                .any(|payload| {
+                    String::from_utf8_lossy(payload)
+                        .to_lowercase()
+                        .contains(text.as_str())
+                }),
+        }
+    }
+}
+
+/// Parses paths like `.lines[0].sku`, up to whatever follows them.
+fn parse_path(input: &str) -> Result<(Vec<Segment>, &str), String> {
+    let mut segments = Vec::new();
+    let mut rest = input;
+
+    loop {
+        if let Some(key) = rest.strip_prefix('.') {
+            let end = key
+                .find(|c: char| c == '.' || c == '[' || c.is_whitespace() || "=!<>".contains(c))
+                .unwrap_or(key.len());
+
+            if end == 0 {
+                return Err(format!("Invalid JSON path '${}'", input));
+            }
+
+            segments.push(Segment::Key(key[..end].to_string()));
+            rest = &key[end..];
+        } else if let Some(index) = rest.strip_prefix('[') {
+            let (index, remaining) = index
+                .split_once(']')
+                .ok_or_else(|| format!("Invalid JSON path '${}'", input))?;
+
+            let index = index
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid index '{}' in JSON path", index))?;
+
+            segments.push(Segment::Index(index));
+            rest = remaining;
+        } else {
+            return Ok((segments, rest));
+        }
+    }
+}
+
+fn compare(value: &Value, operator: Operator, expected: &Value) -> bool {
+    let ordering = match (value.as_f64(), expected.as_f64()) {
+        (Some(value), Some(expected)) => value.partial_cmp(&expected),
+        _ => None,
+    };
+
+    match operator {
+        Operator::Equal => value == expected || ordering == Some(Ordering::Equal),
+        Operator::NotEqual => value != expected && ordering != Some(Ordering::Equal),
+        Operator::Less => ordering == Some(Ordering::Less),
+        Operator::LessOrEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        Operator::Greater => ordering == Some(Ordering::Greater),
+        Operator::GreaterOrEqual => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+/// `*` matches any sequence of characters and `?` any single one.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    // Where to resume from after the last `*`, if the text doesn't match past it.
+    let mut backtrack = None;
+    let (mut p, mut t) = (0, 0);
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
@@ -0,0 +1,304 @@
+use crate::transfer::{ExportProgress, ExportRange};
+use crate::views::all_filter::parse_position;
+use crate::views::input::{Form, LineInput};
+use crate::views::{centered_rect, TaskHandle, ViewCtx, B};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use crossterm::event::KeyCode;
+use eventstore::{Position, StreamPosition};
+use std::path::PathBuf;
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use tui::Frame;
+
+static FIELDS: &[&str] = &["File", "Range", "From", "To"];
+
+/// Popup picking the file and the range of an export, then following its progress.
+pub struct ExportDialog {
+    stream_name: String,
+    path: LineInput,
+    // Time range rather than revisions, or positions for `$all`.
+    by_time: bool,
+    from: LineInput,
+    to: LineInput,
+    // Focus indexes `FIELDS`, the outcome is the progress of the export or why the fields were
+    // rejected.
+    form: Form,
+    submitted: Option<(PathBuf, ExportRange)>,
+    // Export in progress, aborted when dropped.
+    running: Option<(PathBuf, TaskHandle)>,
+    written: usize,
+}
+
+impl ExportDialog {
+    pub fn new(stream_name: String) -> Self {
+        let mut path = LineInput::default();
+        let file_name = stream_name
+            .trim_start_matches('$')
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || "-_.".contains(c) {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        path.set(format!("{}.ndjson", file_name));
+
+        Self {
+            stream_name,
+            path,
+            by_time: false,
+            from: LineInput::default(),
+            to: LineInput::default(),
+            form: Form::new(FIELDS.len()),
+            submitted: None,
+            running: None,
+            written: 0,
+        }
+    }
+
+    fn is_all(&self) -> bool {
+        self.stream_name == "$all"
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    pub fn previous(&mut self) {
+        self.form.previous();
+    }
+
+    pub fn next(&mut self) {
+        self.form.next();
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode) {
+        if self.is_running() {
+            return;
+        }
+
+        let edited = match (self.form.focus(), key) {
+            (0, key) => self.path.on_key_pressed(key),
+            (1, KeyCode::Left | KeyCode::Right) => {
+                self.by_time = !self.by_time;
+                self.from = LineInput::default();
+                self.to = LineInput::default();
+                true
+            }
+            (1, _) => false,
+            (2, key) => self.from.on_key_pressed(key),
+            (_, key) => self.to.on_key_pressed(key),
+        };
+
+        if edited {
+            self.form.clear_outcome();
+        }
+    }
+
+    /// Checks the fields. The file and range are then handed out once by `take_submitted`.
+    pub fn submit(&mut self) {
+        if self.is_running() {
+            return;
+        }
+
+        match self.build() {
+            Ok(submitted) => {
+                self.submitted = Some(submitted);
+                self.form.clear_outcome();
+            }
+            Err(e) => self.form.set_outcome(Err(e)),
+        }
+    }
+
+    fn build(&self) -> Result<(PathBuf, ExportRange), String> {
+        let path = self.path.value().trim();
+
+        if path.is_empty() {
+            return Err("The file is required".to_string());
+        }
+
+        let (from, to) = (self.from.value(), self.to.value());
+        let range = if self.by_time {
+            ExportRange::Time(parse_time(from)?, parse_time(to)?)
+        } else if self.is_all() {
+            ExportRange::Positions(parse_bound(from)?, parse_bound(to)?)
+        } else {
+            ExportRange::Revisions(parse_revision(from)?, parse_revision(to)?)
+        };
+
+        if range.is_empty() {
+            return Err("The range is empty, 'From' is past 'To'".to_string());
+        }
+
+        Ok((PathBuf::from(path), range))
+    }
+
+    pub fn take_submitted(&mut self) -> Option<(PathBuf, ExportRange)> {
+        self.submitted.take()
+    }
+
+    pub fn start(&mut self, path: PathBuf, handle: TaskHandle) {
+        self.written = 0;
+        self.form
+            .set_outcome(Ok(format!("Exporting to '{}'...", path.display())));
+        self.running = Some((path, handle));
+    }
+
+    /// Stops the export in progress, returns `false` if there's none.
+    pub fn cancel(&mut self) -> bool {
+        let (path, _) = match self.running.take() {
+            Some(running) => running,
+            None => return false,
+        };
+
+        self.form.set_outcome(Err(format!(
+            "Cancelled after {} events, '{}' is incomplete",
+            self.written,
+            path.display()
+        )));
+
+        true
+    }
+
+    pub fn set_progress(&mut self, path: PathBuf, progress: ExportProgress) {
+        match self.running.as_ref() {
+            Some((running, _)) if *running == path => {}
+            _ => return,
+        }
+
+        let outcome = match progress {
+            ExportProgress::Exporting(written) => {
+                self.written = written;
+
+                Ok(format!(
+                    "Exporting to '{}'... {} events written",
+                    path.display(),
+                    written
+                ))
+            }
+            ExportProgress::Done(written) => {
+                self.written = written;
+                self.running = None;
+
+                Ok(format!(
+                    "Exported {} events to '{}'",
+                    written,
+                    path.display()
+                ))
+            }
+            ExportProgress::Failed(e) => {
+                self.running = None;
+
+                Err(format!(
+                    "Export failed after {} events: {}",
+                    self.written, e
+                ))
+            }
+        };
+
+        self.form.set_outcome(outcome);
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>) {
+        let block = Block::default()
+            .title(format!("Export '{}' to NDJSON", self.stream_name))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.popup);
+        let area = centered_rect(60, 40, frame.size());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(2),
+                Constraint::Min(1),
+            ])
+            .direction(Direction::Vertical)
+            .split(area);
+
+        for (idx, (rect, label)) in rects.iter().zip(FIELDS).enumerate() {
+            let area = self.form.draw_label(ctx, frame, *rect, idx, label);
+
+            match idx {
+                0 => self.path.draw(ctx, frame, area, ""),
+                1 => {
+                    let kind = match (self.by_time, self.is_all()) {
+                        (true, _) => "time",
+                        (false, true) => "positions",
+                        (false, false) => "revisions",
+                    };
+
+                    let kind = Paragraph::new(format!("< {} >", kind)).style(ctx.theme.text);
+                    frame.render_widget(kind, area);
+                }
+                2 => self.from.draw(ctx, frame, area, ""),
+                _ => self.to.draw(ctx, frame, area, ""),
+            }
+        }
+
+        let hint = match (self.by_time, self.is_all()) {
+            (true, _) => {
+                "Inclusive, empty for no bound. Like 2022-04-15T05:20:00Z, or a date in UTC."
+            }
+            (false, true) => "Inclusive commit positions like C:1024/P:1024, empty for no bound.",
+            (false, false) => "Inclusive revisions, empty for no bound.",
+        };
+
+        let hint = Paragraph::new(hint)
+            .style(ctx.theme.label)
+            .wrap(Wrap { trim: true });
+
+        frame.render_widget(hint, rects[4]);
+
+        self.form.draw_outcome(ctx, frame, rects[5]);
+    }
+}
+
+fn parse_revision(input: &str) -> Result<Option<u64>, String> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    input
+        .parse::<u64>()
+        .map(Some)
+        .map_err(|_| format!("Invalid revision '{}'", input))
+}
+
+fn parse_bound(input: &str) -> Result<Option<Position>, String> {
+    match parse_position(input)? {
+        StreamPosition::Position(position) => Ok(Some(position)),
+        _ => Ok(None),
+    }
+}
+
+/// RFC 3339, or a date alone meaning its midnight in UTC.
+fn parse_time(input: &str) -> Result<Option<DateTime<Utc>>, String> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(Some(time.with_timezone(&Utc)));
+    }
+
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|time| Some(Utc.from_utc_datetime(&time)))
+        .ok_or_else(|| format!("Invalid time '{}'", input))
+}
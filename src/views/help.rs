@@ -0,0 +1,120 @@
+use crate::views::input::LineInput;
+use crate::views::{ViewCtx, B};
+use crossterm::event::KeyCode;
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Paragraph};
+use tui::Frame;
+
+/// Keys bound in one stage of a view, or globally, along with their labels.
+pub struct HelpSection {
+    pub title: String,
+    pub bindings: Vec<(String, String)>,
+}
+
+pub enum HelpRequest {
+    Noop,
+    Close,
+}
+
+/// Full-screen list of every binding. Typing filters it down to the matching keys and labels.
+#[derive(Default)]
+pub struct HelpOverlay {
+    search: LineInput,
+    scroll: u16,
+}
+
+impl HelpOverlay {
+    pub fn on_key_pressed(&mut self, key: KeyCode) -> HelpRequest {
+        match key {
+            KeyCode::Esc => return HelpRequest::Close,
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            key => {
+                if self.search.on_key_pressed(key) {
+                    self.scroll = 0;
+                }
+            }
+        }
+
+        HelpRequest::Noop
+    }
+
+    pub fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, sections: &[HelpSection]) {
+        let area = frame.size();
+        let block = Block::default()
+            .title("Help")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.text);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .direction(Direction::Vertical)
+            .split(area);
+
+        self.search.draw(ctx, frame, rects[0], "Search: ");
+
+        let query = self.search.value().to_lowercase();
+        let max_key = sections
+            .iter()
+            .flat_map(|section| section.bindings.iter())
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or_default();
+
+        let mut lines = Vec::new();
+
+        for section in sections {
+            let bindings = section
+                .bindings
+                .iter()
+                .filter(|(key, label)| {
+                    key.to_lowercase().contains(query.as_str())
+                        || label.to_lowercase().contains(query.as_str())
+                })
+                .collect::<Vec<_>>();
+
+            if bindings.is_empty() {
+                continue;
+            }
+
+            lines.push(Spans::from(Span::styled(
+                section.title.as_str(),
+                ctx.theme.header,
+            )));
+
+            for (key, label) in bindings {
+                lines.push(Spans::from(vec![
+                    Span::styled(format!("  {:>width$}", key, width = max_key), ctx.theme.key),
+                    Span::styled(format!(" {}", label), ctx.theme.text),
+                ]));
+            }
+
+            lines.push(Spans::from(""));
+        }
+
+        if lines.is_empty() {
+            lines.push(Spans::from(format!(
+                "No binding matches '{}'",
+                self.search.value()
+            )));
+        }
+
+        let max_scroll = (lines.len() as u16).saturating_sub(rects[2].height);
+        self.scroll = self.scroll.min(max_scroll);
+
+        let paragraph = Paragraph::new(lines).scroll((self.scroll, 0));
+        frame.render_widget(paragraph, rects[2]);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::transfer::{ImportOptions, ImportProgress, DEFAULT_BATCH_SIZE};
+use crate::views::input::{Form, LineInput};
+use crate::views::{centered_rect, TaskHandle, ViewCtx, B};
+use crossterm::event::KeyCode;
+use std::path::PathBuf;
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use tui::Frame;
+
+static FIELDS: &[&str] = &["File", "Target stream", "Dry run"];
+
+/// Popup picking the file and the stream of an import, then following its progress.
+pub struct ImportDialog {
+    path: LineInput,
+    stream_name: LineInput,
+    dry_run: bool,
+    // Focus indexes `FIELDS`, the outcome is the progress of the import or why the fields were
+    // rejected.
+    form: Form,
+    submitted: Option<(PathBuf, ImportOptions)>,
+    // Import in progress, aborted when dropped.
+    running: Option<(PathBuf, TaskHandle)>,
+    // Options of the last started import.
+    options: Option<ImportOptions>,
+    imported: bool,
+}
+
+impl ImportDialog {
+    pub fn new(stream_name: Option<String>) -> Self {
+        let mut input = LineInput::default();
+
+        if let Some(stream_name) = stream_name {
+            input.set(stream_name);
+        }
+
+        Self {
+            path: LineInput::default(),
+            stream_name: input,
+            dry_run: false,
+            form: Form::new(FIELDS.len()),
+            submitted: None,
+            running: None,
+            options: None,
+            imported: false,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Whether events were appended, as opposed to only checked.
+    pub fn is_imported(&self) -> bool {
+        self.imported
+    }
+
+    pub fn previous(&mut self) {
+        self.form.previous();
+    }
+
+    pub fn next(&mut self) {
+        self.form.next();
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode) {
+        if self.is_running() {
+            return;
+        }
+
+        let edited = match (self.form.focus(), key) {
+            (0, key) => self.path.on_key_pressed(key),
+            (1, key) => self.stream_name.on_key_pressed(key),
+            (_, KeyCode::Left | KeyCode::Right) => {
+                self.dry_run = !self.dry_run;
+                true
+            }
+            _ => false,
+        };
+
+        if edited {
+            self.form.clear_outcome();
+        }
+    }
+
+    /// Checks the fields. The file and options are then handed out once by `take_submitted`.
+    pub fn submit(&mut self) {
+        if self.is_running() {
+            return;
+        }
+
+        let path = self.path.value().trim();
+        let stream_name = self.stream_name.value().trim();
+
+        let error = if path.is_empty() {
+            "The file is required"
+        } else if stream_name.is_empty() {
+            "The target stream is required"
+        } else if stream_name == "$all" {
+            "Events can't be appended to $all"
+        } else {
+            let options = ImportOptions {
+                stream_name: stream_name.to_string(),
+                batch_size: DEFAULT_BATCH_SIZE,
+                dry_run: self.dry_run,
+            };
+
+            self.submitted = Some((PathBuf::from(path), options));
+            self.form.clear_outcome();
+
+            return;
+        };
+
+        self.form.set_outcome(Err(error.to_string()));
+    }
+
+    pub fn take_submitted(&mut self) -> Option<(PathBuf, ImportOptions)> {
+        self.submitted.take()
+    }
+
+    pub fn start(&mut self, path: PathBuf, options: ImportOptions, handle: TaskHandle) {
+        self.form
+            .set_outcome(Ok(format!("Checking '{}'...", path.display())));
+        self.options = Some(options);
+        self.running = Some((path, handle));
+    }
+
+    /// Stops the import in progress, returns `false` if there's none.
+    pub fn cancel(&mut self) -> bool {
+        if self.running.take().is_none() {
+            return false;
+        }
+
+        let message = if self.imported {
+            "Cancelled, the events appended so far are kept"
+        } else {
+            "Cancelled, nothing was written"
+        };
+
+        self.form.set_outcome(Err(message.to_string()));
+
+        true
+    }
+
+    pub fn set_progress(&mut self, path: PathBuf, progress: ImportProgress) {
+        match self.running.as_ref() {
+            Some((running, _)) if *running == path => {}
+            _ => return,
+        }
+
+        let (stream_name, dry_run) = match self.options.as_ref() {
+            Some(options) => (options.stream_name.clone(), options.dry_run),
+            None => return,
+        };
+
+        let outcome = match progress {
+            ImportProgress::Validated(count) => Ok(format!(
+                "Checking '{}'... {} valid events",
+                path.display(),
+                count
+            )),
+            ImportProgress::Imported(count) => {
+                self.imported = true;
+
+                Ok(format!(
+                    "Importing into '{}'... {} events appended",
+                    stream_name, count
+                ))
+            }
+            ImportProgress::Done(count) if dry_run => {
+                self.running = None;
+
+                Ok(format!(
+                    "Dry run: {} valid events, nothing was written",
+                    count
+                ))
+            }
+            ImportProgress::Done(count) => {
+                self.running = None;
+
+                Ok(format!("Imported {} events into '{}'", count, stream_name))
+            }
+            ImportProgress::Failed(e) => {
+                self.running = None;
+
+                Err(e)
+            }
+        };
+
+        self.form.set_outcome(outcome);
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>) {
+        let block = Block::default()
+            .title("Import events from NDJSON")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.popup);
+        let area = centered_rect(60, 40, frame.size());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(2),
+                Constraint::Min(1),
+            ])
+            .direction(Direction::Vertical)
+            .split(area);
+
+        for (idx, (rect, label)) in rects.iter().zip(FIELDS).enumerate() {
+            let area = self.form.draw_label(ctx, frame, *rect, idx, label);
+
+            match idx {
+                0 => self.path.draw(ctx, frame, area, ""),
+                1 => self.stream_name.draw(ctx, frame, area, ""),
+                _ => {
+                    let dry_run = if self.dry_run { "< yes >" } else { "< no >" };
+                    let dry_run = Paragraph::new(dry_run).style(ctx.theme.text);
+
+                    frame.render_widget(dry_run, area);
+                }
+            }
+        }
+
+        let hint = Paragraph::new(
+            "The whole file is checked before anything is appended. Event ids are kept, \
+             importing a file twice doesn't duplicate its events.",
+        )
+        .style(ctx.theme.label)
+        .wrap(Wrap { trim: true });
+
+        frame.render_widget(hint, rects[3]);
+
+        self.form.draw_outcome(ctx, frame, rects[4]);
+    }
+}
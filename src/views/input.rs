@@ -0,0 +1,347 @@
+use crate::views::{ViewCtx, B};
+use crossterm::event::KeyCode;
+use eventstore::ExpectedRevision;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::Style;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, Wrap};
+use tui::Frame;
+
+/// Width of the labels column of forms.
+pub const LABEL_WIDTH: u16 = 19;
+
+/// What popups made of labelled fields have in common: which field has the focus, cycled with
+/// `previous` and `next`, and the line telling how the last submission went.
+pub struct Form {
+    fields: usize,
+    focus: usize,
+    // Result of the last submission, or why the fields were rejected.
+    outcome: Option<Result<String, String>>,
+}
+
+impl Form {
+    pub fn new(fields: usize) -> Self {
+        debug_assert!(fields > 0, "forms have at least one field");
+
+        Self {
+            fields,
+            focus: 0,
+            outcome: None,
+        }
+    }
+
+    /// Index of the focused field.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    pub fn previous(&mut self) {
+        self.focus = (self.focus + self.fields - 1) % self.fields;
+    }
+
+    pub fn next(&mut self) {
+        self.focus = (self.focus + 1) % self.fields;
+    }
+
+    pub fn set_outcome(&mut self, outcome: Result<String, String>) {
+        self.outcome = Some(outcome);
+    }
+
+    pub fn clear_outcome(&mut self) {
+        self.outcome = None;
+    }
+
+    /// Style of the label of field `idx`, highlighted when it has the focus.
+    pub fn label_style(&self, ctx: ViewCtx, idx: usize) -> Style {
+        if idx == self.focus {
+            ctx.theme.selected
+        } else {
+            ctx.theme.label
+        }
+    }
+
+    /// Draws the label of field `idx` on the left of `area`, returns where its value goes.
+    pub fn draw_label(
+        &self,
+        ctx: ViewCtx,
+        frame: &mut Frame<B>,
+        area: Rect,
+        idx: usize,
+        label: &str,
+    ) -> Rect {
+        let layout = Layout::default()
+            .constraints([Constraint::Length(LABEL_WIDTH), Constraint::Min(1)])
+            .direction(Direction::Horizontal)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(label.to_string()).style(self.label_style(ctx, idx)),
+            layout[0],
+        );
+
+        layout[1]
+    }
+
+    /// Errors are highlighted, nothing is drawn before the first submission.
+    pub fn draw_outcome(&self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
+        let outcome = match self.outcome.as_ref() {
+            Some(Ok(message)) => Paragraph::new(message.as_str()).style(ctx.theme.text),
+            Some(Err(error)) => Paragraph::new(error.as_str()).style(ctx.theme.error),
+            None => return,
+        };
+
+        frame.render_widget(outcome.wrap(Wrap { trim: true }), area);
+    }
+}
+
+/// Single line text input, rendered as `label: value____`. `Left`, `Right`, `Home` and `End`
+/// move the cursor, which is highlighted unless it's at the end.
+#[derive(Default)]
+pub struct LineInput {
+    buffer: String,
+    // In characters.
+    cursor: usize,
+}
+
+impl LineInput {
+    pub fn value(&self) -> &str {
+        self.buffer.as_str()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// The cursor goes at the end.
+    pub fn set(&mut self, value: String) {
+        self.cursor = value.chars().count();
+        self.buffer = value;
+    }
+
+    fn len(&self) -> usize {
+        self.buffer.chars().count()
+    }
+
+    // Byte offset of the `idx`th character.
+    fn offset(&self, idx: usize) -> usize {
+        self.buffer
+            .char_indices()
+            .nth(idx)
+            .map(|(offset, _)| offset)
+            .unwrap_or(self.buffer.len())
+    }
+
+    /// Returns `true` if the key edited the input.
+    pub fn on_key_pressed(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.buffer.remove(self.offset(self.cursor));
+                true
+            }
+            KeyCode::Delete if self.cursor < self.len() => {
+                self.buffer.remove(self.offset(self.cursor));
+                true
+            }
+            KeyCode::Char(c) if !c.is_control() => {
+                let offset = self.offset(self.cursor);
+                self.buffer.insert(offset, c);
+                self.cursor += 1;
+                true
+            }
+            KeyCode::Left => {
+                self.cursor = self.cursor.saturating_sub(1);
+                false
+            }
+            KeyCode::Right => {
+                self.cursor = (self.cursor + 1).min(self.len());
+                false
+            }
+            KeyCode::Home => {
+                self.cursor = 0;
+                false
+            }
+            KeyCode::End => {
+                self.cursor = self.len();
+                false
+            }
+            _ => false,
+        }
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect, label: &str) {
+        let layout = Layout::default()
+            .constraints([
+                Constraint::Length(label.chars().count() as u16),
+                Constraint::Min(1),
+            ])
+            .direction(Direction::Horizontal)
+            .split(area);
+
+        let width = layout[1].width as usize;
+        let count = self.len();
+
+        frame.render_widget(
+            Paragraph::new(label.to_string()).style(ctx.theme.text),
+            layout[0],
+        );
+
+        if width == 0 {
+            return;
+        }
+
+        // Keeps the cursor, where the typing happens, in sight.
+        let skipped = (self.cursor + 1).saturating_sub(width);
+        let mut chars = self
+            .buffer
+            .chars()
+            .skip(skipped)
+            .take(width)
+            .collect::<Vec<_>>();
+        chars.extend(std::iter::repeat('_').take(width.saturating_sub(count - skipped)));
+
+        let cursor = self.cursor - skipped;
+        let mut spans = vec![Span::styled(
+            chars[..cursor].iter().collect::<String>(),
+            ctx.theme.text,
+        )];
+
+        if self.cursor < count {
+            spans.push(Span::styled(chars[cursor].to_string(), ctx.theme.selected));
+            spans.push(Span::styled(
+                chars[cursor + 1..].iter().collect::<String>(),
+                ctx.theme.text,
+            ));
+        } else {
+            spans.push(Span::styled(
+                chars[cursor..].iter().collect::<String>(),
+                ctx.theme.text,
+            ));
+        }
+
+        frame.render_widget(Paragraph::new(Spans::from(spans)), layout[1]);
+    }
+}
+
+static EXPECTED_REVISIONS: &[&str] = &["any", "no stream", "exists", "exact"];
+
+/// Expected revision of a write, picked with `Left` and `Right`. The revision is typed when
+/// `exact` is picked.
+#[derive(Default)]
+pub struct ExpectedRevisionInput {
+    // Index in `EXPECTED_REVISIONS`.
+    choice: usize,
+    revision: LineInput,
+}
+
+impl ExpectedRevisionInput {
+    /// Returns `true` if the key edited the input.
+    pub fn on_key_pressed(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Left => {
+                self.choice =
+                    (self.choice + EXPECTED_REVISIONS.len() - 1) % EXPECTED_REVISIONS.len();
+                true
+            }
+            KeyCode::Right => {
+                self.choice = (self.choice + 1) % EXPECTED_REVISIONS.len();
+                true
+            }
+            // Revisions only.
+            KeyCode::Char(c) if !c.is_ascii_digit() => false,
+            key if self.is_exact() => self.revision.on_key_pressed(key),
+            _ => false,
+        }
+    }
+
+    fn is_exact(&self) -> bool {
+        self.choice == EXPECTED_REVISIONS.len() - 1
+    }
+
+    pub fn value(&self) -> Result<ExpectedRevision, String> {
+        let expected = match self.choice {
+            0 => ExpectedRevision::Any,
+            1 => ExpectedRevision::NoStream,
+            2 => ExpectedRevision::StreamExists,
+            _ => {
+                let revision = self
+                    .revision
+                    .value()
+                    .parse::<u64>()
+                    .map_err(|_| "The expected revision is required".to_string())?;
+
+                ExpectedRevision::Exact(revision)
+            }
+        };
+
+        Ok(expected)
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
+        let choice = format!("< {} > ", EXPECTED_REVISIONS[self.choice]);
+
+        if self.is_exact() {
+            self.revision.draw(ctx, frame, area, choice.as_str());
+        } else {
+            frame.render_widget(Paragraph::new(choice).style(ctx.theme.text), area);
+        }
+    }
+}
+
+/// Multi-line text input, edited at its end like `LineInput`. `Enter` starts a new line.
+#[derive(Default)]
+pub struct TextArea {
+    buffer: String,
+}
+
+impl TextArea {
+    pub fn value(&self) -> &str {
+        self.buffer.as_str()
+    }
+
+    /// Returns `true` if the key edited the input.
+    pub fn on_key_pressed(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Enter => {
+                self.buffer.push('\n');
+                true
+            }
+            KeyCode::Tab => {
+                self.buffer.push_str("  ");
+                true
+            }
+            KeyCode::Backspace => {
+                self.buffer.pop();
+                true
+            }
+            KeyCode::Char(c) if !c.is_control() => {
+                self.buffer.push(c);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect, title: &str, style: Style) {
+        let mut lines = self.buffer.split('\n').collect::<Vec<_>>();
+        let height = area.height.saturating_sub(2) as usize;
+
+        // Keeps the last lines, where the typing happens, in sight.
+        let skipped = lines.len().saturating_sub(height);
+        lines.drain(..skipped);
+
+        let text = lines.join("\n") + "_";
+        let paragraph = Paragraph::new(text).style(ctx.theme.text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(title.to_string(), style)),
+        );
+
+        frame.render_widget(paragraph, area);
+    }
+}
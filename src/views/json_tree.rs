@@ -0,0 +1,504 @@
+use crate::views::input::LineInput;
+use crate::views::{ViewCtx, B};
+use crossterm::event::KeyCode;
+use serde_json::Value;
+use std::collections::HashSet;
+use tui::layout::{Constraint, Layout, Rect};
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Paragraph};
+use tui::Frame;
+
+// Columns moved per horizontal scroll.
+const SCROLL_STEP: u16 = 8;
+
+/// JSON document displayed as a tree: objects and arrays fold, values are colored by type and
+/// can be searched. Nodes are identified by their JSON pointer, like `/items/0/id`.
+pub struct JsonTree {
+    value: Value,
+    folded: HashSet<String>,
+    // Index in the visible lines.
+    cursor: usize,
+    // First visible line.
+    offset: usize,
+    column: u16,
+    // Lines displayed by the last draw, paging moves by that much.
+    height: usize,
+    // Being typed.
+    search: Option<LineInput>,
+    query: Option<String>,
+    // Pointers of the matching nodes, in document order.
+    matches: Vec<String>,
+}
+
+enum Node<'a> {
+    /// Non-empty object or array, `true` when folded.
+    Open(&'a Value, bool),
+    Close(char),
+    /// Anything else, empty objects and arrays included.
+    Scalar(&'a Value),
+}
+
+struct Line<'a> {
+    depth: usize,
+    key: Option<&'a str>,
+    pointer: String,
+    node: Node<'a>,
+    comma: bool,
+}
+
+impl JsonTree {
+    pub fn new(value: Value) -> Self {
+        Self {
+            value,
+            folded: HashSet::new(),
+            cursor: 0,
+            offset: 0,
+            column: 0,
+            height: 0,
+            search: None,
+            query: None,
+            matches: Vec::new(),
+        }
+    }
+
+    /// Replaces the document, folds and search are kept.
+    pub fn set_value(&mut self, value: Value) {
+        if self.value == value {
+            return;
+        }
+
+        self.value = value;
+        self.update_matches();
+        self.cursor = self
+            .cursor
+            .min(self.visible_lines().len().saturating_sub(1));
+    }
+
+    fn visible_lines(&self) -> Vec<Line<'_>> {
+        lines(&self.value, Some(&self.folded))
+    }
+
+    fn all_lines(&self) -> Vec<Line<'_>> {
+        lines(&self.value, None)
+    }
+
+    pub fn up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.visible_lines().len().saturating_sub(1));
+    }
+
+    pub fn page_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(self.height.max(1));
+    }
+
+    pub fn page_down(&mut self) {
+        let last = self.visible_lines().len().saturating_sub(1);
+        self.cursor = (self.cursor + self.height.max(1)).min(last);
+    }
+
+    pub fn first(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn last(&mut self) {
+        self.cursor = self.visible_lines().len().saturating_sub(1);
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.column = self.column.saturating_sub(SCROLL_STEP);
+    }
+
+    pub fn scroll_right(&mut self) {
+        self.column = self.column.saturating_add(SCROLL_STEP);
+    }
+
+    /// Folds or unfolds the object or array under the cursor.
+    pub fn toggle_fold(&mut self) {
+        let lines = self.visible_lines();
+        let pointer = match lines.get(self.cursor) {
+            Some(line) if matches!(line.node, Node::Open(..) | Node::Close(_)) => {
+                line.pointer.clone()
+            }
+            _ => return,
+        };
+
+        if !self.folded.remove(&pointer) {
+            self.folded.insert(pointer.clone());
+        }
+
+        self.select(pointer.as_str());
+    }
+
+    /// Moves the cursor to the opening line of `pointer`.
+    fn select(&mut self, pointer: &str) {
+        if let Some(idx) = self
+            .visible_lines()
+            .iter()
+            .position(|line| line.pointer == pointer && !matches!(line.node, Node::Close(_)))
+        {
+            self.cursor = idx;
+        }
+    }
+
+    pub fn start_search(&mut self) {
+        self.search = Some(LineInput::default());
+    }
+
+    pub fn cancel_search(&mut self) {
+        self.search = None;
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode) {
+        if let Some(input) = self.search.as_mut() {
+            input.on_key_pressed(key);
+        }
+    }
+
+    /// Searches keys and values, case insensitively, then goes to the first match. An empty
+    /// search clears the previous one.
+    pub fn submit_search(&mut self) {
+        let query = match self.search.take() {
+            Some(input) => input.value().trim().to_string(),
+            None => return,
+        };
+
+        if query.is_empty() {
+            self.query = None;
+            self.matches.clear();
+            return;
+        }
+
+        self.query = Some(query);
+        self.update_matches();
+
+        if let Some(first) = self.matches.first().cloned() {
+            self.reveal(first.as_str());
+        }
+    }
+
+    fn update_matches(&mut self) {
+        let query = match self.query.as_ref() {
+            Some(query) => query.to_lowercase(),
+            None => return,
+        };
+
+        self.matches = self
+            .all_lines()
+            .into_iter()
+            .filter(|line| !matches!(line.node, Node::Close(_)))
+            .filter(|line| line_matches(line, query.as_str()))
+            .map(|line| line.pointer)
+            .collect();
+    }
+
+    pub fn next_match(&mut self) {
+        self.jump_to_match(true);
+    }
+
+    pub fn previous_match(&mut self) {
+        self.jump_to_match(false);
+    }
+
+    fn jump_to_match(&mut self, forwards: bool) {
+        if self.matches.is_empty() {
+            return;
+        }
+
+        // Where the cursor is in the unfolded document.
+        let current = self
+            .visible_lines()
+            .get(self.cursor)
+            .map(|line| line.pointer.clone())
+            .unwrap_or_default();
+        let order = self
+            .all_lines()
+            .into_iter()
+            .filter(|line| !matches!(line.node, Node::Close(_)))
+            .map(|line| line.pointer)
+            .collect::<Vec<_>>();
+        let position = |pointer: &str| order.iter().position(|p| p == pointer);
+        let current = position(current.as_str()).unwrap_or_default();
+
+        let target = if forwards {
+            self.matches
+                .iter()
+                .find(|m| matches!(position(m.as_str()), Some(p) if p > current))
+                .or_else(|| self.matches.first())
+        } else {
+            self.matches
+                .iter()
+                .rev()
+                .find(|m| matches!(position(m.as_str()), Some(p) if p < current))
+                .or_else(|| self.matches.last())
+        };
+
+        if let Some(target) = target.cloned() {
+            self.reveal(target.as_str());
+        }
+    }
+
+    /// Unfolds the ancestors of `pointer` and moves the cursor to it.
+    fn reveal(&mut self, pointer: &str) {
+        let mut end = pointer.len();
+
+        while let Some(idx) = pointer[..end].rfind('/') {
+            self.folded.remove(&pointer[..idx]);
+            end = idx;
+        }
+
+        self.select(pointer);
+    }
+
+    pub fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect, block: Block) {
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let show_bar = self.search.is_some() || self.query.is_some();
+        let rects = Layout::default()
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(if show_bar { 1 } else { 0 }),
+            ])
+            .split(inner);
+
+        let lines = lines(&self.value, Some(&self.folded));
+        let height = rects[0].height as usize;
+
+        self.height = height;
+        self.cursor = self.cursor.min(lines.len().saturating_sub(1));
+
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if height > 0 && self.cursor >= self.offset + height {
+            self.offset = self.cursor + 1 - height;
+        }
+
+        self.offset = self.offset.min(lines.len().saturating_sub(height));
+
+        let query = self.query.as_ref().map(|q| q.to_lowercase());
+        let spans = lines
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(height)
+            .map(|(idx, line)| {
+                let spans = render_line(ctx, line, query.as_deref());
+
+                if idx == self.cursor {
+                    let content = spans.0.into_iter().map(|span| {
+                        Span::styled(span.content, span.style.patch(ctx.theme.selected))
+                    });
+
+                    Spans::from(content.collect::<Vec<_>>())
+                } else {
+                    spans
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let widest = spans.iter().map(Spans::width).max().unwrap_or_default() as u16;
+        self.column = self
+            .column
+            .min(widest.saturating_sub(rects[0].width.saturating_sub(SCROLL_STEP)));
+
+        let paragraph = Paragraph::new(spans)
+            .style(ctx.theme.text)
+            .scroll((0, self.column));
+
+        frame.render_widget(paragraph, rects[0]);
+
+        if let Some(input) = self.search.as_ref() {
+            input.draw(ctx, frame, rects[1], "Search: ");
+        } else if let Some(query) = self.query.as_ref() {
+            let current = lines.get(self.cursor).map(|line| line.pointer.as_str());
+            let status = match self
+                .matches
+                .iter()
+                .position(|m| Some(m.as_str()) == current)
+            {
+                _ if self.matches.is_empty() => format!("No match for '{}'", query),
+                Some(idx) => format!(
+                    "Match {} of {} for '{}'",
+                    idx + 1,
+                    self.matches.len(),
+                    query
+                ),
+                None => format!("{} matches for '{}'", self.matches.len(), query),
+            };
+
+            frame.render_widget(Paragraph::new(status).style(ctx.theme.label), rects[1]);
+        }
+    }
+}
+
+/// Visible lines of `value`, every line when `folded` is `None`.
+fn lines<'a>(value: &'a Value, folded: Option<&HashSet<String>>) -> Vec<Line<'a>> {
+    let mut lines = Vec::new();
+
+    flatten(value, None, String::new(), 0, true, folded, &mut lines);
+
+    lines
+}
+
+fn flatten<'a>(
+    value: &'a Value,
+    key: Option<&'a str>,
+    pointer: String,
+    depth: usize,
+    last: bool,
+    folded: Option<&HashSet<String>>,
+    lines: &mut Vec<Line<'a>>,
+) {
+    let children: Vec<(Option<&'a str>, &'a Value)> = match value {
+        Value::Object(map) if !map.is_empty() => {
+            map.iter().map(|(k, v)| (Some(k.as_str()), v)).collect()
+        }
+        Value::Array(values) if !values.is_empty() => values.iter().map(|v| (None, v)).collect(),
+        value => {
+            lines.push(Line {
+                depth,
+                key,
+                pointer,
+                node: Node::Scalar(value),
+                comma: !last,
+            });
+
+            return;
+        }
+    };
+
+    let is_folded = matches!(folded, Some(folded) if folded.contains(&pointer));
+
+    lines.push(Line {
+        depth,
+        key,
+        pointer: pointer.clone(),
+        node: Node::Open(value, is_folded),
+        comma: is_folded && !last,
+    });
+
+    if is_folded {
+        return;
+    }
+
+    let count = children.len();
+
+    for (idx, (child_key, child)) in children.into_iter().enumerate() {
+        let segment = match child_key {
+            Some(name) => name.replace('~', "~0").replace('/', "~1"),
+            None => idx.to_string(),
+        };
+
+        flatten(
+            child,
+            child_key,
+            format!("{}/{}", pointer, segment),
+            depth + 1,
+            idx + 1 == count,
+            folded,
+            lines,
+        );
+    }
+
+    let close = if value.is_array() { ']' } else { '}' };
+
+    lines.push(Line {
+        depth,
+        key: None,
+        pointer,
+        node: Node::Close(close),
+        comma: !last,
+    });
+}
+
+fn contains(text: &str, query: &str) -> bool {
+    text.to_lowercase().contains(query)
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// `query` is lowercase.
+fn line_matches(line: &Line, query: &str) -> bool {
+    if matches!(line.key, Some(key) if contains(key, query)) {
+        return true;
+    }
+
+    match line.node {
+        Node::Scalar(value) => contains(scalar_text(value).as_str(), query),
+        _ => false,
+    }
+}
+
+/// Keys and values containing `query` are highlighted.
+fn render_line<'a>(ctx: ViewCtx, line: &Line, query: Option<&str>) -> Spans<'a> {
+    let theme = ctx.theme;
+    let highlight = |text: &str, style: Style| match query {
+        Some(query) if contains(text, query) => theme.highlight.add_modifier(Modifier::UNDERLINED),
+        _ => style,
+    };
+    let marker = match line.node {
+        Node::Open(_, true) => "▸ ",
+        Node::Open(_, false) => "▾ ",
+        _ => "  ",
+    };
+
+    let mut spans = vec![Span::raw(format!("{}{}", "  ".repeat(line.depth), marker))];
+
+    if let Some(key) = line.key {
+        let style = highlight(key, theme.json_key);
+        spans.push(Span::styled(Value::from(key).to_string(), style));
+        spans.push(Span::raw(": "));
+    }
+
+    match line.node {
+        Node::Open(value, folded) => {
+            let (open, close, count, unit) = match value {
+                Value::Array(values) => ('[', ']', values.len(), "item"),
+                Value::Object(map) => ('{', '}', map.len(), "key"),
+                _ => unreachable!("only objects and arrays are opened"),
+            };
+
+            if folded {
+                spans.push(Span::raw(format!("{}…{}", open, close)));
+                if line.comma {
+                    spans.push(Span::raw(","));
+                }
+                let plural = if count == 1 { "" } else { "s" };
+                let summary = format!(" {} {}{}", count, unit, plural);
+                spans.push(Span::styled(summary, theme.label));
+
+                return Spans::from(spans);
+            }
+
+            spans.push(Span::raw(open.to_string()));
+        }
+        Node::Close(close) => spans.push(Span::raw(close.to_string())),
+        Node::Scalar(value) => {
+            let style = match value {
+                Value::String(_) => theme.json_string,
+                Value::Number(_) => theme.json_number,
+                Value::Bool(_) | Value::Null => theme.json_literal,
+                _ => theme.text,
+            };
+
+            let style = highlight(scalar_text(value).as_str(), style);
+
+            spans.push(Span::styled(value.to_string(), style));
+        }
+    }
+
+    if line.comma {
+        spans.push(Span::raw(","));
+    }
+
+    Spans::from(spans)
+}
@@ -0,0 +1,320 @@
+use crate::config::{Bindings, KeysConfig};
+use crate::views::{Tab, TABS};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Everything a key can be bound to. Views list the actions they support in their current stage,
+/// which is also what the footer displays.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Action {
+    NextTab,
+    PreviousTab,
+    Connect,
+    Quit,
+    Retry,
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Back,
+    Search,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    Jump,
+    Tail,
+    Pause,
+    Metadata,
+    Edit,
+    Append,
+    Delete,
+    Tombstone,
+    Filter,
+    Export,
+    Import,
+    Fold,
+    ScrollLeft,
+    ScrollRight,
+    NextMatch,
+    PreviousMatch,
+    Mark,
+    Diff,
+    FollowLink,
+    Submit,
+    Command,
+    Help,
+}
+
+// Names used in the configuration file.
+static ACTION_NAMES: &[(Action, &str)] = &[
+    (Action::NextTab, "next-tab"),
+    (Action::PreviousTab, "previous-tab"),
+    (Action::Connect, "connect"),
+    (Action::Quit, "quit"),
+    (Action::Retry, "retry"),
+    (Action::Up, "up"),
+    (Action::Down, "down"),
+    (Action::Left, "left"),
+    (Action::Right, "right"),
+    (Action::Select, "select"),
+    (Action::Back, "back"),
+    (Action::Search, "search"),
+    (Action::PageUp, "page-up"),
+    (Action::PageDown, "page-down"),
+    (Action::First, "first"),
+    (Action::Last, "last"),
+    (Action::Jump, "jump"),
+    (Action::Tail, "tail"),
+    (Action::Pause, "pause"),
+    (Action::Metadata, "metadata"),
+    (Action::Edit, "edit"),
+    (Action::Append, "append"),
+    (Action::Delete, "delete"),
+    (Action::Tombstone, "tombstone"),
+    (Action::Filter, "filter"),
+    (Action::Export, "export"),
+    (Action::Import, "import"),
+    (Action::Fold, "fold"),
+    (Action::ScrollLeft, "scroll-left"),
+    (Action::ScrollRight, "scroll-right"),
+    (Action::NextMatch, "next-match"),
+    (Action::PreviousMatch, "previous-match"),
+    (Action::Mark, "mark"),
+    (Action::Diff, "diff"),
+    (Action::FollowLink, "follow-link"),
+    (Action::Submit, "submit"),
+    (Action::Command, "command"),
+    (Action::Help, "help"),
+];
+
+static DEFAULT_BINDINGS: &[(Action, &[&str])] = &[
+    (Action::NextTab, &["Tab"]),
+    (Action::PreviousTab, &["BackTab"]),
+    (Action::Connect, &["C-o"]),
+    (Action::Quit, &["q"]),
+    (Action::Retry, &["r"]),
+    (Action::Up, &["Up"]),
+    (Action::Down, &["Down"]),
+    (Action::Left, &["Left"]),
+    (Action::Right, &["Right"]),
+    (Action::Select, &["Enter"]),
+    (Action::Back, &["Esc"]),
+    (Action::Search, &["/"]),
+    (Action::PageUp, &["PageUp"]),
+    (Action::PageDown, &["PageDown"]),
+    (Action::First, &["Home"]),
+    (Action::Last, &["End"]),
+    (Action::Jump, &["g"]),
+    (Action::Tail, &["t"]),
+    (Action::Pause, &["p"]),
+    (Action::Metadata, &["m"]),
+    (Action::Edit, &["e"]),
+    (Action::Append, &["a"]),
+    (Action::Delete, &["Delete"]),
+    (Action::Tombstone, &["S-Delete"]),
+    (Action::Filter, &["f"]),
+    (Action::Export, &["x"]),
+    (Action::Import, &["i"]),
+    (Action::Fold, &["Space"]),
+    (Action::ScrollLeft, &["<"]),
+    (Action::ScrollRight, &[">"]),
+    (Action::NextMatch, &["n"]),
+    (Action::PreviousMatch, &["N"]),
+    (Action::Mark, &["Space"]),
+    (Action::Diff, &["d"]),
+    (Action::FollowLink, &["l"]),
+    (Action::Submit, &["C-s"]),
+    (Action::Command, &[":"]),
+    (Action::Help, &["?"]),
+];
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Self> {
+        ACTION_NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(action, _)| *action)
+    }
+}
+
+/// A key along with its modifiers, like `C-o`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+
+        // Shift is already part of those keys.
+        if let KeyCode::Char(_) | KeyCode::BackTab = code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+
+        Self { code, modifiers }
+    }
+
+    /// Parses chords like `q`, `Enter`, `C-o` or `A-Left`. Modifiers are `C-` (control), `A-`
+    /// (alt) and `S-` (shift).
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = input;
+
+        loop {
+            let modifier = match rest.get(..2) {
+                Some("C-") => KeyModifiers::CONTROL,
+                Some("A-") => KeyModifiers::ALT,
+                Some("S-") => KeyModifiers::SHIFT,
+                _ => break,
+            };
+
+            if rest.len() == 2 {
+                break;
+            }
+
+            modifiers |= modifier;
+            rest = &rest[2..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => match c {
+                '↑' => KeyCode::Up,
+                '↓' => KeyCode::Down,
+                '←' => KeyCode::Left,
+                '→' => KeyCode::Right,
+                c if modifiers.contains(KeyModifiers::SHIFT) => {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                }
+                c => KeyCode::Char(c),
+            },
+
+            _ => match rest.to_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("Unknown key '{}'", input)),
+                },
+            },
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "C-")?;
+        }
+
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "A-")?;
+        }
+
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "S-")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::BackTab => write!(f, "S-Tab"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Key chords bound to each action. Bindings of a view take precedence over the global ones.
+pub struct Keymap {
+    global: Bindings,
+    views: HashMap<Tab, Bindings>,
+}
+
+impl Keymap {
+    pub fn new(config: &KeysConfig) -> Self {
+        let mut global = DEFAULT_BINDINGS
+            .iter()
+            .map(|(action, chords)| {
+                let chords = chords
+                    .iter()
+                    .map(|chord| KeyChord::parse(chord).expect("valid default binding"))
+                    .collect();
+
+                (*action, chords)
+            })
+            .collect::<Bindings>();
+
+        global.extend(config.global.clone());
+
+        let views = TABS
+            .iter()
+            .map(|tab| (*tab, config.view(*tab).clone()))
+            .collect();
+
+        Self { global, views }
+    }
+
+    pub fn chords(&self, tab: Tab, action: Action) -> &[KeyChord] {
+        self.views
+            .get(&tab)
+            .and_then(|bindings| bindings.get(&action))
+            .or_else(|| self.global.get(&action))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// First of `actions` bound to `chord`.
+    pub fn resolve<I>(&self, tab: Tab, chord: KeyChord, actions: I) -> Option<Action>
+    where
+        I: IntoIterator<Item = Action>,
+    {
+        actions
+            .into_iter()
+            .find(|action| self.chords(tab, *action).contains(&chord))
+    }
+
+    /// How the chords bound to `action` are displayed, like `q/C-c`.
+    pub fn label(&self, tab: Tab, action: Action) -> String {
+        self.chords(tab, action)
+            .iter()
+            .map(|chord| chord.to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
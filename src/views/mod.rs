@@ -1,23 +1,59 @@
+use crate::backend::{
+    is_connection_error, Backend, EsdbBackend, ProjectionCommand, UnavailableBackend,
+};
+use crate::config::{Config, Connection};
+use crate::views::command::{Command, CommandLine, CommandRequest, Completions};
+use crate::views::connections::{ConnectionSwitcher, SwitcherRequest};
+use crate::views::help::{HelpOverlay, HelpRequest, HelpSection};
+use crate::views::keymap::{Action, KeyChord, Keymap};
+use crate::views::theme::Theme;
+use chrono::{DateTime, Local};
 use crossterm::event::{KeyCode, KeyEvent};
-use eventstore::ClientSettings;
-use std::collections::HashMap;
+use eventstore::{ClientSettings, PersistentSubscriptionInfo, RevisionOrPosition};
+use eventstore_extras::stats::Statistics;
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use std::future::Future;
 use std::io;
-use std::io::Stdout;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::runtime::{Handle, Runtime};
-use tui::backend::CrosstermBackend;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans};
-use tui::widgets::{Block, Borders, Clear, Paragraph, Tabs};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Tabs, Wrap};
 use tui::Frame;
 
+mod all_filter;
+mod append;
+mod command;
+mod connections;
 pub mod dashboard;
+mod delete;
+mod event_diff;
+mod event_filter;
+mod export;
+mod help;
+pub mod import;
+mod input;
+mod json_tree;
+pub mod keymap;
 pub mod monitoring;
 pub mod persistent_subscriptions;
 pub mod projections;
 pub mod stream_browser;
+mod stream_metadata;
+mod stream_search;
+mod terminal;
+#[cfg(test)]
+mod tests;
+pub mod theme;
 
-pub type B = CrosstermBackend<Stdout>;
+pub use terminal::TermBackend;
+
+pub type B = TermBackend;
 
 static HEADERS: &[&'static str] = &[
     "Dashboard",
@@ -27,154 +63,728 @@ static HEADERS: &[&'static str] = &[
     "Monitoring",
 ];
 
-static KEYBINDINGS: &[(&'static str, &'static str)] = &[
-    ("TAB", "Next tab"),
-    ("B/TAB", "Previous tab"),
-    ("q", "Exit"),
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Tab {
+    Dashboard,
+    Streams,
+    Projections,
+    PersistentSubscriptions,
+    Monitoring,
+}
+
+// Same order as `HEADERS`.
+pub static TABS: &[Tab] = &[
+    Tab::Dashboard,
+    Tab::Streams,
+    Tab::Projections,
+    Tab::PersistentSubscriptions,
+    Tab::Monitoring,
 ];
 
+impl Tab {
+    pub fn index(self) -> usize {
+        TABS.iter().position(|t| *t == self).unwrap_or_default()
+    }
+
+    /// Same as in the configuration file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Tab::Dashboard => "dashboard",
+            Tab::Streams => "streams",
+            Tab::Projections => "projections",
+            Tab::PersistentSubscriptions => "persistent-subscriptions",
+            Tab::Monitoring => "monitoring",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        TABS.iter().copied().find(|tab| tab.name() == name)
+    }
+}
+
+static GLOBAL_ACTIONS: &[(Action, &'static str)] = &[
+    (Action::NextTab, "Next tab"),
+    (Action::PreviousTab, "Previous tab"),
+    (Action::Connect, "Connect"),
+    (Action::Command, "Command"),
+    (Action::Help, "Help"),
+    (Action::Quit, "Quit"),
+];
+
+// Available while the error popup is displayed.
+static FAILURE_ACTIONS: &[(Action, &'static str)] = &[
+    (Action::Retry, "Retry now"),
+    (Action::Back, "Dismiss"),
+    (Action::Quit, "Quit"),
+];
+
+// Delay before the first automatic retry, doubled after every failed attempt.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+// How long the outcome of a command stays in the title.
+const NOTICE_DURATION: Duration = Duration::from_secs(5);
+
+// Rows of the footer, the last one is kept for the global actions.
+const FOOTER_ROWS: usize = 3;
+// Spaces between two columns of the footer.
+const FOOTER_GAP: usize = 3;
+
 pub struct Context {
+    config: Config,
+    // `None` when the backend wasn't created from a connection string, like in tests.
+    connection: Option<Connection>,
+    // `false` when the client for `connection` couldn't be created yet.
+    connected: bool,
     runtime: Runtime,
     view_ctx: ViewCtx,
-    client: eventstore::Client,
-    op_client: eventstore::operations::Client,
-    proj_client: eventstore::ProjectionClient,
+    backend: Arc<dyn Backend>,
+    cluster: String,
+    switcher: Option<ConnectionSwitcher>,
+    command_line: Option<CommandLine>,
+    help: Option<HelpOverlay>,
+    completions: Completions,
+    notice: Option<(String, Instant)>,
     selected_tab: usize,
     views: Vec<Box<dyn View>>,
-    default_mappings: HashMap<String, String>,
-    last_error: Option<eventstore::Error>,
+    statuses: Vec<ViewStatus>,
+    sender: UnboundedSender<Response>,
+    receiver: UnboundedReceiver<Response>,
+    keymap: Keymap,
+    failure: Option<Failure>,
+}
+
+/// Last interaction with the cluster failed. Cleared by the next successful response.
+struct Failure {
+    error: eventstore::Error,
+    since: DateTime<Local>,
+    attempts: u32,
+    next_retry: Instant,
+    dismissed: bool,
+}
+
+impl Failure {
+    fn retry_delay(attempts: u32) -> Duration {
+        RETRY_DELAY
+            .checked_mul(1 << attempts.saturating_sub(1).min(16))
+            .unwrap_or(MAX_RETRY_DELAY)
+            .min(MAX_RETRY_DELAY)
+    }
 }
 
 #[derive(Clone)]
 pub struct Env {
     handle: Handle,
-    client: eventstore::Client,
-    op_client: eventstore::operations::Client,
-    proj_client: eventstore::ProjectionClient,
+    backend: Arc<dyn Backend>,
+    view: usize,
+    generation: u64,
+    pending: Arc<AtomicUsize>,
+    sender: UnboundedSender<Response>,
+}
+
+impl Env {
+    /// Runs `fut` in the background. Its outcome is handed back to the view through
+    /// `View::on_msg` once `Context::process_responses` picks it up.
+    pub fn spawn<F>(&self, fut: F)
+    where
+        F: Future<Output = eventstore::Result<Msg>> + Send + 'static,
+    {
+        let view = self.view;
+        let generation = self.generation;
+        let sender = self.sender.clone();
+
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        self.handle.spawn(async move {
+            let result = fut.await;
+            let _ = sender.send(Response {
+                view,
+                generation,
+                result,
+            });
+        });
+    }
+
+    /// Hands every item of `stream` to `View::on_msg` until the returned handle is dropped.
+    /// Unlike `spawn`, the view isn't considered busy while waiting for the next item.
+    pub fn spawn_stream<S>(&self, stream: S) -> TaskHandle
+    where
+        S: Stream<Item = Msg> + Send + 'static,
+    {
+        let view = self.view;
+        let generation = self.generation;
+        let pending = self.pending.clone();
+        let sender = self.sender.clone();
+
+        let handle = self.handle.spawn(async move {
+            let mut stream = Box::pin(stream);
+
+            while let Some(msg) = stream.next().await {
+                pending.fetch_add(1, Ordering::SeqCst);
+
+                let resp = Response {
+                    view,
+                    generation,
+                    result: Ok(msg),
+                };
+
+                if sender.send(resp).is_err() {
+                    break;
+                }
+            }
+        });
+
+        TaskHandle(handle)
+    }
+}
+
+/// Aborts the background task when dropped.
+pub struct TaskHandle(JoinHandle<()>);
+
+impl Drop for TaskHandle {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Results produced by background tasks, one variant per view. `Notice` reports the outcome of
+/// a command and isn't handed to any view.
+pub enum Msg {
+    Dashboard(Box<Statistics>),
+    Streams(stream_browser::StreamsMsg),
+    Projections(projections::ProjectionsMsg),
+    PersistentSubscriptions(Vec<PersistentSubscriptionInfo<RevisionOrPosition>>),
+    Monitoring(monitoring::MonitoringMsg),
+    Notice(String),
+}
+
+pub struct Response {
+    view: usize,
+    generation: u64,
+    result: eventstore::Result<Msg>,
+}
+
+#[derive(Default)]
+struct ViewStatus {
+    // Bumped every time the view is unloaded so late responses get discarded.
+    generation: u64,
+    pending: Arc<AtomicUsize>,
+    last_update: Option<Instant>,
 }
 
 #[derive(Copy, Clone)]
 pub struct ViewCtx {
-    selected_style: Style,
-    normal_style: Style,
+    theme: Theme,
 }
 
 impl Context {
-    pub fn new(setts: ClientSettings) -> io::Result<Self> {
+    pub fn new(connection: Connection, config: Config) -> io::Result<Self> {
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .build()?;
 
-        let (client, op_client, proj_client) = runtime
-            .block_on(async move {
-                let proj_client = eventstore::ProjectionClient::new(setts.clone());
-                let client = eventstore::Client::new(setts)?;
-                let op_client = eventstore::operations::Client::from(client.clone());
+        let setts = parse_settings(&connection)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
 
-                Ok::<_, eventstore::Error>((client, op_client, proj_client))
-            })
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        // The cluster being down must not prevent the application from starting, the client
+        // creation is retried like any other failure.
+        let (backend, error) = match create_backend(&runtime, setts) {
+            Ok(backend) => (backend, None),
+            Err(e) => {
+                let backend: Arc<dyn Backend> = Arc::new(UnavailableBackend::new(e.to_string()));
+                (backend, Some(e))
+            }
+        };
 
-        let default_mappings = KEYBINDINGS
-            .iter()
-            .map(|(key, label)| (key.to_string(), label.to_string()))
-            .collect();
+        let mut ctx = Self::with_backend(runtime, backend, connection.name.clone(), config);
+        ctx.connection = Some(connection);
+
+        if let Some(e) = error {
+            ctx.connected = false;
+            ctx.record_failure(e);
+        }
+
+        Ok(ctx)
+    }
+
+    pub fn with_backend(
+        runtime: Runtime,
+        backend: Arc<dyn Backend>,
+        cluster: String,
+        config: Config,
+    ) -> Self {
+        let (sender, receiver) = unbounded_channel();
+
+        let selected_tab = config.default_tab.map(Tab::index).unwrap_or_default();
+        let keymap = Keymap::new(&config.keys);
+        let theme = config.theme().unwrap_or_default();
+        let completions = Completions::new(&config);
+        let decoders = config.decoders().unwrap_or_default();
 
-        Ok(Self {
-            default_mappings,
+        Self {
+            config,
+            connection: None,
+            connected: true,
+            keymap,
             runtime,
-            client,
-            op_client,
-            proj_client,
-            selected_tab: 0,
-            last_error: None,
+            backend,
+            cluster,
+            switcher: None,
+            command_line: None,
+            help: None,
+            completions,
+            notice: None,
+            sender,
+            receiver,
+            selected_tab,
+            failure: None,
+            statuses: HEADERS.iter().map(|_| ViewStatus::default()).collect(),
             views: vec![
                 Box::new(dashboard::DashboardView::default()),
-                Box::new(stream_browser::StreamsView::default()),
+                Box::new(stream_browser::StreamsView::new(decoders)),
                 Box::new(projections::ProjectionsViews::default()),
                 Box::new(persistent_subscriptions::PersistentSubscriptionView::default()),
                 Box::new(monitoring::MonitoringView::default()),
             ],
-            view_ctx: ViewCtx {
-                selected_style: Style::default().add_modifier(Modifier::REVERSED),
-                normal_style: Style::default().add_modifier(Modifier::REVERSED),
-            },
-        })
+            view_ctx: ViewCtx { theme },
+        }
     }
 
-    fn mk_env(&self) -> Env {
+    fn mk_env(&self, view: usize) -> Env {
+        let status = &self.statuses[view];
+
         Env {
             handle: self.runtime.handle().clone(),
-            client: self.client.clone(),
-            op_client: self.op_client.clone(),
-            proj_client: self.proj_client.clone(),
+            backend: self.backend.clone(),
+            view,
+            generation: status.generation,
+            pending: status.pending.clone(),
+            sender: self.sender.clone(),
         }
     }
 
-    pub fn on_key_pressed(&mut self, key: KeyEvent) -> Request {
-        let env = self.mk_env();
+    /// How often the selected view wants to be refreshed.
+    pub fn refresh_rate(&self) -> Duration {
+        self.config.refresh.interval(TABS[self.selected_tab])
+    }
 
-        if self.last_error.is_some() {
-            match key.code {
-                KeyCode::Char('q' | 'Q') => {
-                    return Request::Exit;
-                }
+    pub fn tick_rate(&self) -> Duration {
+        self.config.refresh.tick_rate
+    }
+
+    fn select_tab(&mut self, tab: usize) {
+        let env = self.mk_env(self.selected_tab);
+        if let Some(view) = self.views.get_mut(self.selected_tab) {
+            view.unload(&env);
+        }
+
+        let status = &mut self.statuses[self.selected_tab];
+        status.generation += 1;
+        status.last_update = None;
+
+        self.selected_tab = tab;
 
-                _ => {}
+        let env = self.mk_env(self.selected_tab);
+        if let Some(view) = self.views.get_mut(self.selected_tab) {
+            if let Err(e) = view.load(&env) {
+                self.record_failure(e);
             }
         }
+    }
 
-        match key.code {
-            KeyCode::Tab => {
-                if let Some(view) = self.views.get_mut(self.selected_tab) {
-                    view.unload(&env);
-                }
+    /// Replaces the backend with one connected to `connection`. Responses still in flight from
+    /// the previous cluster get discarded.
+    fn connect(&mut self, connection: Connection) -> Result<(), String> {
+        let setts = parse_settings(&connection)?;
+        let backend = create_backend(&self.runtime, setts).map_err(|e| e.to_string())?;
+
+        let env = self.mk_env(self.selected_tab);
+        if let Some(view) = self.views.get_mut(self.selected_tab) {
+            view.unload(&env);
+        }
+
+        for status in self.statuses.iter_mut() {
+            status.generation += 1;
+            status.last_update = None;
+        }
+
+        info!("Switching to cluster '{}'", connection.name);
+
+        self.backend = backend;
+        self.cluster = connection.name.clone();
+        self.connection = Some(connection);
+        self.connected = true;
+        self.failure = None;
+        self.completions = Completions::new(&self.config);
+        self.init();
 
-                self.selected_tab = (self.selected_tab + 1) % HEADERS.len();
+        Ok(())
+    }
 
-                if let Some(view) = self.views.get_mut(self.selected_tab) {
-                    if let Err(e) = view.load(&env) {
-                        self.last_error = Some(e);
+    pub fn on_key_pressed(&mut self, key: KeyEvent) -> Request {
+        if let Some(switcher) = self.switcher.as_mut() {
+            match switcher.on_key_pressed(key.code) {
+                SwitcherRequest::Noop => {}
+                SwitcherRequest::Close => self.switcher = None,
+                SwitcherRequest::Connect(connection) => match self.connect(connection) {
+                    Ok(_) => self.switcher = None,
+                    Err(e) => {
+                        if let Some(switcher) = self.switcher.as_mut() {
+                            switcher.set_error(e);
+                        }
                     }
-                }
+                },
             }
-            KeyCode::BackTab => {
-                if let Some(view) = self.views.get_mut(self.selected_tab) {
-                    view.unload(&env);
-                }
 
-                if self.selected_tab == 0 {
-                    self.selected_tab = HEADERS.len() - 1;
-                } else {
-                    self.selected_tab -= 1;
-                }
+            return Request::Noop;
+        }
 
-                if let Some(view) = self.views.get_mut(self.selected_tab) {
-                    if let Err(e) = view.load(&env) {
-                        self.last_error = Some(e);
+        if let Some(command_line) = self.command_line.as_mut() {
+            match command_line.on_key_pressed(key.code, &self.completions) {
+                CommandRequest::Noop => {}
+                CommandRequest::Close => self.command_line = None,
+                CommandRequest::Run(command) => match self.run_command(command) {
+                    Ok(request) => {
+                        self.command_line = None;
+                        return request;
                     }
-                }
+                    Err(e) => {
+                        if let Some(command_line) = self.command_line.as_mut() {
+                            command_line.set_error(e);
+                        }
+                    }
+                },
+            }
+
+            return Request::Noop;
+        }
+
+        if let Some(help) = self.help.as_mut() {
+            if let HelpRequest::Close = help.on_key_pressed(key.code) {
+                self.help = None;
             }
-            _ => {
-                if let Some(view) = self.views.get_mut(self.selected_tab) {
-                    return view.on_key_pressed(key.code);
+
+            return Request::Noop;
+        }
+
+        let tab = TABS[self.selected_tab];
+        let chord = KeyChord::from(key);
+
+        if self.failure.as_ref().filter(|f| !f.dismissed).is_some() {
+            let actions = FAILURE_ACTIONS.iter().map(|(action, _)| *action);
+            let action = self.keymap.resolve(tab, chord, actions);
+
+            match action {
+                Some(Action::Quit) => return Request::Exit,
+                Some(Action::Retry) => {
+                    self.retry();
+                    return Request::Noop;
                 }
+                Some(_) => {
+                    if let Some(failure) = self.failure.as_mut() {
+                        failure.dismissed = true;
+                    }
+
+                    return Request::Noop;
+                }
+                None => {}
+            }
+        }
+
+        let view = match self.views.get_mut(self.selected_tab) {
+            Some(view) => view,
+            None => return Request::Noop,
+        };
+
+        let globals = if view.captures_input() {
+            &[]
+        } else {
+            GLOBAL_ACTIONS
+        };
+
+        let actions = view
+            .actions()
+            .iter()
+            .chain(globals.iter())
+            .map(|(action, _)| *action);
+
+        let action = match self.keymap.resolve(tab, chord, actions) {
+            Some(action) => action,
+            // Text inputs get every other key as is.
+            None if view.captures_input() => return view.on_key_pressed(key.code),
+            None => return Request::Noop,
+        };
+
+        if view.actions().iter().any(|(a, _)| *a == action) {
+            return view.on_action(action);
+        }
+
+        match action {
+            Action::NextTab => self.select_tab((self.selected_tab + 1) % HEADERS.len()),
+            Action::PreviousTab => {
+                self.select_tab((self.selected_tab + HEADERS.len() - 1) % HEADERS.len())
             }
+            Action::Connect => {
+                self.switcher = Some(ConnectionSwitcher::new(&self.config, &self.cluster));
+            }
+            Action::Command => self.command_line = Some(CommandLine::default()),
+            Action::Help => self.help = Some(HelpOverlay::default()),
+            Action::Quit => return Request::Exit,
+            _ => {}
         }
 
         Request::Noop
     }
 
+    fn run_command(&mut self, command: Command) -> Result<Request, String> {
+        let tab = TABS[self.selected_tab];
+
+        match command {
+            Command::Stream(name) => Ok(self.open(Tab::Streams, name.as_str())),
+            Command::Subscription(name) => {
+                Ok(self.open(Tab::PersistentSubscriptions, name.as_str()))
+            }
+
+            Command::Projection(command, name) => {
+                let env = self.mk_env(self.selected_tab);
+                let backend = env.backend.clone();
+
+                // A failed command is reported like its outcome, the cluster itself is fine.
+                env.spawn(async move {
+                    let (verb, outcome) = match command {
+                        ProjectionCommand::Enable => ("enable", "enabled"),
+                        ProjectionCommand::Disable => ("disable", "disabled"),
+                        ProjectionCommand::Reset => ("reset", "reset"),
+                        ProjectionCommand::Abort => ("abort", "aborted"),
+                    };
+
+                    let notice = match backend.update_projection(name.clone(), command).await {
+                        Ok(()) => format!("Projection '{}' {}", name, outcome),
+                        Err(e) => format!("Couldn't {} projection '{}': {}", verb, name, e),
+                    };
+
+                    Ok(Msg::Notice(notice))
+                });
+
+                Ok(Request::Noop)
+            }
+
+            Command::Tab(tab) => {
+                if tab.index() != self.selected_tab {
+                    self.select_tab(tab.index());
+                }
+
+                Ok(Request::Noop)
+            }
+
+            Command::Refresh(None) => Ok(Request::Refresh),
+
+            Command::Refresh(Some(interval)) => {
+                self.config.refresh.set_interval(tab, interval);
+                self.notice = Some((format!("Refreshing every {:?}", interval), Instant::now()));
+
+                Ok(Request::Noop)
+            }
+
+            Command::Connect(name) => {
+                let connection = if name.contains("://") {
+                    Connection::ad_hoc(name)
+                } else {
+                    self.config
+                        .connection(Some(name.as_str()))
+                        .map_err(|e| e.to_string())?
+                };
+
+                self.connect(connection)?;
+
+                Ok(Request::Noop)
+            }
+
+            Command::Quit => Ok(Request::Exit),
+        }
+    }
+
+    /// Switches to `tab` and shows the item named `name` there.
+    fn open(&mut self, tab: Tab, name: &str) -> Request {
+        if tab.index() != self.selected_tab {
+            self.select_tab(tab.index());
+        }
+
+        match self.views.get_mut(self.selected_tab) {
+            Some(view) => view.open(name),
+            None => Request::Noop,
+        }
+    }
+
     pub fn refresh(&mut self) {
-        let env = self.mk_env();
+        let env = self.mk_env(self.selected_tab);
         if let Some(view) = self.views.get_mut(self.selected_tab) {
             if let Err(e) = view.refresh(&env) {
-                self.last_error = Some(e);
+                self.record_failure(e);
+            }
+        }
+    }
+
+    fn is_view_idle(&self) -> bool {
+        self.statuses[self.selected_tab]
+            .pending
+            .load(Ordering::SeqCst)
+            == 0
+    }
+
+    /// Periodic refresh. Skipped while the previous one is still running so a slow cluster
+    /// doesn't pile up requests, and while failing since retries have their own schedule.
+    pub fn refresh_if_idle(&mut self) {
+        if self.failure.is_none() && self.is_view_idle() {
+            self.refresh();
+        }
+    }
+
+    /// Retries once the backoff delay of the current failure is over.
+    pub fn on_tick(&mut self) {
+        let due = match self.failure.as_ref() {
+            Some(failure) => Instant::now() >= failure.next_retry,
+            None => false,
+        };
+
+        if due && self.is_view_idle() {
+            self.retry();
+        }
+    }
+
+    fn retry(&mut self) {
+        if let Some(failure) = self.failure.as_mut() {
+            // Prevents `on_tick` from retrying again before this attempt completes.
+            failure.next_retry = Instant::now() + Failure::retry_delay(failure.attempts + 1);
+        }
+
+        if !self.connected {
+            if let Some(connection) = self.connection.clone() {
+                let backend = parse_settings(&connection)
+                    .map_err(eventstore::Error::InitializationError)
+                    .and_then(|setts| create_backend(&self.runtime, setts));
+
+                match backend {
+                    Err(e) => {
+                        self.record_failure(e);
+                        return;
+                    }
+
+                    Ok(backend) => {
+                        self.backend = backend;
+                        self.connected = true;
+                    }
+                }
+            }
+        }
+
+        if self.statuses[self.selected_tab].last_update.is_none() {
+            self.init();
+        } else {
+            self.refresh();
+        }
+    }
+
+    fn record_failure(&mut self, error: eventstore::Error) {
+        warn!("Request to '{}' failed: {}", self.cluster, error);
+
+        match self.failure.as_mut() {
+            Some(failure) => {
+                failure.attempts += 1;
+                failure.next_retry = Instant::now() + Failure::retry_delay(failure.attempts);
+                failure.error = error;
+            }
+
+            None => {
+                self.failure = Some(Failure {
+                    error,
+                    since: Local::now(),
+                    attempts: 1,
+                    next_retry: Instant::now() + Failure::retry_delay(1),
+                    dismissed: false,
+                });
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub fn is_idle(&self) -> bool {
+        self.statuses
+            .iter()
+            .all(|status| status.pending.load(Ordering::SeqCst) == 0)
+    }
+
+    /// Applies whatever background tasks have completed since the last call.
+    pub fn process_responses(&mut self) {
+        while let Ok(resp) = self.receiver.try_recv() {
+            let status = &mut self.statuses[resp.view];
+            status.pending.fetch_sub(1, Ordering::SeqCst);
+
+            // Outcomes of commands are reported even when the view was reloaded meanwhile.
+            let is_notice = matches!(resp.result, Ok(Msg::Notice(_)));
+
+            if status.generation != resp.generation && !is_notice {
+                continue;
+            }
+
+            match resp.result {
+                Err(e) => self.record_failure(e),
+                Ok(Msg::Notice(notice)) => self.notice = Some((notice, Instant::now())),
+                Ok(msg) => {
+                    status.last_update = Some(Instant::now());
+                    self.failure = None;
+                    self.completions.observe(&msg);
+
+                    if let Some(view) = self.views.get_mut(resp.view) {
+                        view.on_msg(msg);
+                    }
+                }
             }
         }
     }
 
+    fn status_label(&self) -> String {
+        if let Some(failure) = self.failure.as_ref() {
+            let state = if is_connection_error(&failure.error) {
+                "Disconnected"
+            } else {
+                "Failing"
+            };
+
+            return format!("{} since {} | ", state, failure.since.format("%H:%M:%S"));
+        }
+
+        if let Some((notice, time)) = self.notice.as_ref() {
+            if time.elapsed() < NOTICE_DURATION {
+                return format!("{} | ", notice);
+            }
+        }
+
+        if self.views[self.selected_tab].is_live() {
+            return "Live | ".to_string();
+        }
+
+        let status = &self.statuses[self.selected_tab];
+
+        if status.pending.load(Ordering::SeqCst) > 0 {
+            if status.last_update.is_none() {
+                return "Loading… | ".to_string();
+            }
+
+            return "Refreshing… | ".to_string();
+        }
+
+        // A view that missed two refreshes in a row gets flagged as stale.
+        match status.last_update {
+            Some(time) if time.elapsed() >= self.refresh_rate() * 2 => {
+                format!("Stale ({}s) | ", time.elapsed().as_secs())
+            }
+            _ => String::new(),
+        }
+    }
+
     pub fn draw(&mut self, frame: &mut Frame<B>) {
+        let theme = self.view_ctx.theme;
         let rects = Layout::default()
             .constraints([Constraint::Min(10), Constraint::Length(5)])
             .vertical_margin(0)
@@ -183,119 +793,203 @@ impl Context {
 
         let titles = HEADERS
             .iter()
-            .map(|t| {
-                Spans::from(vec![Span::styled(
-                    *t,
-                    Style::default().fg(Color::LightGreen),
-                )])
-            })
+            .map(|t| Spans::from(vec![Span::styled(*t, theme.tab)]))
             .collect();
 
         let tabs = Tabs::new(titles)
             .block(
                 Block::default()
                     .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT | Borders::BOTTOM)
-                    .style(Style::default().bg(Color::DarkGray))
-                    .title("EventStoreDB Administration Tool")
+                    .style(theme.panel)
+                    .title(format!(
+                        "{}{} | EventStoreDB Administration Tool",
+                        self.status_label(),
+                        self.cluster
+                    ))
                     .title_alignment(Alignment::Right),
             )
             .select(self.selected_tab)
-            .style(Style::default().fg(Color::Gray))
-            .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+            .style(theme.text)
+            .highlight_style(theme.selected_tab);
 
         frame.render_widget(tabs, rects[0]);
 
-        let mut mappings = self.default_mappings.clone();
+        let tab = TABS[self.selected_tab];
+        let mut mappings = Vec::new();
+        let mut global_mappings = Vec::new();
 
         if let Some(view) = self.views.get_mut(self.selected_tab) {
             view.draw(self.view_ctx, frame, rects[0]);
 
-            for (key, value) in view.keybindings() {
-                mappings.insert(key.to_string(), value.to_string());
-            }
-        }
-
-        let max_key = mappings
-            .keys()
-            .map(|k| k.chars().count())
-            .max()
-            .unwrap_or_default();
-
-        let mut parts = vec![Vec::new(), Vec::new(), Vec::new()];
-        let mut count = 0usize;
-
-        let mut sorted_mappings = mappings.into_iter().collect::<Vec<_>>();
-        sorted_mappings.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()));
+            let globals = if view.captures_input() {
+                &[]
+            } else {
+                GLOBAL_ACTIONS
+            };
 
-        for (mut key, mut label) in sorted_mappings {
-            let key_count = key.chars().count();
-            let idx = count % 3;
+            // Generated from the keymap so it can't disagree with what the keys do.
+            let label = |actions: &[(Action, &str)]| {
+                actions
+                    .iter()
+                    .map(|(action, label)| (self.keymap.label(tab, *action), label.to_string()))
+                    .filter(|(key, _)| !key.is_empty())
+                    .collect::<Vec<_>>()
+            };
 
-            if key_count < max_key {
-                for _ in 0..max_key - key_count {
-                    key.insert(0, ' ');
-                }
-            }
+            mappings = label(view.actions());
+            global_mappings = label(globals);
+        }
 
-            let key_count = key.chars().count();
-            let label_count = label.chars().count();
+        // Global actions, `Help` among them, get a row of their own so they're always listed.
+        let width = rects[1].width.saturating_sub(2) as usize;
+        let help = self.keymap.label(tab, Action::Help);
+        let rows = if global_mappings.is_empty() {
+            FOOTER_ROWS
+        } else {
+            FOOTER_ROWS - 1
+        };
 
-            for _ in 0..(20 - (key_count + label_count)) {
-                label.push(' ');
-            }
+        let mut lines = footer_lines(theme, &mappings, rows, width, help.as_str());
 
-            parts[idx].push(Span::styled(key, Style::default().fg(Color::Green)));
-            parts[idx].push(Span::styled(
-                format!(" {}", label),
-                Style::default().fg(Color::Gray),
+        if !global_mappings.is_empty() {
+            lines.extend(footer_lines(
+                theme,
+                &global_mappings,
+                1,
+                width,
+                help.as_str(),
             ));
-
-            count += 1;
         }
 
-        let paragraph = Paragraph::new(parts.into_iter().map(|xs| Spans(xs)).collect::<Vec<_>>())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .style(Style::default().bg(Color::DarkGray)),
-            )
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).style(theme.panel))
             .alignment(Alignment::Left);
 
         frame.render_widget(paragraph, rects[1]);
 
-        if let Some(e) = self.last_error.as_ref() {
+        if let Some(failure) = self.failure.as_ref().filter(|f| !f.dismissed) {
             let block = Block::default()
-                .title("Panic")
+                .title("Error")
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL)
-                .style(Style::default().bg(Color::Black).fg(Color::Red));
-            let area = centered_rect(40, 15, frame.size());
+                .style(theme.error);
+            let area = centered_rect(50, 30, frame.size());
             frame.render_widget(Clear, area);
             frame.render_widget(block, area);
 
-            let rect = Layout::default()
+            let rects = Layout::default()
                 .margin(2)
-                .constraints([Constraint::Percentage(100)])
-                .direction(Direction::Horizontal)
-                .split(area)[0];
+                .constraints([Constraint::Min(1), Constraint::Length(3)])
+                .direction(Direction::Vertical)
+                .split(area);
+
+            let label = Paragraph::new(failure.error.to_string())
+                .style(theme.text)
+                .wrap(Wrap { trim: true });
+
+            frame.render_widget(label, rects[0]);
+
+            let next_retry = failure
+                .next_retry
+                .saturating_duration_since(Instant::now())
+                .as_secs();
 
-            let label = Paragraph::new(e.to_string()).style(Style::default().fg(Color::Gray));
+            let actions = FAILURE_ACTIONS
+                .iter()
+                .map(|(action, label)| format!("{}: {}", self.keymap.label(tab, *action), label))
+                .collect::<Vec<_>>();
 
-            frame.render_widget(label, rect);
+            let help = Paragraph::new(vec![
+                Spans::from(format!(
+                    "Retrying in {}s (attempt {})",
+                    next_retry,
+                    failure.attempts + 1
+                )),
+                Spans::from(""),
+                Spans::from(actions.join(" | ")),
+            ])
+            .style(theme.text);
+
+            frame.render_widget(help, rects[1]);
+        }
+
+        if let Some(command_line) = self.command_line.as_ref() {
+            command_line.draw(self.view_ctx, frame, &self.completions);
+        }
+
+        if self.help.is_some() {
+            let sections = self.help_sections();
+
+            if let Some(help) = self.help.as_mut() {
+                help.draw(self.view_ctx, frame, &sections);
+            }
+        }
+
+        if let Some(switcher) = self.switcher.as_mut() {
+            switcher.draw(self.view_ctx, frame);
         }
     }
 
-    pub fn init(&mut self) {
-        if let Some(view) = self.views.get_mut(self.selected_tab) {
-            let env = Env {
-                handle: self.runtime.handle().clone(),
-                client: self.client.clone(),
-                op_client: self.op_client.clone(),
-                proj_client: self.proj_client.clone(),
+    /// Bindings of every stage of every view, starting with the current one, followed by the
+    /// global ones. Built from the same actions as the footer.
+    fn help_sections(&self) -> Vec<HelpSection> {
+        let current = (self.selected_tab, self.views[self.selected_tab].stage());
+        let mut stages = vec![current];
+
+        let others = (0..self.views.len()).filter(|idx| *idx != self.selected_tab);
+
+        for idx in std::iter::once(self.selected_tab).chain(others) {
+            for stage in 0..self.views[idx].stages().len() {
+                if (idx, stage) != current {
+                    stages.push((idx, stage));
+                }
+            }
+        }
+
+        let mut sections = Vec::new();
+
+        for (idx, stage) in stages {
+            let stages = self.views[idx].stages();
+            let actions = match stages.get(stage) {
+                Some(stage) => stage.actions,
+                None => continue,
             };
 
+            let mut title = if stages.len() == 1 {
+                HEADERS[idx].to_string()
+            } else {
+                format!("{} - {}", HEADERS[idx], stages[stage].name)
+            };
+
+            if (idx, stage) == current {
+                title.push_str(" (current)");
+            }
+
+            sections.push(self.help_section(TABS[idx], title, actions));
+        }
+
+        let tab = TABS[self.selected_tab];
+        sections.push(self.help_section(tab, "Global".to_string(), GLOBAL_ACTIONS));
+        sections.push(self.help_section(tab, "Error popup".to_string(), FAILURE_ACTIONS));
+
+        sections
+    }
+
+    fn help_section(&self, tab: Tab, title: String, actions: &[(Action, &str)]) -> HelpSection {
+        let bindings = actions
+            .iter()
+            .map(|(action, label)| (self.keymap.label(tab, *action), label.to_string()))
+            .filter(|(key, _)| !key.is_empty())
+            .collect();
+
+        HelpSection { title, bindings }
+    }
+
+    pub fn init(&mut self) {
+        let env = self.mk_env(self.selected_tab);
+        if let Some(view) = self.views.get_mut(self.selected_tab) {
             if let Err(e) = view.load(&env) {
-                self.last_error = Some(e);
+                self.record_failure(e);
             }
         }
     }
@@ -312,19 +1006,57 @@ pub trait View {
         Ok(())
     }
 
+    fn on_msg(&mut self, _msg: Msg) {}
+
     fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect);
 
-    fn on_key_pressed(&mut self, key: KeyCode) -> Request {
-        match key {
-            KeyCode::Char('q' | 'Q') => Request::Exit,
+    /// Every stage of the view along with its actions, as listed by the help overlay.
+    fn stages(&self) -> &'static [StageActions] {
+        &[]
+    }
 
-            _ => Request::Noop,
-        }
+    /// Index of the current stage in `stages`.
+    fn stage(&self) -> usize {
+        0
     }
 
-    fn keybindings(&self) -> &[(&str, &str)] {
-        KEYBINDINGS
+    /// Actions available in the current stage, along with their footer label.
+    fn actions(&self) -> &'static [(Action, &'static str)] {
+        self.stages()
+            .get(self.stage())
+            .map(|stage| stage.actions)
+            .unwrap_or_default()
     }
+
+    fn on_action(&mut self, _action: Action) -> Request {
+        Request::Noop
+    }
+
+    /// When `true`, keys not bound to one of `actions` are handed to `on_key_pressed` and global
+    /// actions are disabled.
+    fn captures_input(&self) -> bool {
+        false
+    }
+
+    fn on_key_pressed(&mut self, _key: KeyCode) -> Request {
+        Request::Noop
+    }
+
+    /// `true` while fed by a subscription rather than refreshed, it can't get stale then.
+    fn is_live(&self) -> bool {
+        false
+    }
+
+    /// Shows the item named `name`, like a stream, as asked from the command line.
+    fn open(&mut self, _name: &str) -> Request {
+        Request::Noop
+    }
+}
+
+/// Actions available in one stage of a view, along with their footer label.
+pub struct StageActions {
+    pub name: &'static str,
+    pub actions: &'static [(Action, &'static str)],
 }
 
 pub enum Request {
@@ -333,6 +1065,97 @@ pub enum Request {
     Exit,
 }
 
+fn parse_settings(connection: &Connection) -> Result<ClientSettings, String> {
+    ClientSettings::parse_str(connection.connection_string.as_str())
+        .map_err(|e| format!("Invalid connection string: {}", e))
+}
+
+fn create_backend(
+    runtime: &Runtime,
+    setts: ClientSettings,
+) -> eventstore::Result<Arc<dyn Backend>> {
+    // The client spawns its connection tasks, so it has to be created within the runtime.
+    let backend = runtime.block_on(async move { EsdbBackend::new(setts) })?;
+
+    Ok(Arc::new(backend))
+}
+
+/// Lays out `(key, label)` mappings in columns of `rows` entries, filled top to bottom, as many
+/// as fit in `width`. The ones that don't fit are replaced by a `<help> N more` entry.
+fn footer_lines(
+    theme: Theme,
+    mappings: &[(String, String)],
+    rows: usize,
+    width: usize,
+    help: &str,
+) -> Vec<Spans<'static>> {
+    let columns = |entries: &[(String, String)]| {
+        entries
+            .chunks(rows)
+            .map(|column| {
+                let key = column.iter().map(|(k, _)| k.chars().count()).max();
+                let label = column.iter().map(|(_, l)| l.chars().count()).max();
+
+                (
+                    column.to_vec(),
+                    key.unwrap_or_default(),
+                    label.unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let fits = |columns: &[(Vec<(String, String)>, usize, usize)]| {
+        let total = columns
+            .iter()
+            .map(|(_, key, label)| key + 1 + label + FOOTER_GAP)
+            .sum::<usize>();
+
+        total.saturating_sub(FOOTER_GAP) <= width
+    };
+
+    let mut layout = columns(mappings);
+
+    for shown in (0..mappings.len()).rev() {
+        if fits(&layout) {
+            break;
+        }
+
+        let mut entries = mappings[..shown].to_vec();
+        entries.push((help.to_string(), format!("{} more", mappings.len() - shown)));
+        layout = columns(&entries);
+    }
+
+    (0..rows)
+        .map(|row| {
+            let mut spans = Vec::new();
+
+            for (column, key_width, label_width) in layout.iter() {
+                let (key, label) = match column.get(row) {
+                    Some((key, label)) => (key.as_str(), label.as_str()),
+                    None => ("", ""),
+                };
+
+                spans.push(Span::styled(
+                    format!("{:>width$}", key, width = key_width),
+                    theme.key,
+                ));
+                spans.push(Span::styled(
+                    format!(
+                        " {:<width$}{:gap$}",
+                        label,
+                        "",
+                        width = label_width,
+                        gap = FOOTER_GAP
+                    ),
+                    theme.text,
+                ));
+            }
+
+            Spans::from(spans)
+        })
+        .collect()
+}
+
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
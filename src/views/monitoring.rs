@@ -1,11 +1,5 @@
-use std::{sync::Arc, time::Duration};
-
-use eventstore::operations::{Stats, StatsOptions};
-use eventstore_extras::stats::StatisticsExt;
-use tokio::sync::{Mutex, RwLock};
 use tui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
     symbols::Marker,
     text::{Span, Spans},
     widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph},
@@ -13,21 +7,19 @@ use tui::{
 };
 
 use crate::models::Monitoring;
+use eventstore::operations::{MemberInfo, ServerVersion};
+use eventstore_extras::stats::Statistics;
 
-use super::{Env, View, B};
+use super::{Env, Msg, View, ViewCtx, B};
 
-pub struct MonitoringView {
-    model: Monitoring,
-    stats_iter: Arc<RwLock<Option<Stats>>>,
+pub enum MonitoringMsg {
+    ServerVersion(Option<ServerVersion>),
+    Update(Vec<MemberInfo>, Box<Statistics>),
 }
 
-impl Default for MonitoringView {
-    fn default() -> Self {
-        Self {
-            model: Default::default(),
-            stats_iter: Arc::new(RwLock::new(None)),
-        }
-    }
+#[derive(Default)]
+pub struct MonitoringView {
+    model: Monitoring,
 }
 
 impl MonitoringView {
@@ -104,7 +96,7 @@ impl MonitoringView {
         frame.render_widget(paragraph, area);
     }
 
-    fn draw_env_metrics(&mut self, frame: &mut Frame<B>, area: Rect) {
+    fn draw_env_metrics(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
         let sections = Layout::default()
             .constraints([Constraint::Percentage(80), Constraint::Percentage(20)].as_ref())
             .direction(Direction::Vertical)
@@ -118,7 +110,7 @@ impl MonitoringView {
                 .data(self.model.cpu_load.as_ref())
                 .marker(Marker::Dot)
                 .graph_type(GraphType::Line)
-                .style(Style::default().fg(Color::Green)),
+                .style(ctx.theme.header),
         );
 
         let time_bounds = self.model.time_bounds();
@@ -134,18 +126,18 @@ impl MonitoringView {
                     .title_alignment(Alignment::Right)
                     .borders(Borders::NONE),
             )
-            .style(Style::default().bg(Color::DarkGray))
+            .style(ctx.theme.panel)
             .x_axis(
                 Axis::default()
                     .title("Time (secs)")
-                    .style(Style::default().fg(Color::White))
+                    .style(ctx.theme.emphasis)
                     .labels(time_labels)
                     .bounds(self.model.time_period()),
             )
             .y_axis(
                 Axis::default()
                     .title("Value")
-                    .style(Style::default().fg(Color::White))
+                    .style(ctx.theme.emphasis)
                     .labels(vec![Span::raw("0%"), Span::raw("100%")])
                     .bounds([0f64, 100f64]),
             );
@@ -164,7 +156,7 @@ impl MonitoringView {
         // frame.render_widget(legend, rects[1]);
     }
 
-    fn draw_drive_metrics(&mut self, frame: &mut Frame<B>, area: Rect) {
+    fn draw_drive_metrics(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
         if let Some(drive) = self.model.drive.as_ref() {
             let sections = Layout::default()
                 .constraints(
@@ -215,7 +207,7 @@ impl MonitoringView {
                     .data(self.model.bytes_written.as_ref())
                     .marker(Marker::Dot)
                     .graph_type(GraphType::Line)
-                    .style(Style::default().fg(Color::Green)),
+                    .style(ctx.theme.header),
             );
 
             let time_bounds = self.model.time_bounds();
@@ -231,18 +223,18 @@ impl MonitoringView {
                         .title_alignment(Alignment::Right)
                         .borders(Borders::NONE),
                 )
-                .style(Style::default().bg(Color::DarkGray))
+                .style(ctx.theme.panel)
                 .x_axis(
                     Axis::default()
                         .title("Time (secs)")
-                        .style(Style::default().fg(Color::White))
+                        .style(ctx.theme.emphasis)
                         .labels(time_labels)
                         .bounds(self.model.time_period()),
                 )
                 .y_axis(
                     Axis::default()
                         .title("Value")
-                        .style(Style::default().fg(Color::White))
+                        .style(ctx.theme.emphasis)
                         .labels(vec![
                             Span::raw(format!("{:.2}", bytes_written_bounds[0])),
                             Span::raw(format!("{:.2}", bytes_written_bounds[1])),
@@ -255,11 +247,12 @@ impl MonitoringView {
             let total = drive.stats.total_bytes as f64 / 1_073_741_824f64;
             let available = drive.stats.available_bytes as f64 / 1_073_741_824f64;
             let used = drive.stats.used_bytes as f64 / 1_073_741_824f64;
-            let mut bytes_written = if let Some((_, bytes_written)) = self.model.bytes_written.last() {
-                *bytes_written
-            } else {
-                0f64
-            };
+            let mut bytes_written =
+                if let Some((_, bytes_written)) = self.model.bytes_written.last() {
+                    *bytes_written
+                } else {
+                    0f64
+                };
 
             let mut steps = 1;
 
@@ -273,7 +266,7 @@ impl MonitoringView {
                 }
 
                 break;
-            };
+            }
 
             let mut unit = 0;
             loop {
@@ -334,47 +327,45 @@ impl MonitoringView {
 
 impl View for MonitoringView {
     fn load(&mut self, env: &Env) -> eventstore::Result<()> {
-        let client = env.op_client.clone();
+        let backend = env.backend.clone();
 
-        self.model.server_version = env
-            .handle
-            .block_on(async move {
-                let info = client.server_version().await?;
+        env.spawn(async move {
+            let version = backend.server_version().await?;
 
-                Ok(info.map(|i| i.version()))
-            })?
-            .unwrap_or_default();
+            Ok(Msg::Monitoring(MonitoringMsg::ServerVersion(version)))
+        });
 
         self.refresh(env)
     }
 
     fn refresh(&mut self, env: &Env) -> eventstore::Result<()> {
-        let client = env.op_client.clone();
-        let stats_ref = self.stats_iter.clone();
+        let backend = env.backend.clone();
 
-        let (gossip, stats) = env.handle.block_on(async move {
-            let members = client.read_gossip().await?;
+        env.spawn(async move {
+            let members = backend.read_gossip().await?;
+            let stats = backend.stats().await?;
 
-            let mut stats_ref = stats_ref.write().await;
+            Ok(Msg::Monitoring(MonitoringMsg::Update(
+                members,
+                Box::new(stats),
+            )))
+        });
 
-            if stats_ref.is_none() {
-                let options = StatsOptions::default().refresh_time(Duration::from_secs(2));
-                *stats_ref = Some(client.stats(&options).await?);
-            }
-
-            let stats = stats_ref
-                .as_mut()
-                .unwrap()
-                .next()
-                .await?
-                .parse_statistics()?;
+        Ok(())
+    }
 
-            Ok((members, stats))
-        })?;
+    fn on_msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::Monitoring(MonitoringMsg::ServerVersion(version)) => {
+                self.model.server_version = version.unwrap_or_default();
+            }
 
-        self.model.update(stats, gossip);
+            Msg::Monitoring(MonitoringMsg::Update(gossip, stats)) => {
+                self.model.update(*stats, gossip);
+            }
 
-        Ok(())
+            _ => {}
+        }
     }
 
     fn draw(
@@ -402,9 +393,9 @@ impl View for MonitoringView {
             .margin(2)
             .split(vert_rects[0]);
 
-        self.draw_env_metrics(frame, top_sections[0]);
+        self.draw_env_metrics(ctx, frame, top_sections[0]);
         self.draw_key_metrics(frame, top_sections[2]);
-        self.draw_drive_metrics(frame, vert_rects[1]);
+        self.draw_drive_metrics(ctx, frame, vert_rects[1]);
 
         // let mut datasets = Vec::<Dataset>::new();
         //
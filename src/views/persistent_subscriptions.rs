@@ -1,10 +1,9 @@
 use crate::models::PersistentSubscriptions;
-use crate::views::{Env, ViewCtx};
+use crate::views::keymap::Action;
+use crate::views::{Env, Msg, StageActions, ViewCtx};
 use crate::{Request, View, B};
-use crossterm::event::KeyCode;
 use eventstore::{RevisionOrPosition, StreamPosition};
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use tui::style::{Color, Modifier, Style};
 use tui::widgets::{Block, Borders, Cell, Clear, Row, Table, TableState};
 use tui::Frame;
 
@@ -40,6 +39,31 @@ enum Stage {
     Detail,
 }
 
+// Same order as `Stage`.
+static STAGES: &[StageActions] = &[
+    StageActions {
+        name: "Subscriptions",
+        actions: &[
+            (Action::Up, "Scroll up"),
+            (Action::Down, "Scroll down"),
+            (Action::Select, "Select"),
+        ],
+    },
+    StageActions {
+        name: "Actions",
+        actions: &[
+            (Action::Up, "Scroll up"),
+            (Action::Down, "Scroll down"),
+            (Action::Select, "Select"),
+            (Action::Back, "Close"),
+        ],
+    },
+    StageActions {
+        name: "Settings",
+        actions: &[(Action::Back, "Close")],
+    },
+];
+
 impl Default for Stage {
     fn default() -> Self {
         Stage::Main
@@ -54,9 +78,21 @@ pub struct PersistentSubscriptionView {
     selected: u16,
     selected_choices: u16,
     model: PersistentSubscriptions,
+    // Subscription to select once the list is loaded.
+    pending_selection: Option<String>,
 }
 
 impl PersistentSubscriptionView {
+    fn select_by_key(&mut self, key: &str) -> bool {
+        match self.model.list().position(|(k, _)| k == key) {
+            Some(idx) => {
+                self.selected = idx as u16;
+                true
+            }
+            None => false,
+        }
+    }
+
     fn draw_main(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
         let rects = Layout::default()
             .constraints([Constraint::Min(0)].as_ref())
@@ -65,7 +101,7 @@ impl PersistentSubscriptionView {
 
         let header_cells = HEADERS
             .iter()
-            .map(|h| Cell::from(*h).style(Style::default().fg(Color::Green)));
+            .map(|h| Cell::from(*h).style(ctx.theme.header));
 
         let mut rows: Vec<Row> = Vec::new();
         for (key, sub) in self.model.list() {
@@ -89,7 +125,7 @@ impl PersistentSubscriptionView {
         }
 
         let header = Row::new(header_cells)
-            .style(ctx.normal_style)
+            .style(ctx.theme.normal)
             .height(1)
             .bottom_margin(1);
 
@@ -101,7 +137,7 @@ impl PersistentSubscriptionView {
                     .title("Persistent Subscriptions")
                     .title_alignment(tui::layout::Alignment::Right),
             )
-            .highlight_style(ctx.selected_style)
+            .highlight_style(ctx.theme.selected)
             .widths(&[
                 Constraint::Percentage(20),
                 Constraint::Percentage(20),
@@ -119,7 +155,7 @@ impl PersistentSubscriptionView {
                 .title("Actions")
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL)
-                .style(Style::default().add_modifier(Modifier::REVERSED));
+                .style(ctx.theme.popup);
             let area = centered_rect(19, 22, frame.size());
 
             frame.render_widget(Clear, area);
@@ -147,7 +183,7 @@ impl PersistentSubscriptionView {
                 .select(Some(self.selected_choices as usize));
 
             let table = Table::new(rows)
-                .highlight_style(Style::default().fg(Color::Green))
+                .highlight_style(ctx.theme.highlight)
                 .widths(&[Constraint::Percentage(100)]);
 
             frame.render_stateful_widget(table, layout, &mut self.choices_table_state);
@@ -162,7 +198,7 @@ impl PersistentSubscriptionView {
 
         let header_cells = SETTINGS_HEADERS
             .iter()
-            .map(|h| Cell::from(*h).style(Style::default().fg(Color::Green)));
+            .map(|h| Cell::from(*h).style(ctx.theme.header));
 
         let mut rows: Vec<Row> = Vec::new();
         let p = self.model.get(self.selected).unwrap();
@@ -186,7 +222,7 @@ impl PersistentSubscriptionView {
         rows.push(Row::new(cells));
 
         let header = Row::new(header_cells)
-            .style(ctx.normal_style)
+            .style(ctx.theme.normal)
             .height(1)
             .bottom_margin(1);
 
@@ -200,7 +236,7 @@ impl PersistentSubscriptionView {
                     .title(title)
                     .title_alignment(tui::layout::Alignment::Right),
             )
-            .highlight_style(ctx.selected_style)
+            .highlight_style(ctx.theme.selected)
             .widths(&[
                 Constraint::Percentage(8),
                 Constraint::Percentage(8),
@@ -237,21 +273,29 @@ impl View for PersistentSubscriptionView {
     }
 
     fn refresh(&mut self, env: &Env) -> eventstore::Result<()> {
-        let client = env.client.clone();
-
         if self.stage == Stage::Main {
-            let subs = env.handle.block_on(async move {
-                client
-                    .list_all_persistent_subscriptions(&Default::default())
-                    .await
-            })?;
+            let backend = env.backend.clone();
 
-            self.model.update(subs);
+            env.spawn(async move {
+                let subs = backend.list_persistent_subscriptions().await?;
+
+                Ok(Msg::PersistentSubscriptions(subs))
+            });
         }
 
         Ok(())
     }
 
+    fn on_msg(&mut self, msg: Msg) {
+        if let Msg::PersistentSubscriptions(subs) = msg {
+            self.model.update(subs);
+
+            if let Some(key) = self.pending_selection.take() {
+                self.select_by_key(key.as_str());
+            }
+        }
+    }
+
     fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
         match self.stage {
             Stage::Main | Stage::Choices => self.draw_main(ctx, frame, area),
@@ -259,21 +303,15 @@ impl View for PersistentSubscriptionView {
         }
     }
 
-    fn on_key_pressed(&mut self, key: KeyCode) -> Request {
-        match key {
-            KeyCode::Char('q' | 'Q') => {
-                if self.stage == Stage::Choices || self.stage == Stage::Detail {
-                    self.stage = Stage::Main;
-                    self.selected = 0;
-                    self.selected_choices = 0;
-
-                    return Request::Noop;
-                }
-
-                return Request::Exit;
+    fn on_action(&mut self, action: Action) -> Request {
+        match action {
+            Action::Back => {
+                self.stage = Stage::Main;
+                self.selected = 0;
+                self.selected_choices = 0;
             }
 
-            KeyCode::Enter => {
+            Action::Select => {
                 if self.stage == Stage::Main {
                     self.stage = Stage::Choices;
                 } else if self.stage == Stage::Choices {
@@ -283,7 +321,7 @@ impl View for PersistentSubscriptionView {
                 }
             }
 
-            KeyCode::Up => {
+            Action::Up => {
                 if self.stage == Stage::Main {
                     if self.selected > 0 {
                         self.selected -= 1;
@@ -295,7 +333,7 @@ impl View for PersistentSubscriptionView {
                 }
             }
 
-            KeyCode::Down => {
+            Action::Down => {
                 if self.stage == Stage::Main {
                     self.selected += 1;
                 } else if self.stage == Stage::Choices {
@@ -309,23 +347,23 @@ impl View for PersistentSubscriptionView {
         Request::Noop
     }
 
-    fn keybindings(&self) -> &[(&str, &str)] {
-        match self.stage {
-            Stage::Main => &[
-                ("↑", "Scroll up"),
-                ("↓", "Scroll down"),
-                ("Enter", "Select"),
-            ],
-
-            Stage::Detail => &[("q", "Close")],
-
-            Stage::Choices => &[
-                ("↑", "Scroll up"),
-                ("↓", "Scroll down"),
-                ("Enter", "Select"),
-                ("q", "Close"),
-            ],
+    fn open(&mut self, name: &str) -> Request {
+        self.stage = Stage::Main;
+        self.selected_choices = 0;
+
+        if !self.select_by_key(name) {
+            self.pending_selection = Some(name.to_string());
         }
+
+        Request::Noop
+    }
+
+    fn stages(&self) -> &'static [StageActions] {
+        STAGES
+    }
+
+    fn stage(&self) -> usize {
+        self.stage as usize
     }
 }
 
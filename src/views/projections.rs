@@ -1,13 +1,16 @@
+use crate::backend::ReadRequest;
 use crate::models::{Projection, Projections};
-use crate::views::{render_line_numbers, Env, Request, ViewCtx, B};
+use crate::views::json_tree::JsonTree;
+use crate::views::keymap::Action;
+use crate::views::{render_line_numbers, Env, Msg, Request, StageActions, ViewCtx, B};
 use crate::View;
 use crossterm::event::KeyCode;
-use eventstore::{ReadStreamOptions, StreamPosition};
-use futures::TryStreamExt;
+use eventstore::{ProjectionStatus, StreamPosition};
 use serde::Deserialize;
+use serde_json::Value;
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use tui::style::{Color, Modifier, Style};
-use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Tabs};
 use tui::Frame;
 
 static HEADERS: &[&'static str] = &[
@@ -23,12 +26,57 @@ static HEADERS: &[&'static str] = &[
     "Events",
 ];
 
+static DETAIL_PANES: &[&str] = &["Query", "State", "Result"];
+
+// Events of `$projections-<name>` read backwards to find its last `$ProjectionUpdated`.
+const DETAILS_READ_LIMIT: usize = 100;
+
+pub enum ProjectionsMsg {
+    List(Vec<ProjectionStatus>),
+    Query(String, String),
+    // Name, state and result.
+    Output(String, Value, Value),
+}
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum Stage {
     Main,
     Detail,
+    Search,
 }
 
+// Same order as `Stage`.
+static STAGES: &[StageActions] = &[
+    StageActions {
+        name: "Projections",
+        actions: &[
+            (Action::Up, "Scroll up"),
+            (Action::Down, "Scroll down"),
+            (Action::Select, "Select"),
+        ],
+    },
+    StageActions {
+        name: "Projection",
+        actions: &[
+            (Action::Up, "Scroll up"),
+            (Action::Down, "Scroll down"),
+            (Action::Right, "Next pane"),
+            (Action::Left, "Previous pane"),
+            (Action::Back, "Close"),
+            (Action::Fold, "Fold/unfold"),
+            (Action::ScrollLeft, "Scroll left"),
+            (Action::ScrollRight, "Scroll right"),
+            (Action::Search, "Search"),
+            (Action::NextMatch, "Next match"),
+            (Action::PreviousMatch, "Previous match"),
+        ],
+    },
+    StageActions {
+        name: "Search projection",
+        actions: &[(Action::Select, "Search"), (Action::Back, "Cancel")],
+    },
+];
+
 impl Default for Stage {
     fn default() -> Self {
         Stage::Main
@@ -48,9 +96,22 @@ pub struct ProjectionsViews {
     selected: usize,
     stage: Stage,
     scroll: u16,
+    // Index in `DETAIL_PANES`.
+    pane: usize,
+    state_tree: Option<JsonTree>,
+    result_tree: Option<JsonTree>,
 }
 
 impl ProjectionsViews {
+    /// Tree of the pane shown, `None` for the query.
+    fn tree_mut(&mut self) -> Option<&mut JsonTree> {
+        match self.pane {
+            1 => self.state_tree.as_mut(),
+            2 => self.result_tree.as_mut(),
+            _ => None,
+        }
+    }
+
     fn draw_main(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
         let rects = Layout::default()
             .constraints([Constraint::Min(0)].as_ref())
@@ -59,7 +120,7 @@ impl ProjectionsViews {
 
         let header_cells = HEADERS
             .iter()
-            .map(|h| Cell::from(*h).style(Style::default().fg(Color::Green)));
+            .map(|h| Cell::from(*h).style(ctx.theme.header));
 
         let mut rows: Vec<Row> = Vec::new();
 
@@ -68,7 +129,7 @@ impl ProjectionsViews {
         }
 
         let header = Row::new(header_cells)
-            .style(ctx.normal_style)
+            .style(ctx.theme.normal)
             .height(1)
             .bottom_margin(1);
 
@@ -80,7 +141,7 @@ impl ProjectionsViews {
                     .title("Projections")
                     .title_alignment(tui::layout::Alignment::Right),
             )
-            .highlight_style(ctx.selected_style)
+            .highlight_style(ctx.theme.selected)
             .widths(&[
                 Constraint::Percentage(15),
                 Constraint::Percentage(5),
@@ -107,15 +168,56 @@ impl ProjectionsViews {
             .direction(Direction::Horizontal)
             .split(area);
 
-        let proj = self.model.by_idx(self.selected).unwrap();
-        let content = render_line_numbers(proj.query.as_str());
+        let left = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+            .split(rects[0]);
 
-        let query = Paragraph::new(content)
-            .alignment(Alignment::Left)
-            .block(Block::default().borders(Borders::ALL))
-            .scroll((self.scroll, 0));
+        let titles = DETAIL_PANES
+            .iter()
+            .map(|t| Spans::from(Span::styled(*t, ctx.theme.tab)))
+            .collect();
+
+        let tabs = Tabs::new(titles)
+            .select(self.pane)
+            .style(ctx.theme.text)
+            .highlight_style(ctx.theme.selected_tab);
+
+        frame.render_widget(tabs, left[0]);
+
+        let proj = match self.model.by_idx(self.selected) {
+            Some(proj) => proj,
+            None => return,
+        };
+
+        if self.pane == 0 {
+            let content = render_line_numbers(proj.query.as_str());
+
+            let query = Paragraph::new(content)
+                .alignment(Alignment::Left)
+                .block(Block::default().borders(Borders::ALL))
+                .scroll((self.scroll, 0));
+
+            frame.render_widget(query, left[1]);
+        } else {
+            let (output, tree) = if self.pane == 1 {
+                (proj.state.as_ref(), &mut self.state_tree)
+            } else {
+                (proj.result.as_ref(), &mut self.result_tree)
+            };
+
+            match (output, tree.as_mut()) {
+                (Some(value), Some(tree)) => tree.set_value(value.clone()),
+                (Some(value), None) => *tree = Some(JsonTree::new(value.clone())),
+                (None, _) => {}
+            }
 
-        frame.render_widget(query, rects[0]);
+            let block = Block::default().borders(Borders::ALL);
+
+            match tree.as_mut() {
+                Some(tree) => tree.draw(ctx, frame, left[1], block),
+                None => frame.render_widget(Paragraph::new("Loading…").block(block), left[1]),
+            }
+        }
 
         let table = Table::new(detail_proj_mapping(proj))
             .block(
@@ -124,7 +226,7 @@ impl ProjectionsViews {
                     .title(proj.name.as_str())
                     .title_alignment(Alignment::Right),
             )
-            .highlight_style(ctx.selected_style)
+            .highlight_style(ctx.theme.selected)
             .widths(&[Constraint::Percentage(60), Constraint::Percentage(40)]);
 
         frame.render_stateful_widget(table, rects[1], &mut Default::default());
@@ -139,83 +241,183 @@ impl View for ProjectionsViews {
     fn unload(&mut self, _env: &Env) {}
 
     fn refresh(&mut self, env: &Env) -> eventstore::Result<()> {
-        if self.stage == Stage::Detail {
-            let proj = self.model.by_idx_mut(self.selected).unwrap();
-            let proj_name = proj.name.clone();
-            let client = env.client.clone();
-
-            let details = env.handle.block_on(async move {
-                let options = ReadStreamOptions::default()
+        let backend = env.backend.clone();
+
+        if self.stage != Stage::Main {
+            let proj_name = match self.model.by_idx(self.selected) {
+                Some(proj) => proj.name.clone(),
+                None => return Ok(()),
+            };
+
+            let output_backend = backend.clone();
+            let output_name = proj_name.clone();
+
+            env.spawn(async move {
+                let state = output_backend.projection_state(output_name.clone()).await?;
+                let result = output_backend
+                    .projection_result(output_name.clone())
+                    .await?;
+
+                Ok(Msg::Projections(ProjectionsMsg::Output(
+                    output_name,
+                    state,
+                    result,
+                )))
+            });
+
+            env.spawn(async move {
+                let request = ReadRequest::default()
+                    .max_count(DETAILS_READ_LIMIT)
                     .position(StreamPosition::End)
                     .backwards();
 
                 let stream_name = format!("$projections-{}", proj_name);
 
-                let mut stream = client.read_stream(stream_name.as_str(), &options).await?;
-
-                while let Some(event) = stream.next().await? {
+                for event in backend.read_stream(stream_name, request).await? {
                     if event.get_original_event().event_type == "$ProjectionUpdated" {
                         let details = event
                             .get_original_event()
                             .as_json::<ProjectionDetails>()
-                            .expect("valid projection details JSON");
+                            .map_err(|e| eventstore::Error::InternalParsingError(e.to_string()))?;
 
-                        return Ok(details);
+                        return Ok(Msg::Projections(ProjectionsMsg::Query(
+                            proj_name,
+                            details.query,
+                        )));
                     }
                 }
 
                 Err(eventstore::Error::ResourceNotFound)
-            })?;
-
-            proj.query = details.query;
+            });
         } else {
-            let client = env.proj_client.clone();
-            let projections = env.handle.block_on(async move {
-                client
-                    .list(&Default::default())
-                    .await?
-                    .try_collect::<Vec<_>>()
-                    .await
-            })?;
-
-            self.model.update(projections);
+            env.spawn(async move {
+                let projections = backend.list_projections().await?;
+
+                Ok(Msg::Projections(ProjectionsMsg::List(projections)))
+            });
         }
 
         Ok(())
     }
 
+    fn on_msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::Projections(ProjectionsMsg::List(projections)) => {
+                self.model.update(projections);
+            }
+
+            Msg::Projections(ProjectionsMsg::Query(name, query)) => {
+                if let Some(proj) = self.model.by_name_mut(name.as_str()) {
+                    proj.query = query;
+                }
+            }
+
+            Msg::Projections(ProjectionsMsg::Output(name, state, result)) => {
+                if let Some(proj) = self.model.by_name_mut(name.as_str()) {
+                    proj.state = Some(state);
+                    proj.result = Some(result);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
     fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
         match self.stage {
             Stage::Main => self.draw_main(ctx, frame, area),
-            Stage::Detail => self.draw_details(ctx, frame, area),
+            Stage::Detail | Stage::Search => self.draw_details(ctx, frame, area),
         }
     }
 
-    fn on_key_pressed(&mut self, key: KeyCode) -> Request {
-        if let KeyCode::Char('q' | 'Q') = key {
-            if self.stage == Stage::Detail {
+    fn on_action(&mut self, action: Action) -> Request {
+        match action {
+            Action::Back if self.stage == Stage::Search => {
+                if let Some(tree) = self.tree_mut() {
+                    tree.cancel_search();
+                }
+
+                self.stage = Stage::Detail;
+            }
+
+            Action::Back => {
                 self.stage = Stage::Main;
                 self.selected = 0;
-                return Request::Noop;
+                self.scroll = 0;
+                self.pane = 0;
+                self.state_tree = None;
+                self.result_tree = None;
             }
 
-            return Request::Exit;
-        }
+            Action::Select if self.stage == Stage::Search => {
+                if let Some(tree) = self.tree_mut() {
+                    tree.submit_search();
+                }
+
+                self.stage = Stage::Detail;
+            }
+
+            Action::Left | Action::Right if self.stage == Stage::Detail => {
+                let offset = if action == Action::Right {
+                    1
+                } else {
+                    DETAIL_PANES.len() - 1
+                };
 
-        match key {
-            KeyCode::Up => {
+                self.pane = (self.pane + offset) % DETAIL_PANES.len();
+            }
+
+            Action::Up
+            | Action::Down
+            | Action::Fold
+            | Action::ScrollLeft
+            | Action::ScrollRight
+            | Action::Search
+            | Action::NextMatch
+            | Action::PreviousMatch
+                if self.stage == Stage::Detail =>
+            {
+                let tree = match self.tree_mut() {
+                    Some(tree) => tree,
+                    None => {
+                        match action {
+                            Action::Up => self.scroll = self.scroll.saturating_sub(1),
+                            Action::Down => self.scroll = self.scroll.saturating_add(1),
+                            _ => {}
+                        }
+
+                        return Request::Noop;
+                    }
+                };
+
+                match action {
+                    Action::Up => tree.up(),
+                    Action::Down => tree.down(),
+                    Action::Fold => tree.toggle_fold(),
+                    Action::ScrollLeft => tree.scroll_left(),
+                    Action::ScrollRight => tree.scroll_right(),
+                    Action::NextMatch => tree.next_match(),
+                    Action::PreviousMatch => tree.previous_match(),
+                    _ => {
+                        tree.start_search();
+                        self.stage = Stage::Search;
+                    }
+                }
+            }
+
+            Action::Up if self.stage == Stage::Main => {
                 if self.selected > 0 {
                     self.selected -= 1;
                 }
             }
 
-            KeyCode::Down => {
+            Action::Down if self.stage == Stage::Main => {
                 if self.selected + 1 < self.model.count() {
                     self.selected += 1;
                 }
             }
 
-            KeyCode::Enter => {
+            Action::Select if self.stage == Stage::Main => {
                 self.stage = Stage::Detail;
                 return Request::Refresh;
             }
@@ -226,21 +428,24 @@ impl View for ProjectionsViews {
         Request::Noop
     }
 
-    fn keybindings(&self) -> &[(&str, &str)] {
-        match self.stage {
-            Stage::Main => &[
-                ("↑", "Scroll up"),
-                ("↓", "Scroll down"),
-                ("Enter", "Select"),
-            ],
-
-            Stage::Detail => &[
-                ("↑", "Scroll up"),
-                ("↓", "Scroll down"),
-                ("Enter", "Select"),
-                ("q", "Close"),
-            ],
+    fn captures_input(&self) -> bool {
+        self.stage == Stage::Search
+    }
+
+    fn on_key_pressed(&mut self, key: KeyCode) -> Request {
+        if let Some(tree) = self.tree_mut() {
+            tree.on_key_pressed(key);
         }
+
+        Request::Noop
+    }
+
+    fn stages(&self) -> &'static [StageActions] {
+        STAGES
+    }
+
+    fn stage(&self) -> usize {
+        self.stage as usize
     }
 }
 
@@ -315,14 +520,6 @@ fn detail_proj_mapping(proj: &Projection) -> Vec<Row> {
         Cell::from("Last checkpoint"),
         Cell::from(proj.last_checkpoint.as_str()),
     ]));
-    rows.push(Row::new(vec![
-        Cell::from("Results").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from(proj.result.as_str()),
-    ]));
-    rows.push(Row::new(vec![
-        Cell::from("State").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from(proj.state.as_str()),
-    ]));
 
     rows
 }
@@ -1,17 +1,134 @@
-use crate::views::{centered_rect, render_line_numbers, Env, Request, View, ViewCtx, B};
+use crate::backend::{Backend, FilteredPage, ReadDirection, ReadRequest, VersionedStreamMetadata};
+use crate::decoders::{self, Content, Decoded, Decoder, Decoders};
+use crate::transfer::{export_events, import_events, ExportProgress, ImportProgress};
+use crate::views::all_filter::{format_position, AllFilterForm, AllQuery};
+use crate::views::append::AppendForm;
+use crate::views::delete::DeleteDialog;
+use crate::views::event_diff::EventDiff;
+use crate::views::event_filter::EventFilter;
+use crate::views::export::ExportDialog;
+use crate::views::import::ImportDialog;
+use crate::views::input::LineInput;
+use crate::views::json_tree::JsonTree;
+use crate::views::keymap::Action;
+use crate::views::stream_metadata::{metadata_lines, MetadataForm};
+use crate::views::stream_search::{closest, StreamSearch};
+use crate::views::theme::Theme;
+use crate::views::{
+    centered_rect, render_line_numbers, Env, Msg, Request, StageActions, TaskHandle, View, ViewCtx,
+    B,
+};
 use chrono::{DateTime, Utc};
 use crossterm::event::KeyCode;
-use eventstore::{RecordedEvent, ResolvedEvent, StreamPosition};
+use eventstore::{
+    CurrentRevision, ExpectedRevision, Position, RecordedEvent, ResolvedEvent, StreamPosition,
+    WriteResult,
+};
+use futures::StreamExt;
+use std::collections::{BTreeSet, HashSet, VecDeque};
 use std::ops::Add;
-use std::time::{Duration, SystemTime};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use tui::style::{Color, Modifier, Style};
-use tui::text::Text;
-use tui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap};
+use tui::text::{Span, Spans, Text};
+use tui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Tabs, Wrap};
 use tui::Frame;
+use uuid::Uuid;
 
 static HEADERS: &[&'static str] = &["Recently Created Streams", "Recently Changed Streams"];
 static STREAM_HEADERS: &[&'static str] = &["Event #", "Name", "Type", "Created Date"];
+static ALL_HEADERS: &[&str] = &["Position", "Name", "Type", "Created Date"];
+static PREVIEW_PANES: &[&str] = &["Data", "Metadata", "System"];
+
+const PAGE_SIZE: u64 = 500;
+
+// Links of `$streams` read for the search suggestions.
+const STREAM_NAMES_LIMIT: usize = 1_000;
+
+// Events kept while tailing, the oldest ones get dropped.
+const TAIL_BUFFER_SIZE: usize = 1_000;
+const TAIL_RATE_WINDOW: Duration = Duration::from_secs(5);
+
+pub enum StreamsMsg {
+    Streams {
+        last_created: Vec<String>,
+        recently_changed: Vec<String>,
+        tombstoned: Vec<String>,
+    },
+    Events(String, Page, eventstore::Result<EventsPage>),
+    AllEvents(AllQuery, eventstore::Result<FilteredPage>),
+    Tailed(String, eventstore::Result<ResolvedEvent>),
+    Metadata(String, eventstore::Result<Option<VersionedStreamMetadata>>),
+    /// Metadata read back once written.
+    MetadataSaved(String, eventstore::Result<Option<VersionedStreamMetadata>>),
+    Appended(String, eventstore::Result<WriteResult>),
+    Deleted(String, eventstore::Result<()>),
+    Exported(PathBuf, ExportProgress),
+    Imported(PathBuf, ImportProgress),
+    /// Names linked from `$streams`, suggested by the search.
+    StreamNames(Vec<String>),
+}
+
+/// Where a page of events is read from. Events are displayed in the read direction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Page {
+    direction: ReadDirection,
+    position: StreamPosition<u64>,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self::backwards(StreamPosition::End)
+    }
+}
+
+impl Page {
+    fn forwards(position: StreamPosition<u64>) -> Self {
+        Self {
+            direction: ReadDirection::Forwards,
+            position,
+        }
+    }
+
+    fn backwards(position: StreamPosition<u64>) -> Self {
+        Self {
+            direction: ReadDirection::Backwards,
+            position,
+        }
+    }
+}
+
+pub struct EventsPage {
+    events: Vec<ResolvedEvent>,
+    // Revision of the last event of the stream, not of the page.
+    last_revision: Option<u64>,
+}
+
+/// Subscription appending new events to the displayed ones, newest last.
+struct Tail {
+    _handle: TaskHandle,
+    paused: bool,
+    // Keeps the newest event selected.
+    follow: bool,
+    // Received while paused or previewing an event.
+    held: Vec<ResolvedEvent>,
+    arrivals: VecDeque<Instant>,
+}
+
+impl Tail {
+    fn events_per_second(&mut self) -> f64 {
+        while let Some(time) = self.arrivals.front() {
+            if time.elapsed() < TAIL_RATE_WINDOW {
+                break;
+            }
+
+            self.arrivals.pop_front();
+        }
+
+        self.arrivals.len() as f64 / TAIL_RATE_WINDOW.as_secs_f64()
+    }
+}
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum Stage {
@@ -19,8 +136,184 @@ enum Stage {
     Stream,
     StreamPreview,
     Search,
+    Jump,
+    Tail,
+    Metadata,
+    EditMetadata,
+    Append,
+    Delete,
+    Filter,
+    BrowseAll,
+    Export,
+    Import,
+    PreviewSearch,
+    Diff,
 }
 
+// Same order as `Stage`, followed by the error popup.
+static STAGES: &[StageActions] = &[
+    StageActions {
+        name: "Streams",
+        actions: &[
+            (Action::Up, "Scroll up"),
+            (Action::Down, "Scroll down"),
+            (Action::Right, "Move right"),
+            (Action::Left, "Move left"),
+            (Action::Search, "Search"),
+            (Action::Select, "Select"),
+            (Action::Import, "Import"),
+        ],
+    },
+    StageActions {
+        name: "Stream",
+        actions: &[
+            (Action::Up, "Scroll up"),
+            (Action::Down, "Scroll down"),
+            (Action::Select, "Select"),
+            (Action::Back, "Close"),
+            (Action::PageUp, "Previous page"),
+            (Action::PageDown, "Next page"),
+            (Action::First, "From start"),
+            (Action::Last, "From end"),
+            (Action::Jump, "Go to"),
+            (Action::Tail, "Tail"),
+            (Action::Metadata, "Metadata"),
+            (Action::Append, "Append event"),
+            (Action::Delete, "Delete"),
+            (Action::Tombstone, "Tombstone"),
+            (Action::Filter, "Filter"),
+            (Action::Export, "Export"),
+            (Action::Import, "Import"),
+            (Action::Mark, "Mark"),
+            (Action::Diff, "Diff"),
+            (Action::FollowLink, "Follow link"),
+        ],
+    },
+    StageActions {
+        name: "Event preview",
+        actions: &[
+            (Action::Up, "Scroll up"),
+            (Action::Down, "Scroll down"),
+            (Action::Right, "Next pane"),
+            (Action::Left, "Previous pane"),
+            (Action::Back, "Close"),
+            (Action::PageUp, "Page up"),
+            (Action::PageDown, "Page down"),
+            (Action::Fold, "Fold/unfold"),
+            (Action::ScrollLeft, "Scroll left"),
+            (Action::ScrollRight, "Scroll right"),
+            (Action::Search, "Search"),
+            (Action::NextMatch, "Next match"),
+            (Action::PreviousMatch, "Previous match"),
+            (Action::FollowLink, "Follow link"),
+        ],
+    },
+    StageActions {
+        name: "Search",
+        actions: &[(Action::Select, "Open"), (Action::Back, "Cancel")],
+    },
+    StageActions {
+        name: "Go to revision",
+        actions: &[(Action::Select, "Go"), (Action::Back, "Cancel")],
+    },
+    StageActions {
+        name: "Tail",
+        actions: &[
+            (Action::Up, "Scroll up"),
+            (Action::Down, "Scroll down"),
+            (Action::Select, "Select"),
+            (Action::Back, "Close"),
+            (Action::Pause, "Pause/resume"),
+            (Action::Last, "Follow"),
+            (Action::Tail, "Stop tail"),
+        ],
+    },
+    StageActions {
+        name: "Metadata",
+        actions: &[(Action::Edit, "Edit"), (Action::Back, "Close")],
+    },
+    StageActions {
+        name: "Edit metadata",
+        actions: &[
+            (Action::Up, "Previous field"),
+            (Action::Down, "Next field"),
+            (Action::Select, "Save"),
+            (Action::Back, "Cancel"),
+        ],
+    },
+    StageActions {
+        name: "Append event",
+        actions: &[
+            (Action::Up, "Previous field"),
+            (Action::Down, "Next field"),
+            (Action::Submit, "Append"),
+            (Action::Back, "Close"),
+        ],
+    },
+    StageActions {
+        name: "Delete stream",
+        actions: &[
+            (Action::Up, "Previous field"),
+            (Action::Down, "Next field"),
+            (Action::Select, "Confirm"),
+            (Action::Back, "Close"),
+        ],
+    },
+    StageActions {
+        name: "Filter events",
+        actions: &[(Action::Select, "Apply"), (Action::Back, "Cancel")],
+    },
+    StageActions {
+        name: "Browse $all",
+        actions: &[
+            (Action::Up, "Previous field"),
+            (Action::Down, "Next field"),
+            (Action::Select, "Read"),
+            (Action::Back, "Cancel"),
+        ],
+    },
+    StageActions {
+        name: "Export events",
+        actions: &[
+            (Action::Up, "Previous field"),
+            (Action::Down, "Next field"),
+            (Action::Select, "Export"),
+            (Action::Back, "Cancel/close"),
+        ],
+    },
+    StageActions {
+        name: "Import events",
+        actions: &[
+            (Action::Up, "Previous field"),
+            (Action::Down, "Next field"),
+            (Action::Select, "Import"),
+            (Action::Back, "Cancel/close"),
+        ],
+    },
+    StageActions {
+        name: "Search event",
+        actions: &[(Action::Select, "Search"), (Action::Back, "Cancel")],
+    },
+    StageActions {
+        name: "Event diff",
+        actions: &[
+            (Action::Up, "Scroll up"),
+            (Action::Down, "Scroll down"),
+            (Action::Right, "Next pane"),
+            (Action::Left, "Previous pane"),
+            (Action::Back, "Close"),
+            (Action::PageUp, "Page up"),
+            (Action::PageDown, "Page down"),
+            (Action::ScrollLeft, "Scroll left"),
+            (Action::ScrollRight, "Scroll right"),
+        ],
+    },
+    StageActions {
+        name: "Error",
+        actions: &[(Action::Back, "Close")],
+    },
+];
+
 pub struct StreamsView {
     selected_tab: usize,
     selected: usize,
@@ -29,12 +322,35 @@ pub struct StreamsView {
     model: Model,
     stage: Stage,
     scroll: u16,
-    buffer: String,
+    // Index in `PREVIEW_PANES`.
+    pane: usize,
+    // JSON of the previewed pane, along with the id of its event and the pane.
+    preview_tree: Option<(Uuid, usize, JsonTree)>,
+    // Events to diff, at most two.
+    marked: Vec<ResolvedEvent>,
+    diff: Option<EventDiff>,
+    search: StreamSearch,
+    // Stream names seen so far, suggested by the search.
+    known_streams: BTreeSet<String>,
+    stream_names_loaded: bool,
+    revision: LineInput,
+    filter: LineInput,
+    filter_error: Option<String>,
+    tail: Option<Tail>,
+    metadata_form: Option<MetadataForm>,
+    append_form: Option<AppendForm>,
+    delete_dialog: Option<DeleteDialog>,
+    all_filter_form: Option<AllFilterForm>,
+    export_dialog: Option<ExportDialog>,
+    import_dialog: Option<ImportDialog>,
+    // Where the import dialog was opened from, it's drawn over it.
+    import_origin: Stage,
     last_error: Option<eventstore::Error>,
+    decoders: Decoders,
 }
 
-impl Default for StreamsView {
-    fn default() -> Self {
+impl StreamsView {
+    pub fn new(decoders: Decoders) -> Self {
         Self {
             selected_tab: 0,
             selected: 0,
@@ -43,8 +359,26 @@ impl Default for StreamsView {
             model: Default::default(),
             stage: Stage::Main,
             scroll: 0,
-            buffer: Default::default(),
+            pane: 0,
+            preview_tree: None,
+            marked: Vec::new(),
+            diff: None,
+            search: Default::default(),
+            known_streams: BTreeSet::new(),
+            stream_names_loaded: false,
+            revision: Default::default(),
+            filter: Default::default(),
+            filter_error: None,
+            tail: None,
+            metadata_form: None,
+            append_form: None,
+            delete_dialog: None,
+            all_filter_form: None,
+            export_dialog: None,
+            import_dialog: None,
+            import_origin: Stage::Main,
             last_error: None,
+            decoders,
         }
     }
 }
@@ -55,6 +389,27 @@ struct Model {
     recently_changed: Vec<String>,
     selected_stream: Option<String>,
     selected_stream_events: Vec<ResolvedEvent>,
+    page: Page,
+    last_revision: Option<u64>,
+    // Selects the last event of the page once loaded, when scrolling up past the first one.
+    select_last: bool,
+    // `None` once loaded means the stream has no metadata.
+    metadata: Option<VersionedStreamMetadata>,
+    metadata_loaded: bool,
+    // Flagged in both lists, they can't be read anymore.
+    tombstoned: HashSet<String>,
+    filter: Option<EventFilter>,
+    // Indexes in `selected_stream_events` of the events matching the filter.
+    matches: Option<Vec<usize>>,
+    // Server-side filtered read of `$all`, its last events are displayed otherwise.
+    all_query: Option<AllQuery>,
+    // Query of the page displayed, or being read. Filtered reads can take seconds, a page is
+    // only read again when moving to another one.
+    all_query_read: Option<AllQuery>,
+    // Where the previous pages of the query were read from.
+    all_history: Vec<StreamPosition<Position>>,
+    // The displayed page of the query may be missing matches, the server was still scanning.
+    all_partial: bool,
 }
 
 impl Model {
@@ -63,451 +418,1718 @@ impl Model {
         self.recently_changed.clear();
         self.selected_stream = None;
         self.selected_stream_events.clear();
+        self.page = Page::default();
+        self.last_revision = None;
+        self.select_last = false;
+        self.metadata = None;
+        self.metadata_loaded = false;
+        self.tombstoned.clear();
+        self.clear_filter();
+        self.all_query = None;
+        self.all_query_read = None;
+        self.all_history.clear();
+        self.all_partial = false;
     }
-}
 
-impl StreamsView {
-    fn load_streams(&mut self, env: &Env) -> eventstore::Result<()> {
-        let client = env.client.clone();
-        self.model = env.handle.block_on(async move {
-            let mut model = Model::default();
-            let options_1 = eventstore::ReadStreamOptions::default()
-                .max_count(20)
-                .position(StreamPosition::End)
-                .backwards();
+    fn clear_filter(&mut self) {
+        self.filter = None;
+        self.matches = None;
+    }
 
-            let options_2 = eventstore::ReadAllOptions::default()
-                .max_count(20)
-                .position(StreamPosition::End)
-                .backwards();
+    fn refilter(&mut self) {
+        self.matches = self.filter.as_ref().map(|filter| {
+            self.selected_stream_events
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| filter.matches(event))
+                .map(|(idx, _)| idx)
+                .collect()
+        });
+    }
 
-            let mut stream_names = client.read_stream("$streams", &options_1).await?;
-            let mut all_stream = client.read_all(&options_2).await?;
+    /// Events displayed in the stream table, the ones matching the filter if there's one.
+    fn displayed_events(&self) -> Vec<&ResolvedEvent> {
+        match self.matches.as_ref() {
+            Some(matches) => matches
+                .iter()
+                .map(|idx| &self.selected_stream_events[*idx])
+                .collect(),
+            None => self.selected_stream_events.iter().collect(),
+        }
+    }
 
-            while let Some(event) = read_stream_next(&mut stream_names).await? {
-                let (_, stream_name) =
-                    std::str::from_utf8(event.get_original_event().data.as_ref())
-                        .expect("UTF-8 formatted text")
-                        .rsplit_once('@')
-                        .unwrap_or_default();
+    fn displayed_event(&self, idx: usize) -> Option<&ResolvedEvent> {
+        match self.matches.as_ref() {
+            Some(matches) => matches
+                .get(idx)
+                .map(|idx| &self.selected_stream_events[*idx]),
+            None => self.selected_stream_events.get(idx),
+        }
+    }
 
-                model.last_created.push(stream_name.to_string());
-            }
+    fn displayed_len(&self) -> usize {
+        self.matches
+            .as_ref()
+            .map_or(self.selected_stream_events.len(), Vec::len)
+    }
 
-            while let Some(event) = read_stream_next(&mut all_stream).await? {
-                let stream_id = &event.get_original_event().stream_id;
-                if model.recently_changed.contains(stream_id) {
-                    continue;
-                }
+    fn is_all(&self) -> bool {
+        self.selected_stream.as_deref().map(str::trim) == Some("$all")
+    }
 
-                model.recently_changed.push(stream_id.clone());
-            }
+    // `$all` has positions rather than revisions, its pages are read through `all_query`.
+    fn is_paginated(&self) -> bool {
+        !self.is_all()
+    }
 
-            Ok::<_, eventstore::Error>(model)
-        })?;
+    fn revisions(&self) -> Option<(u64, u64)> {
+        let revisions = self
+            .selected_stream_events
+            .iter()
+            .map(|e| e.get_original_event().revision);
 
-        Ok(())
+        Some((revisions.clone().min()?, revisions.max()?))
     }
-}
 
-impl View for StreamsView {
-    fn load(&mut self, env: &Env) -> eventstore::Result<()> {
-        self.load_streams(env)
-    }
+    /// Page following the displayed one, in the read direction.
+    fn next_page(&self) -> Option<Page> {
+        let (first, last) = self.revisions()?;
 
-    fn unload(&mut self, _env: &Env) {
-        self.selected = 0;
-        self.selected_tab = 0;
-        self.scroll = 0;
-        self.stage = Stage::Main;
-        self.model.clear();
-        self.last_error = None;
+        match self.page.direction {
+            ReadDirection::Backwards if first > 0 => {
+                Some(Page::backwards(StreamPosition::Position(first - 1)))
+            }
+            ReadDirection::Forwards if Some(last) < self.last_revision => {
+                Some(Page::forwards(StreamPosition::Position(last + 1)))
+            }
+            _ => None,
+        }
     }
 
-    fn refresh(&mut self, env: &Env) -> eventstore::Result<()> {
-        if self.stage == Stage::StreamPreview {
-            return Ok(());
-        }
+    fn previous_page(&self) -> Option<Page> {
+        let (first, last) = self.revisions()?;
 
-        if let Some(stream_name) = self.model.selected_stream.clone() {
-            let client = env.client.clone();
-            let result = env.handle.block_on(async move {
-                let mut stream = if stream_name.trim() == "$all" {
-                    let options = eventstore::ReadAllOptions::default()
-                        .max_count(500)
-                        .resolve_link_tos()
-                        .position(StreamPosition::End)
-                        .backwards();
+        match self.page.direction {
+            ReadDirection::Backwards => {
+                let last_revision = self.last_revision?;
 
-                    client.read_all(&options).await?
+                if last >= last_revision {
+                    None
+                } else if last + PAGE_SIZE >= last_revision {
+                    Some(Page::default())
                 } else {
-                    let options = eventstore::ReadStreamOptions::default()
-                        .max_count(500)
-                        .resolve_link_tos()
-                        .position(StreamPosition::End)
-                        .backwards();
+                    Some(Page::backwards(StreamPosition::Position(last + PAGE_SIZE)))
+                }
+            }
+            ReadDirection::Forwards if first > 0 => Some(Page::forwards(StreamPosition::Position(
+                first.saturating_sub(PAGE_SIZE),
+            ))),
+            ReadDirection::Forwards => None,
+        }
+    }
+}
 
-                    client.read_stream(stream_name, &options).await?
-                };
+impl StreamsView {
+    fn is_marked(&self, event: &ResolvedEvent) -> bool {
+        let id = event.get_original_event().id;
 
-                let mut events = Vec::new();
+        self.marked.iter().any(|e| e.get_original_event().id == id)
+    }
 
-                while let Some(event) = stream.next().await? {
-                    events.push(event);
-                }
+    fn stream_title(&mut self, stream_name: &str) -> String {
+        let count = self.model.selected_stream_events.len();
 
-                Ok::<_, eventstore::Error>(events)
-            });
+        if let Some(tail) = self.tail.as_mut() {
+            let mut title = format!(
+                "Tailing '{}' | {} events | {:.1} events/s",
+                stream_name,
+                count,
+                tail.events_per_second()
+            );
 
-            match result {
-                Err(e) => {
-                    self.last_error = Some(e);
-                    self.model.selected_stream_events.clear();
-                }
-                Ok(xs) => self.model.selected_stream_events = xs,
+            if tail.paused {
+                title.push_str(format!(" | paused ({} new)", tail.held.len()).as_str());
             }
 
-            Ok(())
+            return title;
+        }
+
+        let title = format!("Event Stream '{}'", stream_name);
+
+        match (self.model.revisions(), self.model.last_revision) {
+            (Some((first, last)), Some(last_revision)) if self.model.is_paginated() => format!(
+                "{} | revision {}–{} of {}",
+                title,
+                first,
+                last,
+                last_revision + 1
+            ),
+            _ => match self.model.all_query.as_ref() {
+                Some(query) if self.model.is_all() && self.model.all_partial => format!(
+                    "{} | {} | partial, the server was still scanning",
+                    title,
+                    query.describe()
+                ),
+                Some(query) if self.model.is_all() => format!("{} | {}", title, query.describe()),
+                _ => title,
+            },
+        }
+    }
+
+    /// Subscribes from the newest displayed event, the ones written since it was read aren't
+    /// missed.
+    fn start_tail(&mut self, env: &Env) {
+        let stream_name = self.model.selected_stream.clone().unwrap_or_default();
+
+        let events = if self.model.is_all() {
+            let newest = self
+                .model
+                .selected_stream_events
+                .iter()
+                .map(|event| event.get_original_event().position)
+                .max_by_key(|position| position.commit);
+
+            let from = newest.map_or(StreamPosition::End, StreamPosition::Position);
+            let filter = self
+                .model
+                .all_query
+                .as_ref()
+                .and_then(|query| query.filter.clone());
+
+            env.backend.subscribe_to_all(from, filter)
         } else {
-            self.load_streams(env)
+            let from = self
+                .model
+                .revisions()
+                .map_or(StreamPosition::End, |(_, last)| {
+                    StreamPosition::Position(last)
+                });
+            env.backend.subscribe_to_stream(stream_name.clone(), from)
+        };
+
+        let handle = env.spawn_stream(
+            events.map(move |result| Msg::Streams(StreamsMsg::Tailed(stream_name.clone(), result))),
+        );
+
+        self.tail = Some(Tail {
+            _handle: handle,
+            paused: false,
+            follow: true,
+            held: Vec::new(),
+            arrivals: VecDeque::new(),
+        });
+    }
+
+    fn stop_tail(&mut self) -> Request {
+        self.tail = None;
+        self.stage = Stage::Stream;
+
+        self.load_page(Page::default())
+    }
+
+    /// Appends tailed events, dropping the oldest ones past the buffer size.
+    fn append_tailed(&mut self, events: Vec<ResolvedEvent>) {
+        let buffer = &mut self.model.selected_stream_events;
+        buffer.extend(events);
+
+        let overflow = buffer.len().saturating_sub(TAIL_BUFFER_SIZE);
+        buffer.drain(..overflow);
+
+        if self.tail.as_ref().map_or(false, |tail| tail.follow) {
+            self.selected = buffer.len().saturating_sub(1);
+        } else {
+            self.selected = self.selected.saturating_sub(overflow);
         }
     }
 
-    fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
-        match self.stage {
-            Stage::Main | Stage::Search => {
-                let rects = Layout::default()
-                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-                    .direction(Direction::Horizontal)
-                    .margin(2)
-                    .split(area);
+    fn flush_held(&mut self) {
+        let held = match self.tail.as_mut() {
+            Some(tail) if !tail.paused => std::mem::take(&mut tail.held),
+            _ => return,
+        };
 
-                for (idx, name) in HEADERS.iter().enumerate() {
-                    let header_cells =
-                        vec![Cell::from(*name).style(Style::default().fg(Color::Green))];
-                    let header = Row::new(header_cells)
-                        .style(ctx.normal_style)
-                        .height(1)
-                        .bottom_margin(1);
+        self.append_tailed(held);
+    }
 
-                    let cells = match idx {
-                        0 => self.model.last_created.iter(),
-                        _ => self.model.recently_changed.iter(),
+    /// Reads the metadata, or writes the one submitted from the form.
+    fn refresh_metadata(&mut self, env: &Env) {
+        let stream_name = self.model.selected_stream.clone().unwrap_or_default();
+        let backend = env.backend.clone();
+
+        if let Some(form) = self.metadata_form.as_mut() {
+            if let Some(metadata) = form.take_submitted() {
+                let expected = form.expected();
+
+                env.spawn(async move {
+                    let result = match backend
+                        .set_stream_metadata(stream_name.clone(), expected, metadata)
+                        .await
+                    {
+                        Ok(()) => backend.get_stream_metadata(stream_name.clone()).await,
+                        Err(e) => Err(e),
                     };
 
-                    if self.selected_tab == idx {
-                        self.main_table_states[idx].select(Some(self.selected));
-                    } else {
-                        self.main_table_states[idx].select(None);
-                    }
+                    Ok(Msg::Streams(StreamsMsg::MetadataSaved(stream_name, result)))
+                });
+            }
 
-                    let rows = cells
-                        .map(|c| {
-                            Row::new(vec![
-                                Cell::from(c.as_str()).style(Style::default().fg(Color::Gray))
-                            ])
-                        })
-                        .collect::<Vec<_>>();
+            // Edits aren't overwritten by a reload.
+            return;
+        }
 
-                    let border_type = if idx == 0 {
-                        Borders::TOP | Borders::RIGHT
-                    } else {
-                        Borders::TOP
-                    };
+        env.spawn(async move {
+            let result = backend.get_stream_metadata(stream_name.clone()).await;
 
-                    let table = Table::new(rows)
-                        .header(header)
-                        .block(Block::default().borders(border_type))
-                        .highlight_style(ctx.selected_style)
-                        .widths(&[Constraint::Percentage(100)]);
+            Ok(Msg::Streams(StreamsMsg::Metadata(stream_name, result)))
+        });
+    }
 
-                    frame.render_stateful_widget(
-                        table,
-                        rects[idx],
-                        &mut self.main_table_states[idx],
-                    );
+    fn draw_filter_bar(&self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
+        if self.stage == Stage::Filter {
+            let rects = Layout::default()
+                .constraints([Constraint::Length(1), Constraint::Length(1)])
+                .split(area);
 
-                    if let Stage::Search = self.stage {
-                        let block = Block::default()
-                            .title("Search")
-                            .borders(Borders::ALL)
-                            .style(Style::default().add_modifier(Modifier::REVERSED));
-                        let area = centered_rect(40, 15, frame.size());
-                        frame.render_widget(Clear, area);
-                        frame.render_widget(block, area);
+            self.filter.draw(ctx, frame, rects[0], "Filter: ");
 
-                        let layout = Layout::default()
-                            .margin(2)
-                            .constraints([Constraint::Length(13), Constraint::Max(100)])
-                            .direction(Direction::Horizontal)
-                            .split(area);
+            if let Some(error) = self.filter_error.as_ref() {
+                let error = Paragraph::new(error.as_str()).style(ctx.theme.error);
+                frame.render_widget(error, rects[1]);
+            }
+
+            return;
+        }
 
-                        let label =
-                            Paragraph::new("Stream name: ").style(Style::default().fg(Color::Gray));
+        let filter = match self.model.filter.as_ref() {
+            Some(filter) => filter,
+            None => return,
+        };
+
+        let bar = Spans::from(vec![
+            Span::styled("Filter: ", ctx.theme.label),
+            Span::styled(filter.as_str().to_string(), ctx.theme.text),
+            Span::styled(
+                format!(
+                    " | {} of {} loaded events match",
+                    self.model.displayed_len(),
+                    self.model.selected_stream_events.len()
+                ),
+                ctx.theme.highlight,
+            ),
+        ]);
+
+        frame.render_widget(Paragraph::new(bar), area);
+    }
 
-                        frame.render_widget(label, layout[0]);
+    /// Forgets the filters of the previous stream.
+    fn reset_filters(&mut self) {
+        self.model.clear_filter();
+        self.model.all_query = None;
+        self.model.all_query_read = None;
+        self.model.all_history.clear();
+        self.filter = LineInput::default();
+        self.filter_error = None;
+    }
 
-                        let mut input = std::iter::repeat('_').take(100).collect::<String>();
+    /// Reads `$all` from `from`, with the current filter.
+    fn read_all_from(&mut self, from: StreamPosition<Position>) -> Request {
+        if let Some(query) = self.model.all_query.as_mut() {
+            query.from = from;
+            self.model.all_query_read = None;
+            self.selected = 0;
+        }
 
-                        let char_count = self.buffer.chars().count();
-                        input.replace_range(..char_count, self.buffer.as_str());
+        Request::Refresh
+    }
 
-                        let input = Paragraph::new(input).style(Style::default().fg(Color::Gray));
+    /// Applies what's typed in the filter bar, an empty filter shows every event again.
+    fn apply_filter(&mut self) {
+        let input = self.filter.value().trim();
 
-                        frame.render_widget(input, layout[1]);
-                    }
+        if input.is_empty() {
+            self.model.clear_filter();
+        } else {
+            match EventFilter::parse(input) {
+                Ok(filter) => self.model.filter = Some(filter),
+                Err(e) => {
+                    self.filter_error = Some(e);
+                    return;
                 }
             }
-            Stage::Stream => {
-                let rects = Layout::default()
-                    .constraints([Constraint::Percentage(100)].as_ref())
-                    .margin(2)
-                    .split(area);
+        }
 
-                let stream_name = self.model.selected_stream.clone().unwrap_or_default();
+        self.model.refilter();
+        self.selected = 0;
+        self.stage = Stage::Stream;
+    }
 
-                let header_cells = STREAM_HEADERS
-                    .iter()
-                    .map(|h| Cell::from(*h).style(Style::default().fg(Color::Green)));
+    /// Deletes or tombstones the stream once confirmed.
+    fn delete(&mut self, env: &Env) {
+        let dialog = match self.delete_dialog.as_mut() {
+            Some(dialog) => dialog,
+            None => return,
+        };
+
+        let (expected, stream_name) =
+            match (dialog.take_submitted(), self.model.selected_stream.clone()) {
+                (Some(expected), Some(stream_name)) => (expected, stream_name),
+                _ => return,
+            };
+
+        let backend = env.backend.clone();
+        let tombstone = dialog.is_tombstone();
+
+        env.spawn(async move {
+            let result = if tombstone {
+                backend
+                    .tombstone_stream(stream_name.clone(), expected)
+                    .await
+            } else {
+                backend.delete_stream(stream_name.clone(), expected).await
+            };
+
+            Ok(Msg::Streams(StreamsMsg::Deleted(stream_name, result)))
+        });
+    }
 
-                let header = Row::new(header_cells)
-                    .style(ctx.normal_style)
-                    .height(1)
-                    .bottom_margin(1);
+    /// Starts the export submitted from the dialog, its progress is reported after every batch.
+    fn export(&mut self, env: &Env) {
+        let dialog = match self.export_dialog.as_mut() {
+            Some(dialog) => dialog,
+            None => return,
+        };
+
+        let ((path, range), stream_name) =
+            match (dialog.take_submitted(), self.model.selected_stream.clone()) {
+                (Some(submitted), Some(stream_name)) => (submitted, stream_name),
+                _ => return,
+            };
+
+        let progress = export_events(env.backend.clone(), stream_name, range, path.clone());
+        let reported = path.clone();
+        let handle =
+            env.spawn_stream(progress.map(move |progress| {
+                Msg::Streams(StreamsMsg::Exported(reported.clone(), progress))
+            }));
+
+        dialog.start(path, handle);
+    }
 
-                let mut rows = Vec::new();
+    /// Starts the import submitted from the dialog, its progress is reported after every batch.
+    fn import(&mut self, env: &Env) {
+        let dialog = match self.import_dialog.as_mut() {
+            Some(dialog) => dialog,
+            None => return,
+        };
+
+        let (path, options) = match dialog.take_submitted() {
+            Some(submitted) => submitted,
+            None => return,
+        };
+
+        let progress = import_events(env.backend.clone(), path.clone(), options.clone());
+        let reported = path.clone();
+        let handle =
+            env.spawn_stream(progress.map(move |progress| {
+                Msg::Streams(StreamsMsg::Imported(reported.clone(), progress))
+            }));
+
+        dialog.start(path, options, handle);
+    }
 
-                for event in self.model.selected_stream_events.iter() {
-                    let rev = event.get_original_event().revision;
-                    let event = event.event.as_ref().unwrap();
-                    let mut cols = Vec::new();
+    fn set_events(&mut self, stream_name: String, result: eventstore::Result<EventsPage>) {
+        match result {
+            Err(e) => {
+                if let eventstore::Error::ResourceDeleted = e {
+                    self.model.tombstoned.insert(stream_name);
+                }
 
-                    cols.push(Cell::from(rev.to_string()).style(Style::default().fg(Color::Gray)));
+                self.last_error = Some(e);
+                self.model.selected_stream_events.clear();
+                self.model.refilter();
+            }
+            Ok(page) => {
+                self.model.selected_stream_events = page.events;
+                self.model.last_revision = page.last_revision;
+                self.model.refilter();
 
-                    let name = format!("{}@{}", event.revision, event.stream_id);
-                    cols.push(Cell::from(name).style(Style::default().fg(Color::Gray)));
-                    cols.push(
-                        Cell::from(event.event_type.clone())
-                            .style(Style::default().fg(Color::Gray)),
-                    );
-                    cols.push(
-                        Cell::from(event.created.to_string())
-                            .style(Style::default().fg(Color::Gray)),
-                    );
+                let len = self.model.displayed_len();
 
-                    rows.push(Row::new(cols));
+                if std::mem::take(&mut self.model.select_last) {
+                    self.selected = len.saturating_sub(1);
+                } else {
+                    self.selected = self.selected.min(len.saturating_sub(1));
                 }
+            }
+        }
+    }
 
-                let table = Table::new(rows)
-                    .header(header)
-                    .block(
-                        Block::default()
-                            .borders(Borders::TOP)
-                            .title(format!("Event Stream '{}'", stream_name))
-                            .title_alignment(Alignment::Right),
-                    )
-                    .highlight_style(ctx.selected_style)
-                    .widths(&[
-                        Constraint::Percentage(25),
-                        Constraint::Percentage(25),
-                        Constraint::Percentage(25),
-                        Constraint::Percentage(25),
-                    ]);
+    fn load_page(&mut self, page: Page) -> Request {
+        self.model.page = page;
+        self.selected = 0;
 
-                self.stream_table_state.select(Some(self.selected));
+        Request::Refresh
+    }
 
-                frame.render_stateful_widget(table, rects[0], &mut self.stream_table_state);
-            }
-            Stage::StreamPreview => {
-                let rects = Layout::default()
-                    .constraints([Constraint::Length(4), Constraint::Min(0)].as_ref())
-                    .margin(2)
-                    .split(area);
+    fn load_streams(&mut self, env: &Env) -> eventstore::Result<()> {
+        let backend = env.backend.clone();
+        env.spawn(async move {
+            let mut last_created = Vec::new();
+            let mut recently_changed = Vec::new();
+            let mut tombstoned = Vec::new();
+            let request_1 = ReadRequest::default()
+                .max_count(20)
+                .position(StreamPosition::End)
+                .backwards();
 
-                let header_cells = STREAM_HEADERS
-                    .iter()
-                    .map(|h| Cell::from(*h).style(Style::default().fg(Color::Green)));
+            let request_2 = ReadRequest::default()
+                .max_count(20)
+                .position(StreamPosition::End)
+                .backwards();
 
-                let header = Row::new(header_cells)
-                    .style(ctx.normal_style)
-                    .height(1)
-                    .bottom_margin(1);
+            let stream_names = match backend.read_stream("$streams".to_string(), request_1).await {
+                Err(eventstore::Error::ResourceNotFound) => Vec::new(),
+                other => other?,
+            };
 
-                let mut rows = Vec::new();
-                let event = &self.model.selected_stream_events[self.selected];
-                let target_event = event.event.as_ref().unwrap();
-                let mut cols = Vec::new();
+            let all_stream = backend.read_all(request_2).await?;
 
-                cols.push(
-                    Cell::from(event.get_original_event().revision.to_string())
-                        .style(Style::default().fg(Color::Gray)),
-                );
+            last_created.extend(stream_names.iter().filter_map(linked_stream_name));
 
-                let name = format!(
-                    "{}@{}",
-                    event.get_original_event().revision,
-                    event.get_original_event().stream_id
-                );
-                cols.push(Cell::from(name.as_str()).style(Style::default().fg(Color::Gray)));
-                cols.push(
-                    Cell::from(target_event.event_type.clone())
-                        .style(Style::default().fg(Color::Gray)),
-                );
-                cols.push(
-                    Cell::from(target_event.created.to_string())
-                        .style(Style::default().fg(Color::Gray)),
-                );
+            for event in all_stream {
+                let stream_id = &event.get_original_event().stream_id;
 
-                rows.push(Row::new(cols));
+                if event.get_original_event().event_type == "$streamDeleted" {
+                    tombstoned.push(stream_id.clone());
+                }
 
-                let table = Table::new(rows)
-                    .header(header)
-                    .block(
-                        Block::default()
-                            .borders(Borders::TOP)
-                            .title(format!("Event '{}'", name))
-                            .title_alignment(Alignment::Right),
-                    )
-                    .highlight_style(ctx.selected_style)
-                    .widths(&[
-                        Constraint::Percentage(25),
-                        Constraint::Percentage(25),
-                        Constraint::Percentage(25),
-                        Constraint::Percentage(25),
-                    ]);
+                if recently_changed.contains(stream_id) {
+                    continue;
+                }
 
-                self.stream_table_state.select(Some(self.selected));
+                recently_changed.push(stream_id.clone());
+            }
 
-                frame.render_stateful_widget(table, rects[0], &mut Default::default());
+            Ok(Msg::Streams(StreamsMsg::Streams {
+                last_created,
+                recently_changed,
+                tombstoned,
+            }))
+        });
 
-                let content = if event.event.as_ref().unwrap().is_json {
-                    let json =
-                        serde_json::from_slice::<serde_json::Value>(target_event.data.as_ref())
-                            .unwrap();
+        Ok(())
+    }
 
-                    let content = serde_json::to_string_pretty(&json).unwrap();
-                    render_line_numbers(content.as_str())
-                } else {
-                    "<BINARY>".to_string()
-                };
+    fn load_stream_names(&mut self, env: &Env) {
+        let backend = env.backend.clone();
 
-                let text = Text::from(content);
+        env.spawn(async move {
+            let request = ReadRequest::default()
+                .max_count(STREAM_NAMES_LIMIT)
+                .position(StreamPosition::End)
+                .backwards();
+
+            let links = match backend.read_stream("$streams".to_string(), request).await {
+                Err(eventstore::Error::ResourceNotFound) => Vec::new(),
+                other => other?,
+            };
+
+            let names = links.iter().filter_map(linked_stream_name).collect();
+
+            Ok(Msg::Streams(StreamsMsg::StreamNames(names)))
+        });
+    }
+
+    /// Remembers the names of the streams the events are from.
+    fn observe_streams<'a>(&mut self, events: impl IntoIterator<Item = &'a ResolvedEvent>) {
+        for event in events {
+            let stream_id = &event.get_original_event().stream_id;
+
+            if !self.known_streams.contains(stream_id) {
+                self.known_streams.insert(stream_id.clone());
+            }
+        }
+    }
+}
+
+impl View for StreamsView {
+    fn load(&mut self, env: &Env) -> eventstore::Result<()> {
+        self.load_streams(env)
+    }
+
+    fn unload(&mut self, _env: &Env) {
+        self.selected = 0;
+        self.selected_tab = 0;
+        self.scroll = 0;
+        self.preview_tree = None;
+        self.marked.clear();
+        self.diff = None;
+        self.stream_names_loaded = false;
+        self.stage = Stage::Main;
+        self.model.clear();
+        self.tail = None;
+        self.metadata_form = None;
+        self.append_form = None;
+        self.delete_dialog = None;
+        self.all_filter_form = None;
+        self.export_dialog = None;
+        self.import_dialog = None;
+        self.last_error = None;
+    }
+
+    fn refresh(&mut self, env: &Env) -> eventstore::Result<()> {
+        if let Stage::StreamPreview | Stage::PreviewSearch | Stage::Diff = self.stage {
+            return Ok(());
+        }
+
+        if self.stage == Stage::Search {
+            if !std::mem::replace(&mut self.stream_names_loaded, true) {
+                self.load_stream_names(env);
+            }
+
+            return Ok(());
+        }
+
+        if self.stage == Stage::Tail {
+            if self.tail.is_none() {
+                self.start_tail(env);
+            }
+
+            return Ok(());
+        }
+
+        if let Stage::Metadata | Stage::EditMetadata = self.stage {
+            self.refresh_metadata(env);
+
+            return Ok(());
+        }
+
+        if self.stage == Stage::Delete {
+            self.delete(env);
+
+            // The stream may not be readable anymore.
+            return Ok(());
+        }
+
+        if self.stage == Stage::Export {
+            self.export(env);
+
+            return Ok(());
+        }
+
+        if self.stage == Stage::Import {
+            self.import(env);
+
+            return Ok(());
+        }
+
+        let submitted = self
+            .append_form
+            .as_mut()
+            .and_then(|form| form.take_submitted());
+
+        if let (Some((expected, event)), Some(stream_name)) =
+            (submitted, self.model.selected_stream.clone())
+        {
+            let backend = env.backend.clone();
+
+            env.spawn(async move {
+                let result = backend
+                    .append_to_stream(stream_name.clone(), expected, vec![event])
+                    .await;
+
+                Ok(Msg::Streams(StreamsMsg::Appended(stream_name, result)))
+            });
+        }
+
+        if let (Some(query), true) = (self.model.all_query.clone(), self.model.is_all()) {
+            if self.model.all_query_read.as_ref() == Some(&query) {
+                return Ok(());
+            }
+
+            let backend = env.backend.clone();
+            self.model.all_query_read = Some(query.clone());
+
+            env.spawn(async move {
+                let result = backend
+                    .read_all_filtered(query.from, query.filter.clone(), PAGE_SIZE as usize)
+                    .await;
+
+                Ok(Msg::Streams(StreamsMsg::AllEvents(query, result)))
+            });
+
+            return Ok(());
+        }
+
+        if let Some(stream_name) = self.model.selected_stream.clone() {
+            let backend = env.backend.clone();
+            let page = self.model.page;
+            let paginated = self.model.is_paginated();
+
+            env.spawn(async move {
+                let result = if paginated {
+                    read_page(backend, stream_name.clone(), page).await
+                } else {
+                    let request = ReadRequest::default()
+                        .max_count(PAGE_SIZE as usize)
+                        .resolve_link_tos()
+                        .position(StreamPosition::End)
+                        .backwards();
+
+                    backend.read_all(request).await.map(|events| EventsPage {
+                        events,
+                        last_revision: None,
+                    })
+                };
+
+                Ok(Msg::Streams(StreamsMsg::Events(stream_name, page, result)))
+            });
+
+            Ok(())
+        } else {
+            self.load_streams(env)
+        }
+    }
+
+    fn on_msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::Streams(StreamsMsg::Streams {
+                last_created,
+                recently_changed,
+                tombstoned,
+            }) => {
+                let names = last_created.iter().chain(recently_changed.iter());
+                self.known_streams
+                    .extend(names.filter(|name| !name.is_empty()).cloned());
+
+                self.model.last_created = last_created;
+                self.model.recently_changed = recently_changed;
+                self.model.tombstoned.extend(tombstoned);
+            }
+
+            Msg::Streams(StreamsMsg::StreamNames(names)) => self.known_streams.extend(names),
+
+            Msg::Streams(StreamsMsg::Events(stream_name, page, result)) => {
+                if let Ok(page) = result.as_ref() {
+                    if stream_name == "$all" {
+                        self.observe_streams(page.events.iter());
+                    } else {
+                        self.known_streams.insert(stream_name.clone());
+                    }
+                }
+
+                if self.model.selected_stream.as_ref() != Some(&stream_name)
+                    || self.model.page != page
+                    || self.model.all_query.is_some()
+                    || self.tail.is_some()
+                {
+                    return;
+                }
+
+                self.set_events(stream_name, result);
+            }
+
+            Msg::Streams(StreamsMsg::AllEvents(query, result)) => {
+                if let Ok(page) = result.as_ref() {
+                    self.observe_streams(page.events.iter());
+                }
+
+                if !self.model.is_all()
+                    || self.model.all_query.as_ref() != Some(&query)
+                    || self.tail.is_some()
+                {
+                    return;
+                }
+
+                self.model.all_partial = matches!(result, Ok(FilteredPage { partial: true, .. }));
+
+                let result = result.map(|page| EventsPage {
+                    events: page.events,
+                    last_revision: None,
+                });
+
+                self.set_events("$all".to_string(), result);
+            }
+
+            Msg::Streams(StreamsMsg::Tailed(stream_name, result)) => {
+                if let Ok(event) = result.as_ref() {
+                    self.observe_streams(std::iter::once(event));
+                }
+
+                if self.model.selected_stream.as_ref() != Some(&stream_name) {
+                    return;
+                }
+
+                let frozen = self.stage != Stage::Tail;
+                let tail = match self.tail.as_mut() {
+                    Some(tail) => tail,
+                    None => return,
+                };
+
+                match result {
+                    Err(e) => {
+                        self.tail = None;
+                        self.last_error = Some(e);
+
+                        if !frozen {
+                            self.stage = Stage::Stream;
+                        }
+                    }
+
+                    Ok(event) => {
+                        tail.arrivals.push_back(Instant::now());
+
+                        if tail.paused || frozen {
+                            tail.held.push(event);
+
+                            let overflow = tail.held.len().saturating_sub(TAIL_BUFFER_SIZE);
+                            tail.held.drain(..overflow);
+                        } else {
+                            self.append_tailed(vec![event]);
+                        }
+                    }
+                }
+            }
+
+            Msg::Streams(StreamsMsg::Metadata(stream_name, result)) => {
+                if self.model.selected_stream.as_ref() != Some(&stream_name) {
+                    return;
+                }
+
+                match result {
+                    Err(e) => self.last_error = Some(e),
+                    Ok(metadata) => {
+                        self.model.metadata = metadata;
+                        self.model.metadata_loaded = true;
+                    }
+                }
+            }
+
+            Msg::Streams(StreamsMsg::MetadataSaved(stream_name, result)) => {
+                if self.model.selected_stream.as_ref() != Some(&stream_name) {
+                    return;
+                }
+
+                let form = match self.metadata_form.as_mut() {
+                    Some(form) => form,
+                    None => return,
+                };
+
+                match result {
+                    Err(eventstore::Error::WrongExpectedVersion { .. }) => form.set_error(
+                        "The metadata changed since it was loaded, cancel to reload it".to_string(),
+                    ),
+                    Err(e) => form.set_error(e.to_string()),
+                    Ok(metadata) => {
+                        self.model.metadata = metadata;
+                        self.model.metadata_loaded = true;
+                        self.metadata_form = None;
+                        self.stage = Stage::Metadata;
+                    }
+                }
+            }
+
+            Msg::Streams(StreamsMsg::Appended(stream_name, result)) => {
+                if self.model.selected_stream.as_ref() != Some(&stream_name) {
+                    return;
+                }
+
+                let form = match self.append_form.as_mut() {
+                    Some(form) => form,
+                    None => return,
+                };
+
+                let outcome = match result {
+                    Ok(result) => {
+                        // Where the appended event shows up once the dialog is closed.
+                        self.model.page = Page::default();
+
+                        Ok(format!(
+                            "Appended at revision {}",
+                            result.next_expected_version
+                        ))
+                    }
+                    Err(e) => Err(write_error(e)),
+                };
+
+                form.set_outcome(outcome);
+            }
+
+            Msg::Streams(StreamsMsg::Deleted(stream_name, result)) => {
+                if self.model.selected_stream.as_ref() != Some(&stream_name) {
+                    return;
+                }
+
+                let dialog = match self.delete_dialog.as_mut() {
+                    Some(dialog) => dialog,
+                    None => return,
+                };
+
+                let outcome = match result {
+                    Ok(()) if dialog.is_tombstone() => {
+                        self.model.tombstoned.insert(stream_name);
+
+                        Ok("Tombstoned, the stream can't be recreated".to_string())
+                    }
+                    Ok(()) => Ok(
+                        "Deleted. Append to the stream again, or reset $tb (truncate \
+                         before) in its metadata, to undelete it"
+                            .to_string(),
+                    ),
+                    Err(eventstore::Error::ResourceDeleted) => {
+                        self.model.tombstoned.insert(stream_name);
+
+                        Err("The stream is already tombstoned".to_string())
+                    }
+                    Err(e) => Err(write_error(e)),
+                };
+
+                dialog.set_outcome(outcome);
+            }
+
+            Msg::Streams(StreamsMsg::Exported(path, progress)) => {
+                if let Some(dialog) = self.export_dialog.as_mut() {
+                    dialog.set_progress(path, progress);
+                }
+            }
+
+            Msg::Streams(StreamsMsg::Imported(path, progress)) => {
+                if let Some(dialog) = self.import_dialog.as_mut() {
+                    dialog.set_progress(path, progress);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, ctx: ViewCtx, frame: &mut Frame<B>, area: Rect) {
+        let stage = match self.stage {
+            Stage::Import => self.import_origin,
+            stage => stage,
+        };
+
+        match stage {
+            Stage::Main | Stage::Search => {
+                let rects = Layout::default()
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                    .direction(Direction::Horizontal)
+                    .margin(2)
+                    .split(area);
+
+                for (idx, name) in HEADERS.iter().enumerate() {
+                    let header_cells = vec![Cell::from(*name).style(ctx.theme.header)];
+                    let header = Row::new(header_cells)
+                        .style(ctx.theme.normal)
+                        .height(1)
+                        .bottom_margin(1);
+
+                    let cells = match idx {
+                        0 => self.model.last_created.iter(),
+                        _ => self.model.recently_changed.iter(),
+                    };
+
+                    if self.selected_tab == idx {
+                        self.main_table_states[idx].select(Some(self.selected));
+                    } else {
+                        self.main_table_states[idx].select(None);
+                    }
+
+                    let rows = cells
+                        .map(|c| {
+                            let mut name = vec![Span::styled(c.as_str(), ctx.theme.text)];
+
+                            if self.model.tombstoned.contains(c) {
+                                name.push(Span::styled(" [tombstoned]", ctx.theme.warning));
+                            }
+
+                            Row::new(vec![Cell::from(Spans::from(name))])
+                        })
+                        .collect::<Vec<_>>();
+
+                    let border_type = if idx == 0 {
+                        Borders::TOP | Borders::RIGHT
+                    } else {
+                        Borders::TOP
+                    };
+
+                    let table = Table::new(rows)
+                        .header(header)
+                        .block(Block::default().borders(border_type))
+                        .highlight_style(ctx.theme.selected)
+                        .widths(&[Constraint::Percentage(100)]);
+
+                    frame.render_stateful_widget(
+                        table,
+                        rects[idx],
+                        &mut self.main_table_states[idx],
+                    );
+
+                    if let Stage::Search = self.stage {
+                        self.search.draw(ctx, frame, &self.known_streams);
+                    }
+                }
+            }
+            Stage::Stream
+            | Stage::Jump
+            | Stage::Tail
+            | Stage::Append
+            | Stage::Delete
+            | Stage::Filter
+            | Stage::BrowseAll
+            | Stage::Export
+            | Stage::Import => {
+                let bar_height = if self.stage == Stage::Filter {
+                    1 + self.filter_error.is_some() as u16
+                } else {
+                    self.model.filter.is_some() as u16
+                };
+
+                let rects = Layout::default()
+                    .constraints([Constraint::Min(0), Constraint::Length(bar_height)].as_ref())
+                    .margin(2)
+                    .split(area);
+
+                let stream_name = self.model.selected_stream.clone().unwrap_or_default();
+
+                let headers = if self.model.is_all() {
+                    ALL_HEADERS
+                } else {
+                    STREAM_HEADERS
+                };
+
+                let header_cells = headers
+                    .iter()
+                    .map(|h| Cell::from(*h).style(ctx.theme.header));
+
+                let header = Row::new(header_cells)
+                    .style(ctx.theme.normal)
+                    .height(1)
+                    .bottom_margin(1);
+
+                let mut rows = Vec::new();
+
+                for event in self.model.displayed_events() {
+                    let mut number = vec![Span::styled(
+                        event_number(self.model.is_all(), event),
+                        ctx.theme.text,
+                    )];
+
+                    if self.is_marked(event) {
+                        number.push(Span::styled(" [marked]", ctx.theme.emphasis));
+                    }
+
+                    let mut cols = vec![Cell::from(Spans::from(number))];
+                    cols.extend(event_cells(ctx.theme, event));
+
+                    rows.push(Row::new(cols));
+                }
+
+                let table = Table::new(rows)
+                    .header(header)
+                    .block(
+                        Block::default()
+                            .borders(Borders::TOP)
+                            .title(self.stream_title(stream_name.as_str()))
+                            .title_alignment(Alignment::Right),
+                    )
+                    .highlight_style(ctx.theme.selected)
+                    .widths(&[
+                        Constraint::Percentage(25),
+                        Constraint::Percentage(25),
+                        Constraint::Percentage(25),
+                        Constraint::Percentage(25),
+                    ]);
+
+                self.stream_table_state.select(Some(self.selected));
+
+                frame.render_stateful_widget(table, rects[0], &mut self.stream_table_state);
+
+                if bar_height > 0 {
+                    self.draw_filter_bar(ctx, frame, rects[1]);
+                }
+
+                if let Stage::Jump = self.stage {
+                    let block = Block::default()
+                        .title("Go to revision")
+                        .borders(Borders::ALL)
+                        .style(ctx.theme.popup);
+                    let area = centered_rect(40, 15, frame.size());
+                    frame.render_widget(Clear, area);
+                    frame.render_widget(block, area);
+
+                    let layout = Layout::default()
+                        .margin(2)
+                        .constraints([Constraint::Percentage(100)])
+                        .split(area);
+
+                    self.revision.draw(ctx, frame, layout[0], "Revision: ");
+                }
+
+                if let Some(form) = self.append_form.as_ref() {
+                    form.draw(ctx, frame, stream_name.as_str());
+                }
+
+                if let Some(dialog) = self.delete_dialog.as_ref() {
+                    dialog.draw(ctx, frame);
+                }
+
+                if let Some(form) = self.all_filter_form.as_ref() {
+                    form.draw(ctx, frame);
+                }
+
+                if let Some(dialog) = self.export_dialog.as_ref() {
+                    dialog.draw(ctx, frame);
+                }
+            }
+            Stage::Diff => {
+                if let Some(diff) = self.diff.as_mut() {
+                    diff.draw(ctx, frame, area);
+                }
+            }
+            Stage::StreamPreview | Stage::PreviewSearch => {
+                let rects = Layout::default()
+                    .constraints(
+                        [
+                            Constraint::Length(4),
+                            Constraint::Length(1),
+                            Constraint::Min(0),
+                        ]
+                        .as_ref(),
+                    )
+                    .margin(2)
+                    .split(area);
+
+                let headers = if self.model.is_all() {
+                    ALL_HEADERS
+                } else {
+                    STREAM_HEADERS
+                };
+
+                let header_cells = headers
+                    .iter()
+                    .map(|h| Cell::from(*h).style(ctx.theme.header));
+
+                let header = Row::new(header_cells)
+                    .style(ctx.theme.normal)
+                    .height(1)
+                    .bottom_margin(1);
+
+                let mut rows = Vec::new();
+                let event = match self.model.displayed_event(self.selected) {
+                    Some(event) => event,
+                    None => return,
+                };
+                let mut cols = Vec::new();
+
+                cols.push(
+                    Cell::from(event_number(self.model.is_all(), event)).style(ctx.theme.text),
+                );
+
+                let name = format!(
+                    "{}@{}",
+                    event.get_original_event().revision,
+                    event.get_original_event().stream_id
+                );
+                cols.push(Cell::from(name.as_str()).style(ctx.theme.text));
+                cols.extend(event_cells(ctx.theme, event).into_iter().skip(1));
+
+                rows.push(Row::new(cols));
+
+                let table = Table::new(rows)
+                    .header(header)
+                    .block(
+                        Block::default()
+                            .borders(Borders::TOP)
+                            .title(format!("Event '{}'", name))
+                            .title_alignment(Alignment::Right),
+                    )
+                    .highlight_style(ctx.theme.selected)
+                    .widths(&[
+                        Constraint::Percentage(25),
+                        Constraint::Percentage(25),
+                        Constraint::Percentage(25),
+                        Constraint::Percentage(25),
+                    ]);
+
+                self.stream_table_state.select(Some(self.selected));
+
+                frame.render_stateful_widget(table, rects[0], &mut Default::default());
+
+                let titles = PREVIEW_PANES
+                    .iter()
+                    .map(|t| Spans::from(Span::styled(*t, ctx.theme.tab)))
+                    .collect();
+
+                let tabs = Tabs::new(titles)
+                    .select(self.pane)
+                    .style(ctx.theme.text)
+                    .highlight_style(ctx.theme.selected_tab);
+
+                frame.render_widget(tabs, rects[1]);
+
+                let (notes, content) = preview_pane(ctx.theme, &self.decoders, self.pane, event);
+                let event_id = event.get_original_event().id;
+
+                let mut text = match content {
+                    PaneContent::Json(value) => {
+                        let tree = match self.preview_tree.as_mut() {
+                            Some((id, pane, tree)) if *id == event_id && *pane == self.pane => {
+                                tree.set_value(value);
+                                tree
+                            }
+                            _ => {
+                                let tree = JsonTree::new(value);
+                                &mut self.preview_tree.insert((event_id, self.pane, tree)).2
+                            }
+                        };
+
+                        let block = Block::default().borders(Borders::BOTTOM | Borders::TOP);
+                        let inner = block.inner(rects[2]);
+                        let notes_height = notes.len() as u16;
+                        let layout = Layout::default()
+                            .constraints([Constraint::Length(notes_height), Constraint::Min(0)])
+                            .split(inner);
+
+                        frame.render_widget(block, rects[2]);
+                        frame.render_widget(Paragraph::new(notes), layout[0]);
+                        tree.draw(ctx, frame, layout[1], Block::default());
+
+                        return;
+                    }
+                    PaneContent::Text(text) => text,
+                };
+
+                self.preview_tree = None;
+                text.lines.splice(0..0, notes);
+
+                // Hex dumps of large payloads can have more lines than `u16` counts.
+                let height = u16::try_from(text.height())
+                    .unwrap_or(u16::MAX)
+                    .saturating_add(2);
+
+                if rects[2].height >= height {
+                    // We lock scrolling as everything is visible.
+                    self.scroll = 0;
+                } else if self.scroll > height.saturating_sub(rects[2].height) {
+                    // We cap how much we can scroll. It will be difficult to do that part during
+                    // the refresh call as the user might have resized the terminal.
+                    self.scroll = height.saturating_sub(rects[2].height);
+                }
+
+                let paragraph = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(Block::default().borders(Borders::BOTTOM | Borders::TOP))
+                    .scroll((self.scroll, 0));
+
+                frame.render_widget(paragraph, rects[2])
+            }
+            Stage::Metadata | Stage::EditMetadata => {
+                let rects = Layout::default()
+                    .constraints([Constraint::Percentage(100)].as_ref())
+                    .margin(2)
+                    .split(area);
+
+                let stream_name = self.model.selected_stream.clone().unwrap_or_default();
+                let mut title = format!("Stream metadata '{}'", stream_name);
+
+                let lines = if !self.model.metadata_loaded {
+                    vec![Spans::from("Loading...")]
+                } else if let Some(versioned) = self.model.metadata.as_ref() {
+                    title.push_str(format!(" | revision {}", versioned.revision).as_str());
+                    metadata_lines(ctx.theme, &versioned.metadata)
+                } else {
+                    title.push_str(" | not set");
+                    metadata_lines(ctx.theme, &Default::default())
+                };
+
+                let paragraph = Paragraph::new(lines).block(
+                    Block::default()
+                        .borders(Borders::TOP)
+                        .title(title)
+                        .title_alignment(Alignment::Right),
+                );
+
+                frame.render_widget(paragraph, rects[0]);
+
+                if let Some(form) = self.metadata_form.as_ref() {
+                    form.draw(ctx, frame, stream_name.as_str());
+                }
+            }
+        }
+
+        if let Some(dialog) = self.import_dialog.as_ref() {
+            dialog.draw(ctx, frame);
+        }
+
+        if let Some(e) = self.last_error.as_ref() {
+            let stream_name = self.model.selected_stream.clone().unwrap_or_default();
+            let block = Block::default()
+                .title("Error")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .style(ctx.theme.warning);
+            let area = centered_rect(40, 20, frame.size());
+            frame.render_widget(Clear, area);
+            frame.render_widget(block, area);
+
+            let rect = Layout::default()
+                .margin(2)
+                .constraints([Constraint::Percentage(100)])
+                .direction(Direction::Horizontal)
+                .split(area)[0];
+
+            let mut message = format!("Stream '{}': {}.", stream_name, e);
+
+            if let eventstore::Error::ResourceNotFound = e {
+                if let Some(name) = closest(stream_name.as_str(), &self.known_streams) {
+                    message.push_str(format!(" Did you mean '{}'?", name).as_str());
+                }
+            }
+
+            let label = Paragraph::new(message)
+                .style(ctx.theme.text)
+                .wrap(Wrap { trim: false });
+
+            frame.render_widget(label, rect);
+        }
+    }
+
+    fn on_action(&mut self, action: Action) -> Request {
+        if self.last_error.is_some() {
+            if action == Action::Back {
+                self.last_error = None;
+                self.stage = Stage::Main;
+                self.model.selected_stream = None;
+            }
+
+            return Request::Noop;
+        }
+
+        match action {
+            Action::Back => match self.stage {
+                Stage::Main => {}
+                Stage::Search => self.stage = Stage::Main,
+                Stage::Jump => self.stage = Stage::Stream,
+                Stage::Stream => {
+                    self.stage = Stage::Main;
+                    self.selected = 0;
+                    self.marked.clear();
+                }
+                Stage::Diff => {
+                    self.diff = None;
+                    self.stage = Stage::Stream;
+                }
+                Stage::StreamPreview => {
+                    self.scroll = 0;
+                    self.preview_tree = None;
+
+                    if self.tail.is_some() {
+                        self.stage = Stage::Tail;
+                        self.flush_held();
+                    } else {
+                        self.stage = Stage::Stream;
+                    }
+                }
+                Stage::PreviewSearch => {
+                    if let Some((_, _, tree)) = self.preview_tree.as_mut() {
+                        tree.cancel_search();
+                    }
+
+                    self.stage = Stage::StreamPreview;
+                }
+                Stage::Tail => {
+                    self.tail = None;
+                    self.stage = Stage::Main;
+                    self.selected = 0;
+                }
+                Stage::Metadata => self.stage = Stage::Stream,
+                Stage::EditMetadata => {
+                    self.metadata_form = None;
+                    self.stage = Stage::Metadata;
+
+                    return Request::Refresh;
+                }
+                Stage::Append => {
+                    self.append_form = None;
+                    self.stage = Stage::Stream;
+
+                    return Request::Refresh;
+                }
+                Stage::Filter => {
+                    self.filter_error = None;
+                    self.stage = Stage::Stream;
+                }
+                Stage::BrowseAll => {
+                    self.all_filter_form = None;
+                    self.stage = Stage::Stream;
+                }
+                Stage::Export => {
+                    // A running export is cancelled first, the dialog stays open to tell so.
+                    let cancelled = match self.export_dialog.as_mut() {
+                        Some(dialog) => dialog.cancel(),
+                        None => false,
+                    };
+
+                    if !cancelled {
+                        self.export_dialog = None;
+                        self.stage = Stage::Stream;
+                    }
+                }
+                Stage::Import => {
+                    let dialog = match self.import_dialog.as_mut() {
+                        Some(dialog) => dialog,
+                        None => {
+                            self.stage = self.import_origin;
+                            return Request::Noop;
+                        }
+                    };
+
+                    if dialog.cancel() {
+                        return Request::Noop;
+                    }
+
+                    let imported = dialog.is_imported();
+
+                    self.import_dialog = None;
+                    self.stage = self.import_origin;
+
+                    // Shows the imported events.
+                    if imported {
+                        self.model.page = Page::default();
+
+                        return Request::Refresh;
+                    }
+                }
+                Stage::Delete => {
+                    let deleted = self
+                        .delete_dialog
+                        .take()
+                        .map(|dialog| (dialog.is_deleted(), dialog.is_tombstone()));
+
+                    match deleted {
+                        // Nothing is left to display.
+                        Some((true, true)) => {
+                            self.stage = Stage::Main;
+                            self.model.selected_stream = None;
+                            self.selected = 0;
+
+                            return Request::Refresh;
+                        }
+                        // Stays on the stream so it can be undeleted.
+                        Some((true, false)) => {
+                            self.stage = Stage::Stream;
+                            self.model.selected_stream_events.clear();
+                            self.model.last_revision = None;
+                            self.model.refilter();
+                            self.selected = 0;
+                        }
+                        _ => self.stage = Stage::Stream,
+                    }
+                }
+            },
+
+            Action::Up | Action::Down if self.stage == Stage::Append => {
+                if let Some(form) = self.append_form.as_mut() {
+                    if action == Action::Up {
+                        form.previous();
+                    } else {
+                        form.next();
+                    }
+                }
+            }
+
+            Action::Submit if self.stage == Stage::Append => {
+                if let Some(form) = self.append_form.as_mut() {
+                    form.submit();
+                }
+
+                return Request::Refresh;
+            }
+
+            Action::Append if self.stage == Stage::Stream && !self.model.is_all() => {
+                self.append_form = Some(AppendForm::default());
+                self.stage = Stage::Append;
+            }
+
+            Action::Delete | Action::Tombstone
+                if self.stage == Stage::Stream && !self.model.is_all() =>
+            {
+                let stream_name = self.model.selected_stream.clone().unwrap_or_default();
+
+                self.delete_dialog =
+                    Some(DeleteDialog::new(stream_name, action == Action::Tombstone));
+                self.stage = Stage::Delete;
+            }
+
+            Action::Filter if self.stage == Stage::Stream => self.stage = Stage::Filter,
+
+            Action::Export if self.stage == Stage::Stream => {
+                let stream_name = self.model.selected_stream.clone().unwrap_or_default();
+
+                self.export_dialog = Some(ExportDialog::new(stream_name));
+                self.stage = Stage::Export;
+            }
+
+            Action::Import if matches!(self.stage, Stage::Main | Stage::Stream) => {
+                let stream_name = if self.stage == Stage::Stream && !self.model.is_all() {
+                    self.model.selected_stream.clone()
+                } else {
+                    None
+                };
+
+                self.import_dialog = Some(ImportDialog::new(stream_name));
+                self.import_origin = self.stage;
+                self.stage = Stage::Import;
+            }
+
+            Action::Up | Action::Down if self.stage == Stage::Import => {
+                if let Some(dialog) = self.import_dialog.as_mut() {
+                    if action == Action::Up {
+                        dialog.previous();
+                    } else {
+                        dialog.next();
+                    }
+                }
+            }
+
+            Action::Up | Action::Down if self.stage == Stage::Export => {
+                if let Some(dialog) = self.export_dialog.as_mut() {
+                    if action == Action::Up {
+                        dialog.previous();
+                    } else {
+                        dialog.next();
+                    }
+                }
+            }
+
+            Action::Up | Action::Down if self.stage == Stage::BrowseAll => {
+                if let Some(form) = self.all_filter_form.as_mut() {
+                    if action == Action::Up {
+                        form.previous();
+                    } else {
+                        form.next();
+                    }
+                }
+            }
+
+            Action::Jump if self.stage == Stage::Stream && self.model.is_all() => {
+                self.all_filter_form = Some(AllFilterForm::new(self.model.all_query.as_ref()));
+                self.stage = Stage::BrowseAll;
+            }
+
+            Action::PageUp | Action::PageDown | Action::First | Action::Last
+                if self.stage == Stage::Stream
+                    && self.model.is_all()
+                    && self.model.all_query.is_some() =>
+            {
+                match action {
+                    Action::PageDown => {
+                        let last = self
+                            .model
+                            .selected_stream_events
+                            .last()
+                            .map(|e| e.get_original_event().position);
+
+                        if let (Some(last), Some(query)) = (last, self.model.all_query.as_ref()) {
+                            self.model.all_history.push(query.from);
+
+                            return self.read_all_from(StreamPosition::Position(last));
+                        }
+                    }
+                    Action::PageUp => {
+                        if let Some(from) = self.model.all_history.pop() {
+                            return self.read_all_from(from);
+                        }
+                    }
+                    Action::First => {
+                        self.model.all_history.clear();
+
+                        return self.read_all_from(StreamPosition::Start);
+                    }
+                    _ => {
+                        // Back to the last events.
+                        self.model.all_query = None;
+                        self.model.all_history.clear();
+                        self.selected = 0;
+
+                        return Request::Refresh;
+                    }
+                }
+            }
 
-                if rects[1].height >= 2 + text.height() as u16 {
-                    // We lock scrolling as everything is visible.
-                    self.scroll = 0;
-                } else if self.scroll > (2 + text.height() as u16) - rects[1].height {
-                    // We cap how much we can scroll. It will be difficult to do that part during
-                    // the refresh call as the user might have resized the terminal.
-                    self.scroll = (2 + text.height() as u16) - rects[1].height;
+            Action::Up | Action::Down if self.stage == Stage::Delete => {
+                if let Some(dialog) = self.delete_dialog.as_mut() {
+                    dialog.toggle_focus();
                 }
+            }
 
-                let paragraph = Paragraph::new(text)
-                    .alignment(Alignment::Left)
-                    .block(Block::default().borders(Borders::BOTTOM | Borders::TOP))
-                    .scroll((self.scroll, 0));
+            Action::Up | Action::Down if self.stage == Stage::EditMetadata => {
+                if let Some(form) = self.metadata_form.as_mut() {
+                    if action == Action::Up {
+                        form.previous();
+                    } else {
+                        form.next();
+                    }
+                }
+            }
 
-                frame.render_widget(paragraph, rects[1])
+            Action::Mark if self.stage == Stage::Stream => {
+                let event = match self.model.displayed_event(self.selected) {
+                    Some(event) => event.clone(),
+                    None => return Request::Noop,
+                };
+
+                if self.is_marked(&event) {
+                    let id = event.get_original_event().id;
+                    self.marked.retain(|e| e.get_original_event().id != id);
+                } else {
+                    // Marking a third event forgets the oldest mark.
+                    if self.marked.len() == 2 {
+                        self.marked.remove(0);
+                    }
+
+                    self.marked.push(event);
+                }
             }
-        }
 
-        if let Some(e) = self.last_error.as_ref() {
-            let stream_name = self.model.selected_stream.clone().unwrap_or_default();
-            let block = Block::default()
-                .title("Error")
-                .title_alignment(Alignment::Center)
-                .borders(Borders::ALL)
-                .style(Style::default().bg(Color::Black).fg(Color::Yellow));
-            let area = centered_rect(40, 20, frame.size());
-            frame.render_widget(Clear, area);
-            frame.render_widget(block, area);
+            Action::FollowLink => {
+                let target = self
+                    .model
+                    .displayed_event(self.selected)
+                    .and_then(link_target);
 
-            let rect = Layout::default()
-                .margin(2)
-                .constraints([Constraint::Percentage(100)])
-                .direction(Direction::Horizontal)
-                .split(area)[0];
+                if let Some((stream_name, revision)) = target {
+                    self.open(stream_name.as_str());
 
-            let message = format!("Stream '{}': {}. Press 'q' to close.", stream_name, e);
+                    return self.load_page(Page::forwards(StreamPosition::Position(revision)));
+                }
+            }
 
-            let label = Paragraph::new(message)
-                .style(Style::default().fg(Color::Gray))
-                .wrap(Wrap { trim: false });
+            // Two marked events are compared, a single one is compared with the selected event.
+            Action::Diff if self.stage == Stage::Stream => {
+                let selected = self.model.displayed_event(self.selected);
 
-            frame.render_widget(label, rect);
-        }
-    }
+                let (left, right) = match (self.marked.as_slice(), selected) {
+                    ([left, right], _) => (left, right),
+                    ([marked], Some(selected)) if !self.is_marked(selected) => (marked, selected),
+                    _ => return Request::Noop,
+                };
 
-    fn on_key_pressed(&mut self, key: KeyCode) -> Request {
-        if self.last_error.is_some() {
-            if let KeyCode::Char('q' | 'Q') = key {
-                self.last_error = None;
-                self.stage = Stage::Main;
-                self.model.selected_stream = None;
+                self.diff = Some(EventDiff::new(&self.decoders, left, right));
+                self.stage = Stage::Diff;
             }
 
-            return Request::Noop;
-        }
+            Action::Up
+            | Action::Down
+            | Action::Left
+            | Action::Right
+            | Action::PageUp
+            | Action::PageDown
+            | Action::ScrollLeft
+            | Action::ScrollRight
+                if self.stage == Stage::Diff =>
+            {
+                let diff = match self.diff.as_mut() {
+                    Some(diff) => diff,
+                    None => return Request::Noop,
+                };
 
-        if self.stage == Stage::Search {
-            match key {
-                KeyCode::Esc => self.stage = Stage::Main,
-                KeyCode::Backspace => {
-                    self.buffer.pop();
-                }
-                KeyCode::Enter => {
-                    self.selected = 0;
-                    self.stage = Stage::Stream;
-                    self.model.selected_stream =
-                        Some(std::mem::replace(&mut self.buffer, Default::default()));
-                    return Request::Refresh;
+                match action {
+                    Action::Up => diff.scroll_up(1),
+                    Action::Down => diff.scroll_down(1),
+                    Action::Left => diff.previous_pane(),
+                    Action::Right => diff.next_pane(),
+                    Action::PageUp => diff.scroll_up(diff.page()),
+                    Action::PageDown => diff.scroll_down(diff.page()),
+                    Action::ScrollLeft => diff.scroll_left(),
+                    _ => diff.scroll_right(),
                 }
-                KeyCode::Char(c) if c.is_ascii() => self.buffer.push(c),
-                _ => {}
             }
 
-            return Request::Noop;
-        }
+            Action::Left | Action::Right if self.stage == Stage::StreamPreview => {
+                let offset = if action == Action::Right {
+                    1
+                } else {
+                    PREVIEW_PANES.len() - 1
+                };
 
-        match key {
-            KeyCode::Char('q' | 'Q') => {
-                return match self.stage {
-                    Stage::Main => Request::Exit,
-                    Stage::Search => Request::Noop,
-                    Stage::Stream => {
-                        self.stage = Stage::Main;
-                        self.selected = 0;
-                        Request::Noop
-                    }
-                    Stage::StreamPreview => {
-                        self.scroll = 0;
-                        self.stage = Stage::Stream;
-                        Request::Noop
+                self.pane = (self.pane + offset) % PREVIEW_PANES.len();
+                self.scroll = 0;
+            }
+
+            Action::Up
+            | Action::Down
+            | Action::PageUp
+            | Action::PageDown
+            | Action::First
+            | Action::Last
+            | Action::Fold
+            | Action::ScrollLeft
+            | Action::ScrollRight
+            | Action::Search
+            | Action::NextMatch
+            | Action::PreviousMatch
+                if self.stage == Stage::StreamPreview && self.preview_tree.is_some() =>
+            {
+                let tree = match self.preview_tree.as_mut() {
+                    Some((_, _, tree)) => tree,
+                    None => return Request::Noop,
+                };
+
+                match action {
+                    Action::Up => tree.up(),
+                    Action::Down => tree.down(),
+                    Action::PageUp => tree.page_up(),
+                    Action::PageDown => tree.page_down(),
+                    Action::First => tree.first(),
+                    Action::Last => tree.last(),
+                    Action::Fold => tree.toggle_fold(),
+                    Action::ScrollLeft => tree.scroll_left(),
+                    Action::ScrollRight => tree.scroll_right(),
+                    Action::NextMatch => tree.next_match(),
+                    Action::PreviousMatch => tree.previous_match(),
+                    _ => {
+                        tree.start_search();
+                        self.stage = Stage::PreviewSearch;
                     }
                 }
             }
 
-            KeyCode::Char('/') => {
+            Action::Search => {
                 if self.stage == Stage::Main {
+                    self.search.reset();
                     self.stage = Stage::Search;
+
+                    return Request::Refresh;
                 }
             }
-            KeyCode::Left | KeyCode::Right => {
+
+            Action::Left | Action::Right => {
                 self.selected_tab = (self.selected_tab + 1) % 2;
                 self.selected = 0;
             }
 
-            KeyCode::Up => {
+            Action::Up => {
+                if let Some(tail) = self.tail.as_mut() {
+                    tail.follow = false;
+                }
+
                 if self.stage == Stage::StreamPreview {
                     if self.scroll > 0 {
                         self.scroll -= 1;
                     }
                 } else if self.selected > 0 {
                     self.selected -= 1;
+                } else if self.stage == Stage::Stream && self.model.is_paginated() {
+                    if let Some(page) = self.model.previous_page() {
+                        self.model.select_last = true;
+                        return self.load_page(page);
+                    }
                 }
             }
 
-            KeyCode::Down => match self.stage {
+            Action::Down => match self.stage {
                 Stage::Main => {
                     let len = if self.selected_tab == 0 {
                         self.model.last_created.len()
@@ -515,41 +2137,232 @@ impl View for StreamsView {
                         self.model.recently_changed.len()
                     };
 
-                    if self.selected < len - 1 {
+                    if self.selected + 1 < len {
                         self.selected += 1;
                     }
                 }
                 Stage::Stream => {
-                    if self.selected < self.model.selected_stream_events.len() - 1 {
+                    if self.selected + 1 < self.model.displayed_len() {
                         self.selected += 1;
+                    } else if self.model.is_paginated() {
+                        if let Some(page) = self.model.next_page() {
+                            return self.load_page(page);
+                        }
                     }
                 }
                 Stage::StreamPreview => {
-                    self.scroll += 1;
+                    self.scroll = self.scroll.saturating_add(1);
+                }
+                Stage::Tail => {
+                    let len = self.model.selected_stream_events.len();
+
+                    if self.selected + 1 < len {
+                        self.selected += 1;
+                    }
+
+                    // Scrolling back to the newest event follows again.
+                    if let Some(tail) = self.tail.as_mut() {
+                        tail.follow = self.selected + 1 >= len;
+                    }
                 }
 
                 _ => {}
             },
 
-            KeyCode::Enter => {
-                if self.stage == Stage::Main {
-                    self.stage = Stage::Stream;
-
+            Action::Select => match self.stage {
+                Stage::Main => {
                     let rows = if self.selected_tab == 0 {
                         &self.model.last_created
                     } else {
                         &self.model.recently_changed
                     };
 
-                    self.model.selected_stream = Some(rows[self.selected].clone());
-                    self.selected = 0;
+                    if let Some(stream_name) = rows.get(self.selected) {
+                        self.stage = Stage::Stream;
+                        self.model.selected_stream = Some(stream_name.clone());
+                        self.model.page = Page::default();
+                        self.selected = 0;
+                        self.reset_filters();
+
+                        return Request::Refresh;
+                    }
+                }
+
+                Stage::Search => {
+                    if let Some(name) = self.search.submit(&self.known_streams) {
+                        return self.open(name.as_str());
+                    }
+                }
+
+                Stage::Stream | Stage::Tail => {
+                    if self.selected < self.model.displayed_len() {
+                        self.stage = Stage::StreamPreview;
+
+                        return Request::Refresh;
+                    }
+                }
+
+                Stage::Jump => {
+                    self.stage = Stage::Stream;
+
+                    let position = match self.revision.take().parse::<u64>() {
+                        Ok(revision) => StreamPosition::Position(revision),
+                        Err(_) => StreamPosition::Start,
+                    };
+
+                    return self.load_page(Page::forwards(position));
+                }
+
+                Stage::EditMetadata => {
+                    if let Some(form) = self.metadata_form.as_mut() {
+                        form.submit();
+                    }
+
+                    return Request::Refresh;
+                }
+
+                Stage::Delete => {
+                    if let Some(dialog) = self.delete_dialog.as_mut() {
+                        dialog.submit();
+                    }
+
+                    return Request::Refresh;
+                }
+
+                Stage::Filter => self.apply_filter(),
+
+                Stage::Export => {
+                    if let Some(dialog) = self.export_dialog.as_mut() {
+                        dialog.submit();
+                    }
+
+                    return Request::Refresh;
+                }
+
+                Stage::Import => {
+                    if let Some(dialog) = self.import_dialog.as_mut() {
+                        dialog.submit();
+                    }
 
                     return Request::Refresh;
-                } else if self.stage == Stage::Stream {
+                }
+
+                Stage::BrowseAll => {
+                    let form = match self.all_filter_form.as_mut() {
+                        Some(form) => form,
+                        None => return Request::Noop,
+                    };
+
+                    match form.build() {
+                        Err(e) => form.set_error(e),
+                        Ok(query) => {
+                            self.all_filter_form = None;
+                            self.stage = Stage::Stream;
+                            self.selected = 0;
+                            self.model.all_query = Some(query);
+                            // Read again even when the query didn't change.
+                            self.model.all_query_read = None;
+                            self.model.all_history.clear();
+
+                            return Request::Refresh;
+                        }
+                    }
+                }
+
+                Stage::PreviewSearch => {
+                    if let Some((_, _, tree)) = self.preview_tree.as_mut() {
+                        tree.submit_search();
+                    }
+
                     self.stage = Stage::StreamPreview;
+                }
+
+                Stage::StreamPreview | Stage::Metadata | Stage::Append | Stage::Diff => {}
+            },
+
+            Action::Metadata if self.stage == Stage::Stream => {
+                self.stage = Stage::Metadata;
+                self.model.metadata = None;
+                self.model.metadata_loaded = false;
+
+                return Request::Refresh;
+            }
+
+            Action::Edit if self.stage == Stage::Metadata && self.model.metadata_loaded => {
+                let expected = match self.model.metadata.as_ref() {
+                    Some(versioned) => ExpectedRevision::Exact(versioned.revision),
+                    None => ExpectedRevision::NoStream,
+                };
+
+                let metadata = self
+                    .model
+                    .metadata
+                    .as_ref()
+                    .map(|versioned| versioned.metadata.clone())
+                    .unwrap_or_default();
+
+                self.metadata_form = Some(MetadataForm::new(&metadata, expected));
+                self.stage = Stage::EditMetadata;
+            }
+
+            Action::Tail => match self.stage {
+                Stage::Stream => {
+                    // Only the latest events lead up to the tailed ones.
+                    let mut events = std::mem::take(&mut self.model.selected_stream_events);
+                    if self.model.is_all() && self.model.all_query.is_some() {
+                        // Filtered pages of `$all` are read forwards, they're in order already.
+                    } else if self.model.page == Page::default() {
+                        events.reverse();
+                    } else {
+                        events.clear();
+                    }
+
+                    self.stage = Stage::Tail;
+                    self.tail = None;
+                    self.model.last_revision = None;
+                    self.model.clear_filter();
+                    self.append_tailed(events);
+                    self.selected = self.model.selected_stream_events.len().saturating_sub(1);
 
                     return Request::Refresh;
                 }
+                Stage::Tail => return self.stop_tail(),
+                _ => {}
+            },
+
+            Action::Pause if self.stage == Stage::Tail => {
+                if let Some(tail) = self.tail.as_mut() {
+                    tail.paused = !tail.paused;
+                }
+
+                self.flush_held();
+            }
+
+            Action::Last if self.stage == Stage::Tail => {
+                if let Some(tail) = self.tail.as_mut() {
+                    tail.follow = true;
+                }
+
+                self.selected = self.model.selected_stream_events.len().saturating_sub(1);
+            }
+
+            Action::PageUp | Action::PageDown | Action::First | Action::Last | Action::Jump
+                if self.stage == Stage::Stream && self.model.is_paginated() =>
+            {
+                let page = match action {
+                    Action::PageUp => self.model.previous_page(),
+                    Action::PageDown => self.model.next_page(),
+                    Action::First => Some(Page::forwards(StreamPosition::Start)),
+                    Action::Last => Some(Page::default()),
+                    _ => {
+                        self.stage = Stage::Jump;
+                        None
+                    }
+                };
+
+                if let Some(page) = page {
+                    return self.load_page(page);
+                }
             }
 
             _ => {}
@@ -558,38 +2371,377 @@ impl View for StreamsView {
         Request::Noop
     }
 
-    fn keybindings(&self) -> &[(&str, &str)] {
-        match self.stage {
-            Stage::StreamPreview => &[("↑", "Scroll up"), ("↓", "Scroll down"), ("q", "Close")],
-            Stage::Stream => &[
-                ("↑", "Scroll up"),
-                ("↓", "Scroll down"),
-                ("Enter", "Select"),
-                ("q", "Close"),
-            ],
-            Stage::Main | Stage::Search => &[
-                ("↑", "Scroll up"),
-                ("↓", "Scroll down"),
-                ("→", "Move right"),
-                ("← ", "Move left"),
-                ("/", "Search"),
-                ("Enter", "Select"),
-            ],
+    fn captures_input(&self) -> bool {
+        matches!(
+            self.stage,
+            Stage::Search
+                | Stage::Jump
+                | Stage::EditMetadata
+                | Stage::Append
+                | Stage::Delete
+                | Stage::Filter
+                | Stage::BrowseAll
+                | Stage::Export
+                | Stage::Import
+                | Stage::PreviewSearch
+        ) && self.last_error.is_none()
+    }
+
+    fn open(&mut self, name: &str) -> Request {
+        self.last_error = None;
+        self.selected = 0;
+        self.scroll = 0;
+        self.preview_tree = None;
+        self.marked.clear();
+        self.diff = None;
+        self.stage = Stage::Stream;
+        self.tail = None;
+        self.metadata_form = None;
+        self.append_form = None;
+        self.delete_dialog = None;
+        self.all_filter_form = None;
+        self.export_dialog = None;
+        self.import_dialog = None;
+        self.model.selected_stream = Some(name.to_string());
+        self.model.selected_stream_events.clear();
+        self.model.page = Page::default();
+        self.reset_filters();
+
+        Request::Refresh
+    }
+
+    fn on_key_pressed(&mut self, key: KeyCode) -> Request {
+        match key {
+            // Revisions only.
+            KeyCode::Char(c) if self.stage == Stage::Jump && !c.is_ascii_digit() => {}
+            key if self.stage == Stage::Jump => {
+                self.revision.on_key_pressed(key);
+            }
+            key if self.stage == Stage::Append => {
+                if let Some(form) = self.append_form.as_mut() {
+                    form.on_key_pressed(key);
+                }
+            }
+            key if self.stage == Stage::BrowseAll => {
+                if let Some(form) = self.all_filter_form.as_mut() {
+                    form.on_key_pressed(key);
+                }
+            }
+            key if self.stage == Stage::Filter => {
+                if self.filter.on_key_pressed(key) {
+                    self.filter_error = None;
+                }
+            }
+            key if self.stage == Stage::PreviewSearch => {
+                if let Some((_, _, tree)) = self.preview_tree.as_mut() {
+                    tree.on_key_pressed(key);
+                }
+            }
+            key if self.stage == Stage::Import => {
+                if let Some(dialog) = self.import_dialog.as_mut() {
+                    dialog.on_key_pressed(key);
+                }
+            }
+            key if self.stage == Stage::Export => {
+                if let Some(dialog) = self.export_dialog.as_mut() {
+                    dialog.on_key_pressed(key);
+                }
+            }
+            key if self.stage == Stage::Delete => {
+                if let Some(dialog) = self.delete_dialog.as_mut() {
+                    dialog.on_key_pressed(key);
+                }
+            }
+            key if self.stage == Stage::EditMetadata => {
+                if let Some(form) = self.metadata_form.as_mut() {
+                    form.on_key_pressed(key);
+                }
+            }
+            key => {
+                self.search.on_key_pressed(key, &self.known_streams);
+            }
+        }
+
+        Request::Noop
+    }
+
+    fn stages(&self) -> &'static [StageActions] {
+        STAGES
+    }
+
+    fn is_live(&self) -> bool {
+        self.tail.is_some()
+    }
+
+    fn stage(&self) -> usize {
+        if self.last_error.is_some() {
+            return STAGES.len() - 1;
         }
+
+        self.stage as usize
     }
 }
 
-async fn read_stream_next(
-    stream: &mut eventstore::ReadStream,
-) -> eventstore::Result<Option<eventstore::ResolvedEvent>> {
-    match stream.next().await {
-        Err(e) => {
-            if let eventstore::Error::ResourceNotFound = e {
-                return Ok(None);
+/// Revision of the event, or its position in `$all`.
+fn event_number(is_all: bool, event: &ResolvedEvent) -> String {
+    if is_all {
+        format_position(event.get_original_event().position)
+    } else {
+        event.get_original_event().revision.to_string()
+    }
+}
+
+/// Name, type and creation date of the event a row stands for. Links to an event that's gone,
+/// deleted or scavenged, show where they point to instead.
+fn event_cells(theme: Theme, event: &ResolvedEvent) -> Vec<Cell<'static>> {
+    match event.event.as_ref() {
+        Some(target) => vec![
+            Cell::from(format!("{}@{}", target.revision, target.stream_id)).style(theme.text),
+            Cell::from(target.event_type.clone()).style(theme.text),
+            Cell::from(target.created.to_string()).style(theme.text),
+        ],
+        None => {
+            let link = event.get_original_event();
+
+            vec![
+                Cell::from(format!("→ {}", link_text(link))).style(theme.warning),
+                Cell::from("Unresolved link").style(theme.warning),
+                Cell::from(link.created.to_string()).style(theme.text),
+            ]
+        }
+    }
+}
+
+/// Data of a link, like `0@orders-1`, readable even when malformed.
+fn link_text(link: &RecordedEvent) -> String {
+    String::from_utf8_lossy(link.data.as_ref()).into_owned()
+}
+
+/// Revision and stream of a `0@orders-1` link.
+fn parse_link(data: &[u8]) -> Option<(u64, String)> {
+    let (revision, stream_name) = std::str::from_utf8(data).ok()?.split_once('@')?;
+
+    if stream_name.is_empty() {
+        return None;
+    }
+
+    Some((revision.parse().ok()?, stream_name.to_string()))
+}
+
+/// Stream and revision of the event `event` links to, whether it still exists or not. `None`
+/// when it isn't a link.
+fn link_target(event: &ResolvedEvent) -> Option<(String, u64)> {
+    let original = event.get_original_event();
+
+    match event.event.as_ref() {
+        Some(target) if event.link.is_some() => Some((target.stream_id.clone(), target.revision)),
+        _ if original.event_type == "$>" => parse_link(original.data.as_ref())
+            .map(|(revision, stream_name)| (stream_name, revision)),
+        _ => None,
+    }
+}
+
+/// Name of the stream a `$streams` link points to, `0@orders-1` links to `orders-1`.
+fn linked_stream_name(link: &ResolvedEvent) -> Option<String> {
+    parse_link(link.get_original_event().data.as_ref()).map(|(_, stream_name)| stream_name)
+}
+
+/// Explains why a write was refused.
+fn write_error(error: eventstore::Error) -> String {
+    match error {
+        eventstore::Error::WrongExpectedVersion { current, .. } => match current {
+            CurrentRevision::Current(revision) => format!(
+                "Wrong expected revision, the stream is at revision {}",
+                revision
+            ),
+            CurrentRevision::NoStream => {
+                "Wrong expected revision, the stream doesn't exist".to_string()
+            }
+        },
+        e => e.to_string(),
+    }
+}
+
+/// What the `pane` tab of the event preview shows.
+enum PaneContent {
+    Json(serde_json::Value),
+    Text(Text<'static>),
+}
+
+/// Content of the `pane` tab of the event preview, see `PREVIEW_PANES`, along with notes shown
+/// above it.
+fn preview_pane(
+    theme: Theme,
+    decoders: &Decoders,
+    pane: usize,
+    event: &ResolvedEvent,
+) -> (Vec<Spans<'static>>, PaneContent) {
+    let target = event.event.as_ref().unwrap_or(event.get_original_event());
+
+    let (mut notes, content) = match pane {
+        0 => {
+            let data = decoders.decode(
+                target.event_type.as_str(),
+                target.data.as_ref(),
+                target.is_json,
+            );
+
+            format_payload(theme, data)
+        }
+        1 => format_payload(
+            theme,
+            decoders::sniff(target.custom_metadata.as_ref(), true),
+        ),
+        _ => {
+            let mut lines = event_details(theme, target);
+
+            // Links show the event they point to, along with where the link itself was written.
+            if let (Some(link), Some(_)) = (event.link.as_ref(), event.event.as_ref()) {
+                lines.push(Spans::from(""));
+                lines.push(Spans::from(Span::styled("Link", theme.header)));
+                lines.extend(event_details(theme, link));
+            }
+
+            (Vec::new(), PaneContent::Text(Text::from(lines)))
+        }
+    };
+
+    // The link itself is shown then.
+    if event.event.is_none() {
+        let warning = format!(
+            "Unresolved link to {}, its event was deleted or scavenged",
+            link_text(target)
+        );
+
+        if notes.is_empty() {
+            notes.push(Spans::from(""));
+        }
+
+        notes.insert(0, Spans::from(Span::styled(warning, theme.warning)));
+    }
+
+    (notes, content)
+}
+
+fn event_details(theme: Theme, event: &RecordedEvent) -> Vec<Spans<'static>> {
+    let content_type = event
+        .metadata
+        .get("content-type")
+        .cloned()
+        .unwrap_or_else(|| {
+            if event.is_json {
+                "application/json".to_string()
+            } else {
+                "application/octet-stream".to_string()
+            }
+        });
+
+    let mut details = vec![
+        ("Event id", event.id.to_string()),
+        ("Event type", event.event_type.clone()),
+        ("Stream", event.stream_id.clone()),
+        ("Revision", event.revision.to_string()),
+        ("Content type", content_type),
+        ("Created", event.created.to_string()),
+        ("Commit position", event.position.commit.to_string()),
+        ("Prepare position", event.position.prepare.to_string()),
+    ];
+
+    let metadata = serde_json::from_slice::<serde_json::Value>(event.custom_metadata.as_ref()).ok();
+
+    for (label, key) in [
+        ("Correlation id", "$correlationId"),
+        ("Causation id", "$causationId"),
+    ] {
+        let value = metadata.as_ref().and_then(|m| m.get(key));
+
+        if let Some(value) = value {
+            let value = match value {
+                serde_json::Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+
+            details.push((label, value));
+        }
+    }
+
+    details
+        .into_iter()
+        .map(|(label, value)| {
+            Spans::from(vec![
+                Span::styled(format!("{:<18}", label), theme.label),
+                Span::styled(value, theme.text),
+            ])
+        })
+        .collect()
+}
+
+/// JSON goes to the tree, other decoders are named in the notes.
+fn format_payload(theme: Theme, decoded: Decoded) -> (Vec<Spans<'static>>, PaneContent) {
+    let mut notes = Vec::new();
+
+    if let Some(fallback) = decoded.fallback {
+        notes.push(Spans::from(Span::styled(fallback, theme.error)));
+    }
+
+    let content = match (decoded.decoder, decoded.content) {
+        (decoder, Content::Json(value)) => {
+            if let Some(decoder) = decoder.filter(|d| *d != Decoder::Json) {
+                let caption = format!("Shown as {}", decoder);
+                notes.push(Spans::from(Span::styled(caption, theme.label)));
             }
 
-            Err(e)
+            PaneContent::Json(value)
+        }
+        (None, Content::Text(text)) => PaneContent::Text(Text::from(text)),
+        (Some(Decoder::Hex), Content::Text(text)) => {
+            notes.push(Spans::from(Span::styled("Shown as hex dump", theme.label)));
+            PaneContent::Text(Text::from(text))
+        }
+        (Some(decoder), Content::Text(text)) => {
+            let caption = format!("Shown as {}", decoder);
+            notes.push(Spans::from(Span::styled(caption, theme.label)));
+            PaneContent::Text(Text::from(render_line_numbers(text.as_str())))
         }
-        Ok(v) => Ok(v),
+    };
+
+    if !notes.is_empty() {
+        notes.push(Spans::from(""));
     }
+
+    (notes, content)
+}
+
+async fn read_page(
+    backend: Arc<dyn Backend>,
+    stream_name: String,
+    page: Page,
+) -> eventstore::Result<EventsPage> {
+    let request = ReadRequest::default()
+        .max_count(PAGE_SIZE as usize)
+        .resolve_link_tos()
+        .position(page.position);
+
+    let request = match page.direction {
+        ReadDirection::Forwards => request.forwards(),
+        ReadDirection::Backwards => request.backwards(),
+    };
+
+    let events = backend.read_stream(stream_name.clone(), request).await?;
+
+    let request = ReadRequest::default()
+        .max_count(1)
+        .position(StreamPosition::End)
+        .backwards();
+
+    let last_revision = backend
+        .read_stream(stream_name, request)
+        .await?
+        .first()
+        .map(|e| e.get_original_event().revision);
+
+    Ok(EventsPage {
+        events,
+        last_revision,
+    })
 }
@@ -0,0 +1,287 @@
+use crate::config::parse_duration;
+use crate::views::input::{Form, LineInput, LABEL_WIDTH};
+use crate::views::theme::Theme;
+use crate::views::{centered_rect, ViewCtx, B};
+use crossterm::event::KeyCode;
+use eventstore::{Acl, ExpectedRevision, StreamAcl, StreamMetadata};
+use std::collections::HashMap;
+use std::time::Duration;
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear};
+use tui::Frame;
+
+static FIELDS: &[&str] = &[
+    "Max age",
+    "Max count",
+    "Truncate before",
+    "Cache control",
+    "Read roles",
+    "Write roles",
+    "Delete roles",
+    "Meta read roles",
+    "Meta write roles",
+    "Custom properties",
+];
+
+/// Values of `FIELDS` as typed in the form, empty when not set.
+fn field_values(metadata: &StreamMetadata) -> Vec<String> {
+    let roles = |roles: Option<&Vec<String>>| roles.map(|r| r.join(", ")).unwrap_or_default();
+    let acl = match metadata.acl.as_ref() {
+        Some(Acl::Stream(acl)) => Some(acl),
+        _ => None,
+    };
+
+    let custom = if metadata.custom_properties.is_empty() {
+        String::new()
+    } else {
+        let properties = metadata
+            .custom_properties
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<serde_json::Map<_, _>>();
+
+        serde_json::Value::Object(properties).to_string()
+    };
+
+    vec![
+        metadata.max_age.map(format_duration).unwrap_or_default(),
+        metadata
+            .max_count
+            .map(|c| c.to_string())
+            .unwrap_or_default(),
+        metadata
+            .truncate_before
+            .map(|r| r.to_string())
+            .unwrap_or_default(),
+        metadata
+            .cache_control
+            .map(format_duration)
+            .unwrap_or_default(),
+        roles(acl.and_then(|a| a.read_roles.as_ref())),
+        roles(acl.and_then(|a| a.write_roles.as_ref())),
+        roles(acl.and_then(|a| a.delete_roles.as_ref())),
+        roles(acl.and_then(|a| a.meta_read_roles.as_ref())),
+        roles(acl.and_then(|a| a.meta_write_roles.as_ref())),
+        custom,
+    ]
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    if secs > 0 && secs % 3_600 == 0 {
+        format!("{}h", secs / 3_600)
+    } else if secs > 0 && secs % 60 == 0 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn detail_line(theme: Theme, label: &str, value: String) -> Spans<'static> {
+    Spans::from(vec![
+        Span::styled(
+            format!("{:<width$}", label, width = LABEL_WIDTH as usize),
+            theme.label,
+        ),
+        Span::styled(value, theme.text),
+    ])
+}
+
+/// Lines of the metadata panel, unset values are shown as `-`.
+pub fn metadata_lines(theme: Theme, metadata: &StreamMetadata) -> Vec<Spans<'static>> {
+    let mut lines = FIELDS
+        .iter()
+        .zip(field_values(metadata))
+        .map(|(label, value)| {
+            let value = if value.is_empty() {
+                "-".to_string()
+            } else {
+                value
+            };
+
+            detail_line(theme, label, value)
+        })
+        .collect::<Vec<_>>();
+
+    match metadata.acl {
+        Some(Acl::UserStream) => lines.push(detail_line(theme, "ACL", "$userStreamAcl".into())),
+        Some(Acl::SystemStream) => lines.push(detail_line(theme, "ACL", "$systemStreamAcl".into())),
+        _ => {}
+    }
+
+    lines
+}
+
+/// Popup editing every field of the metadata at once. Roles are comma separated, custom
+/// properties are a JSON object.
+pub struct MetadataForm {
+    inputs: Vec<LineInput>,
+    // Focus indexes `FIELDS`.
+    form: Form,
+    // Written along with the metadata so concurrent changes aren't overwritten.
+    expected: ExpectedRevision,
+    // `$userStreamAcl` and `$systemStreamAcl` have no roles, they're kept unless some are typed.
+    acl: Option<Acl>,
+    submitted: Option<StreamMetadata>,
+}
+
+impl MetadataForm {
+    pub fn new(metadata: &StreamMetadata, expected: ExpectedRevision) -> Self {
+        let inputs = field_values(metadata)
+            .into_iter()
+            .map(|value| {
+                let mut input = LineInput::default();
+                input.set(value);
+                input
+            })
+            .collect();
+
+        Self {
+            inputs,
+            form: Form::new(FIELDS.len()),
+            expected,
+            acl: metadata.acl.clone(),
+            submitted: None,
+        }
+    }
+
+    pub fn expected(&self) -> ExpectedRevision {
+        self.expected
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.form.set_outcome(Err(error));
+    }
+
+    pub fn previous(&mut self) {
+        self.form.previous();
+    }
+
+    pub fn next(&mut self) {
+        self.form.next();
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode) {
+        if self.inputs[self.form.focus()].on_key_pressed(key) {
+            self.form.clear_outcome();
+        }
+    }
+
+    /// Validates the fields. The metadata is then handed out once by `take_submitted`.
+    pub fn submit(&mut self) {
+        match self.build() {
+            Ok(metadata) => self.submitted = Some(metadata),
+            Err(e) => self.form.set_outcome(Err(e)),
+        }
+    }
+
+    pub fn take_submitted(&mut self) -> Option<StreamMetadata> {
+        self.submitted.take()
+    }
+
+    fn build(&self) -> Result<StreamMetadata, String> {
+        let values = self
+            .inputs
+            .iter()
+            .map(|input| input.value().trim())
+            .collect::<Vec<_>>();
+
+        let duration = |idx: usize| -> Result<Option<Duration>, String> {
+            if values[idx].is_empty() {
+                return Ok(None);
+            }
+
+            parse_duration(values[idx]).map(Some)
+        };
+
+        let number = |idx: usize| -> Result<Option<u64>, String> {
+            if values[idx].is_empty() {
+                return Ok(None);
+            }
+
+            values[idx]
+                .parse::<u64>()
+                .map(Some)
+                .map_err(|_| format!("Invalid {} '{}'", FIELDS[idx].to_lowercase(), values[idx]))
+        };
+
+        let roles = |idx: usize| -> Option<Vec<String>> {
+            if values[idx].is_empty() {
+                return None;
+            }
+
+            let roles = values[idx]
+                .split(',')
+                .map(str::trim)
+                .filter(|role| !role.is_empty())
+                .map(str::to_string)
+                .collect();
+
+            Some(roles)
+        };
+
+        let acl = if (4..9).all(|idx| values[idx].is_empty()) {
+            match self.acl {
+                Some(Acl::Stream(_)) | None => None,
+                ref acl => acl.clone(),
+            }
+        } else {
+            Some(Acl::Stream(StreamAcl {
+                read_roles: roles(4),
+                write_roles: roles(5),
+                delete_roles: roles(6),
+                meta_read_roles: roles(7),
+                meta_write_roles: roles(8),
+            }))
+        };
+
+        let custom_properties = if values[9].is_empty() {
+            HashMap::new()
+        } else {
+            match serde_json::from_str::<serde_json::Value>(values[9]) {
+                Ok(serde_json::Value::Object(properties)) => properties.into_iter().collect(),
+                _ => return Err("Custom properties must be a JSON object".to_string()),
+            }
+        };
+
+        Ok(StreamMetadata {
+            max_age: duration(0)?,
+            max_count: number(1)?,
+            truncate_before: number(2)?,
+            cache_control: duration(3)?,
+            acl,
+            custom_properties,
+        })
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>, stream_name: &str) {
+        let block = Block::default()
+            .title(format!("Edit metadata '{}'", stream_name))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.popup);
+        let area = centered_rect(60, 60, frame.size());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let mut constraints = vec![Constraint::Length(1); FIELDS.len()];
+        constraints.push(Constraint::Length(1));
+        constraints.push(Constraint::Min(1));
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints(constraints)
+            .direction(Direction::Vertical)
+            .split(area);
+
+        for (idx, (label, input)) in FIELDS.iter().zip(self.inputs.iter()).enumerate() {
+            let area = self.form.draw_label(ctx, frame, rects[idx], idx, label);
+            input.draw(ctx, frame, area, "");
+        }
+
+        self.form.draw_outcome(ctx, frame, rects[FIELDS.len() + 1]);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::views::input::LineInput;
+use crate::views::{centered_rect, ViewCtx, B};
+use crossterm::event::KeyCode;
+use std::collections::{BTreeSet, VecDeque};
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use tui::Frame;
+
+const MAX_SUGGESTIONS: usize = 10;
+const MAX_HISTORY: usize = 20;
+
+// Characters after which a match starts a word, like `7` in `order-7`.
+const SEPARATORS: &[char] = &['-', '_', '.', '/', ':', '@', ' ', '$'];
+
+/// Name along with the positions, in characters, of what matched the query.
+struct Suggestion<'a> {
+    name: &'a str,
+    positions: Vec<usize>,
+}
+
+/// Popup where a stream name is typed. Known names matching what's typed are suggested, picked
+/// with `Up` and `Down` and completed with `Tab`. Previous searches are listed while nothing is
+/// typed.
+#[derive(Default)]
+pub struct StreamSearch {
+    input: LineInput,
+    // Newest first.
+    history: VecDeque<String>,
+    // Index in the suggestions, `None` while the typed name is used.
+    selected: Option<usize>,
+    error: Option<String>,
+}
+
+impl StreamSearch {
+    /// Starts a new search, the history is kept.
+    pub fn reset(&mut self) {
+        self.input.take();
+        self.selected = None;
+        self.error = None;
+    }
+
+    pub fn on_key_pressed(&mut self, key: KeyCode, known: &BTreeSet<String>) {
+        let count = self.suggestions(known).len();
+
+        match key {
+            KeyCode::Down if count > 0 => {
+                self.selected = Some(self.selected.map_or(0, |idx| (idx + 1).min(count - 1)));
+            }
+
+            KeyCode::Up => self.selected = self.selected.and_then(|idx| idx.checked_sub(1)),
+
+            KeyCode::Tab => {
+                let name = self
+                    .suggestions(known)
+                    .get(self.selected.unwrap_or_default())
+                    .map(|suggestion| suggestion.name.to_string());
+
+                if let Some(name) = name {
+                    self.input.set(name);
+                    self.selected = None;
+                }
+            }
+
+            key => {
+                if self.input.on_key_pressed(key) {
+                    self.selected = None;
+                    self.error = None;
+                }
+            }
+        }
+    }
+
+    /// Name of the stream to open, the picked suggestion or what's typed.
+    pub fn submit(&mut self, known: &BTreeSet<String>) -> Option<String> {
+        let picked = self
+            .selected
+            .and_then(|idx| self.suggestions(known).get(idx).map(|s| s.name.to_string()));
+
+        let name = picked.unwrap_or_else(|| self.input.value().trim().to_string());
+
+        if name.is_empty() {
+            self.error = Some("Type a stream name".to_string());
+            return None;
+        }
+
+        self.history.retain(|previous| *previous != name);
+        self.history.push_front(name.clone());
+        self.history.truncate(MAX_HISTORY);
+        self.reset();
+
+        Some(name)
+    }
+
+    /// Best matches first, or the history when nothing is typed.
+    fn suggestions<'a>(&'a self, known: &'a BTreeSet<String>) -> Vec<Suggestion<'a>> {
+        let query = self.input.value().trim();
+
+        if query.is_empty() {
+            return self
+                .history
+                .iter()
+                .take(MAX_SUGGESTIONS)
+                .map(|name| Suggestion {
+                    name: name.as_str(),
+                    positions: Vec::new(),
+                })
+                .collect();
+        }
+
+        let candidates = known
+            .iter()
+            .chain(self.history.iter().filter(|name| !known.contains(*name)));
+
+        let mut matches = candidates
+            .filter_map(|name| {
+                fuzzy_match(query, name).map(|(score, positions)| {
+                    let suggestion = Suggestion {
+                        name: name.as_str(),
+                        positions,
+                    };
+
+                    (score, suggestion)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|(a, x), (b, y)| {
+            b.cmp(a)
+                .then(x.name.len().cmp(&y.name.len()))
+                .then(x.name.cmp(y.name))
+        });
+
+        matches
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, suggestion)| suggestion)
+            .collect()
+    }
+
+    pub fn draw(&self, ctx: ViewCtx, frame: &mut Frame<B>, known: &BTreeSet<String>) {
+        let block = Block::default()
+            .title("Search")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(ctx.theme.popup);
+        let area = centered_rect(50, 40, frame.size());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rects = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .direction(Direction::Vertical)
+            .split(area);
+
+        self.input.draw(ctx, frame, rects[0], "Stream name: ");
+
+        let suggestions = self.suggestions(known);
+
+        let status = match self.error.as_ref() {
+            Some(error) => Span::styled(error.clone(), ctx.theme.error),
+            None if self.input.value().trim().is_empty() && !suggestions.is_empty() => {
+                Span::styled("Recent searches", ctx.theme.label)
+            }
+            None if self.input.value().trim().is_empty() => Span::raw(""),
+            None if suggestions.is_empty() => {
+                Span::styled("No known stream matches", ctx.theme.label)
+            }
+            None => Span::styled("Tab completes, ↑/↓ picks a stream", ctx.theme.label),
+        };
+
+        frame.render_widget(Paragraph::new(Spans::from(status)), rects[1]);
+
+        let items = suggestions
+            .iter()
+            .map(|suggestion| {
+                let spans = suggestion
+                    .name
+                    .chars()
+                    .enumerate()
+                    .map(|(idx, c)| {
+                        let style = if suggestion.positions.contains(&idx) {
+                            ctx.theme.highlight
+                        } else {
+                            ctx.theme.text
+                        };
+
+                        Span::styled(c.to_string(), style)
+                    })
+                    .collect::<Vec<_>>();
+
+                ListItem::new(Spans::from(spans))
+            })
+            .collect::<Vec<_>>();
+
+        let list = List::new(items).highlight_style(ctx.theme.selected);
+        let mut state = ListState::default();
+        state.select(self.selected);
+
+        frame.render_stateful_widget(list, rects[2], &mut state);
+    }
+}
+
+/// Matches when the characters of `query` all appear in `name`, in order and ignoring case.
+/// Consecutive characters, word starts and prefixes score higher.
+fn fuzzy_match(query: &str, name: &str) -> Option<(i64, Vec<usize>)> {
+    let name_chars = name
+        .chars()
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+
+    // Lowercasing can change the number of characters, positions would be off.
+    if name_chars.len() != name.chars().count() {
+        return None;
+    }
+
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut from = 0;
+
+    for c in query.chars().flat_map(char::to_lowercase) {
+        let idx = from + name_chars[from..].iter().position(|n| *n == c)?;
+
+        score += 1;
+
+        if idx > 0 && positions.last() == Some(&(idx - 1)) {
+            score += 5;
+        } else if idx == 0 || SEPARATORS.contains(&name_chars[idx - 1]) {
+            score += 3;
+        } else {
+            score -= (idx - from).min(5) as i64;
+        }
+
+        positions.push(idx);
+        from = idx + 1;
+    }
+
+    if positions.first() == Some(&0) && positions.len() == name_chars.len() {
+        score += 100;
+    }
+
+    Some((score, positions))
+}
+
+/// Known name closest to `name`, when a typo or two away from it.
+pub fn closest<'a>(name: &str, known: &'a BTreeSet<String>) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 4).max(1);
+
+    known
+        .iter()
+        .filter(|candidate| candidate.as_str() != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Levenshtein distance, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, x) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+
+        for (j, y) in b.iter().enumerate() {
+            let substitution = previous[j] + (x != *y) as usize;
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
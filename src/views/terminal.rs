@@ -0,0 +1,83 @@
+use std::io::{self, Stdout};
+#[cfg(test)]
+use tui::backend::TestBackend;
+use tui::backend::{Backend, CrosstermBackend};
+use tui::buffer::Cell;
+use tui::layout::Rect;
+
+/// Terminal backend the views render to. Views only ever see this concrete type, which keeps
+/// `View` object-safe while still letting tests render headlessly.
+pub enum TermBackend {
+    Crossterm(CrosstermBackend<Stdout>),
+    #[cfg(test)]
+    Test(TestBackend),
+}
+
+impl Backend for TermBackend {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        match self {
+            TermBackend::Crossterm(b) => b.draw(content),
+            #[cfg(test)]
+            TermBackend::Test(b) => b.draw(content),
+        }
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(b) => b.hide_cursor(),
+            #[cfg(test)]
+            TermBackend::Test(b) => b.hide_cursor(),
+        }
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(b) => b.show_cursor(),
+            #[cfg(test)]
+            TermBackend::Test(b) => b.show_cursor(),
+        }
+    }
+
+    fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+        match self {
+            TermBackend::Crossterm(b) => b.get_cursor(),
+            #[cfg(test)]
+            TermBackend::Test(b) => b.get_cursor(),
+        }
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(b) => b.set_cursor(x, y),
+            #[cfg(test)]
+            TermBackend::Test(b) => b.set_cursor(x, y),
+        }
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(b) => b.clear(),
+            #[cfg(test)]
+            TermBackend::Test(b) => b.clear(),
+        }
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        match self {
+            TermBackend::Crossterm(b) => b.size(),
+            #[cfg(test)]
+            TermBackend::Test(b) => b.size(),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(b) => b.flush(),
+            #[cfg(test)]
+            TermBackend::Test(b) => b.flush(),
+        }
+    }
+}
@@ -0,0 +1,1413 @@
+use crate::backend::in_memory::{member, persistent_subscription, projection, InMemoryBackend};
+use crate::backend::{Backend, NewEvent};
+use crate::config::Config;
+use crate::views::{Context, Request, TermBackend};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use eventstore::operations::VNodeState;
+use eventstore::{ExpectedRevision, StreamMetadata};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tui::backend::TestBackend;
+use tui::buffer::Cell;
+use tui::style::{Color, Modifier};
+use tui::Terminal;
+
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 40;
+
+/// Drives a `Context` against an in-memory cluster and renders it on a `TestBackend`.
+///
+/// Snapshots live in `src/views/snapshots`. Run the tests with `UPDATE_SNAPSHOTS=1` to
+/// (re)generate them after an intended rendering change.
+struct Harness {
+    ctx: Context,
+    terminal: Terminal<TermBackend>,
+}
+
+impl Harness {
+    fn new(backend: InMemoryBackend) -> Self {
+        Self::with_config(backend, Config::default())
+    }
+
+    fn with_config(backend: InMemoryBackend, config: Config) -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let mut ctx =
+            Context::with_backend(runtime, Arc::new(backend), "in-memory".to_string(), config);
+        let terminal = Terminal::new(TermBackend::Test(TestBackend::new(WIDTH, HEIGHT))).unwrap();
+
+        ctx.init();
+
+        let mut harness = Self { ctx, terminal };
+        harness.settle();
+        harness
+    }
+
+    /// Waits until every background task spawned by the views has been applied.
+    fn settle(&mut self) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        loop {
+            self.ctx.process_responses();
+
+            if self.ctx.is_idle() {
+                return;
+            }
+
+            if Instant::now() >= deadline {
+                panic!("background tasks did not complete in time");
+            }
+
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn press<K>(&mut self, keys: &[K]) -> &mut Self
+    where
+        K: Into<KeyEvent> + Copy + std::fmt::Debug,
+    {
+        for key in keys {
+            self.terminal.draw(|frame| self.ctx.draw(frame)).unwrap();
+
+            match self.ctx.on_key_pressed((*key).into()) {
+                Request::Refresh => self.ctx.refresh(),
+                Request::Exit => panic!("unexpected exit request after {:?}", key),
+                Request::Noop => {}
+            }
+
+            self.settle();
+        }
+
+        self
+    }
+
+    /// Waits for `text` to show up, e.g. once a subscription delivered an event.
+    fn wait_for(&mut self, text: &str) -> &mut Self {
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while !self.render().contains(text) {
+            if Instant::now() >= deadline {
+                panic!("'{}' did not show up in time:\n{}", text, self.render());
+            }
+
+            std::thread::sleep(Duration::from_millis(5));
+            self.settle();
+        }
+
+        self
+    }
+
+    fn render(&mut self) -> String {
+        self.terminal.draw(|frame| self.ctx.draw(frame)).unwrap();
+
+        let buffer = match self.terminal.backend() {
+            TermBackend::Test(backend) => backend.buffer(),
+            _ => unreachable!(),
+        };
+
+        let mut output = String::new();
+
+        for y in 0..buffer.area.height {
+            let mut line = String::new();
+
+            for x in 0..buffer.area.width {
+                line.push_str(buffer.get(x, y).symbol.as_str());
+            }
+
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+
+        output
+    }
+
+    fn cell(&mut self, x: u16, y: u16) -> Cell {
+        self.terminal.draw(|frame| self.ctx.draw(frame)).unwrap();
+
+        match self.terminal.backend() {
+            TermBackend::Test(backend) => backend.buffer().get(x, y).clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn assert_snapshot(&mut self, name: &str) {
+        let actual = self.render();
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/views/snapshots")
+            .join(format!("{}.txt", name));
+
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+
+        let expected = match std::fs::read_to_string(&path) {
+            Ok(expected) => expected,
+            Err(_) => panic!(
+                "missing snapshot '{}', run with UPDATE_SNAPSHOTS=1 to create it:\n{}",
+                name, actual
+            ),
+        };
+
+        if expected != actual {
+            panic!(
+                "snapshot '{}' doesn't match.\n--- expected\n{}\n--- actual\n{}",
+                name, expected, actual
+            );
+        }
+    }
+}
+
+fn cluster() -> InMemoryBackend {
+    InMemoryBackend::default()
+        .with_event("orders-1", "OrderPlaced", json!({ "id": 1, "total": 42 }))
+        .with_event("orders-1", "OrderShipped", json!({ "id": 1 }))
+        .with_event("customers-7", "CustomerCreated", json!({ "name": "Ada" }))
+        .with_projection(
+            projection("orders-by-customer", "Running"),
+            "fromCategory('orders').foreachStream()",
+        )
+        .with_persistent_subscription(persistent_subscription("orders-1", "billing"))
+        .with_member(member(2113, VNodeState::Leader))
+        .with_member(member(2114, VNodeState::Follower))
+}
+
+fn ctrl(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+}
+
+#[test]
+fn dashboard_view() {
+    Harness::new(cluster()).assert_snapshot("dashboard");
+}
+
+#[test]
+fn streams_view_main() {
+    Harness::new(cluster())
+        .press(&[KeyCode::Tab])
+        .assert_snapshot("streams_main");
+}
+
+#[test]
+fn streams_view_stream() {
+    Harness::new(cluster())
+        .press(&[KeyCode::Tab, KeyCode::Down, KeyCode::Enter])
+        .assert_snapshot("streams_stream");
+}
+
+#[test]
+fn streams_view_preview() {
+    Harness::new(cluster())
+        .press(&[KeyCode::Tab, KeyCode::Down, KeyCode::Enter, KeyCode::Enter])
+        .assert_snapshot("streams_preview");
+}
+
+#[test]
+fn streams_view_search() {
+    Harness::new(cluster())
+        .press(&[
+            KeyCode::Tab,
+            KeyCode::Char('/'),
+            KeyCode::Char('o'),
+            KeyCode::Char('r'),
+        ])
+        .assert_snapshot("streams_search");
+}
+
+#[test]
+fn stream_search_suggests_known_streams() {
+    let backend = cluster()
+        .with_event("orders-12", "OrderPlaced", json!({ "id": 12 }))
+        .with_event("café-3", "TableBooked", json!({ "seats": 2 }));
+    let mut harness = Harness::new(backend);
+
+    // Names come from `$streams`, the best match first.
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char('/')])
+        .press(&typed("ord1"))
+        .wait_for("orders-12")
+        .assert_snapshot("streams_search_suggestions");
+
+    let screen = harness
+        .press(&[KeyCode::Down, KeyCode::Down, KeyCode::Enter])
+        .render();
+    assert!(screen.contains("Stream 'orders-12'"));
+
+    // Previous searches are listed while nothing is typed.
+    harness.press(&[KeyCode::Esc, KeyCode::Esc, KeyCode::Char('/')]);
+    assert!(harness.render().contains("Recent searches"));
+
+    // Typing happens at the cursor, and isn't limited to ASCII.
+    harness
+        .press(&typed("af-3"))
+        .press(&[
+            KeyCode::Home,
+            KeyCode::Char('c'),
+            KeyCode::End,
+            KeyCode::Left,
+        ])
+        .press(&[
+            KeyCode::Left,
+            KeyCode::Char('é'),
+            KeyCode::Delete,
+            KeyCode::Enter,
+        ]);
+    let screen = harness.render();
+    assert!(screen.contains("Stream 'café3'"));
+    assert!(screen.contains("mean 'café-3'?"));
+
+    // Tab completes the best match.
+    harness
+        .press(&[KeyCode::Esc, KeyCode::Char('/')])
+        .press(&typed("cust"))
+        .press(&[KeyCode::Tab, KeyCode::Enter]);
+    assert!(harness.render().contains("CustomerCreated"));
+}
+
+#[test]
+fn unresolved_links_are_shown_and_followed() {
+    let backend = cluster()
+        .with_link("$ce-orders", b"1@orders-1")
+        .with_link("$ce-orders", b"4@orders-1")
+        .with_link("$ce-orders", b"not a link")
+        .with_link("$streams", b"\xff\xfe");
+    let mut harness = Harness::new(backend);
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char(':')])
+        .press(&typed("stream $ce-orders"))
+        .press(&[KeyCode::Enter])
+        .wait_for("Unresolved link")
+        .assert_snapshot("streams_unresolved_links");
+
+    // A malformed link can't be followed.
+    harness.press(&[KeyCode::Char('l')]);
+    assert!(harness.render().contains("Stream '$ce-orders'"));
+
+    let screen = harness.press(&[KeyCode::Down, KeyCode::Enter]).render();
+    assert!(screen.contains("Unresolved link to 4@orders-1"));
+
+    // Links are followed to the revision they point to.
+    let screen = harness
+        .press(&[KeyCode::Esc, KeyCode::Down, KeyCode::Char('l')])
+        .wait_for("OrderShipped")
+        .render();
+    assert!(screen.contains("Stream 'orders-1'"));
+    assert!(!screen.contains("OrderPlaced"));
+}
+
+#[test]
+fn streams_view_does_not_panic_on_empty_cluster() {
+    Harness::new(InMemoryBackend::default())
+        .press(&[KeyCode::Tab, KeyCode::Down, KeyCode::Enter, KeyCode::Enter])
+        .press(&[KeyCode::Char('/'), KeyCode::Char('x'), KeyCode::Enter])
+        .press(&[KeyCode::Down, KeyCode::Enter])
+        .assert_snapshot("streams_empty");
+}
+
+#[test]
+fn projections_view() {
+    Harness::new(cluster())
+        .press(&[KeyCode::Tab, KeyCode::Tab])
+        .assert_snapshot("projections_main");
+}
+
+#[test]
+fn projections_view_detail() {
+    Harness::new(cluster())
+        .press(&[KeyCode::Tab, KeyCode::Tab, KeyCode::Enter])
+        .assert_snapshot("projections_detail");
+}
+
+#[test]
+fn persistent_subscriptions_view() {
+    Harness::new(cluster())
+        .press(&[KeyCode::BackTab, KeyCode::BackTab])
+        .assert_snapshot("persistent_subscriptions_main");
+}
+
+#[test]
+fn persistent_subscriptions_view_actions() {
+    Harness::new(cluster())
+        .press(&[KeyCode::BackTab, KeyCode::BackTab, KeyCode::Enter])
+        .assert_snapshot("persistent_subscriptions_actions");
+}
+
+#[test]
+fn monitoring_view() {
+    Harness::new(cluster())
+        .press(&[KeyCode::BackTab])
+        .assert_snapshot("monitoring");
+}
+
+#[test]
+fn connection_switcher_rejects_invalid_connection_string() {
+    let config: Config = toml::from_str(
+        r#"
+        [profiles.local]
+        connection_string = "esdb://localhost:2113?tls=false"
+
+        [profiles.staging]
+        connection_string = "esdb://staging:2113"
+        "#,
+    )
+    .unwrap();
+
+    Harness::with_config(cluster(), config)
+        .press(&[ctrl('o')])
+        .press(&[KeyCode::Down, KeyCode::Char('x'), KeyCode::Enter])
+        .assert_snapshot("connection_switcher");
+}
+
+#[test]
+fn recovers_when_the_cluster_comes_back() {
+    let backend = cluster();
+    backend.set_offline(true);
+
+    let mut harness = Harness::new(backend.clone());
+    let screen = harness.render();
+
+    assert!(screen.contains("Disconnected since"));
+    assert!(screen.contains("r: Retry now"));
+
+    backend.set_offline(false);
+    harness.press(&[KeyCode::Tab, KeyCode::Char('r')]);
+
+    let screen = harness.render();
+
+    assert!(!screen.contains("Disconnected since"));
+    assert!(screen.contains("orders-1"));
+}
+
+#[test]
+fn keymap_overrides_drive_keys_and_footer() {
+    let config: Config = toml::from_str(
+        r#"
+        [keys.global]
+        quit = ["q", "C-c"]
+
+        [keys.streams]
+        search = "s"
+        "#,
+    )
+    .unwrap();
+
+    let mut harness = Harness::with_config(cluster(), config);
+    harness
+        .press(&[KeyCode::Tab])
+        .assert_snapshot("keymap_overrides");
+
+    let screen = harness
+        .press(&[KeyCode::Char('/'), KeyCode::Char('s'), KeyCode::Char('o')])
+        .render();
+
+    assert!(screen.contains("Stream name: o_"));
+}
+
+#[test]
+fn custom_theme_is_applied() {
+    let config: Config = toml::from_str(
+        r##"
+        theme = "mine"
+
+        [themes.mine]
+        base = "light"
+        key = { fg = "#ff8800", modifiers = ["bold"] }
+        "##,
+    )
+    .unwrap();
+
+    let mut harness = Harness::with_config(cluster(), config);
+
+    // Tabs block, from the light base theme.
+    assert_eq!(harness.cell(0, 0).bg, Color::White);
+
+    let screen = harness.render();
+    let (y, line) = screen
+        .lines()
+        .enumerate()
+        .find(|(_, line)| line.contains("Scroll up"))
+        .unwrap();
+    let x = line.chars().position(|c| c == '↑').unwrap();
+    let key = harness.cell(x as u16, y as u16);
+
+    assert_eq!(key.fg, Color::Rgb(255, 136, 0));
+    assert!(key.modifier.contains(Modifier::BOLD));
+}
+
+fn typed(text: &str) -> Vec<KeyCode> {
+    text.chars().map(KeyCode::Char).collect()
+}
+
+#[test]
+fn command_palette_handles_non_ascii_spaces() {
+    let screen = Harness::new(cluster())
+        .press(&[KeyCode::Tab, KeyCode::Char(':')])
+        .press(&typed("stream\u{a0}or"))
+        .press(&[KeyCode::Tab])
+        .render();
+
+    assert!(screen.contains(":stream\u{a0}orders-1"));
+
+    let screen = Harness::new(cluster())
+        .press(&[KeyCode::Char(':')])
+        .press(&typed("tab\u{3000}"))
+        .render();
+
+    assert!(screen.contains("persistent-subscriptions"));
+}
+
+#[test]
+fn command_palette_completes_and_runs_commands() {
+    let mut harness = Harness::new(cluster());
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char(':')])
+        .press(&typed("st"))
+        .press(&[KeyCode::Tab])
+        .assert_snapshot("command_palette");
+
+    let screen = harness
+        .press(&typed("or"))
+        .press(&[KeyCode::Tab, KeyCode::Enter])
+        .render();
+
+    assert!(screen.contains("OrderShipped"));
+
+    let screen = harness
+        .press(&[KeyCode::Char(':')])
+        .press(&typed("proj disable orders-by-customer"))
+        .press(&[KeyCode::Enter, KeyCode::Char(':')])
+        .press(&typed("tab projections"))
+        .press(&[KeyCode::Enter])
+        .render();
+
+    assert!(screen.contains("Projection 'orders-by-customer' disabled"));
+    // Status column is narrow.
+    assert!(screen.contains("Stopp"));
+
+    // A failed command doesn't mean the cluster is failing.
+    let screen = harness
+        .press(&[KeyCode::Char(':')])
+        .press(&typed("proj disable fooo"))
+        .press(&[KeyCode::Enter])
+        .wait_for("Couldn't disable projection 'fooo'")
+        .render();
+
+    assert!(!screen.contains("Failing"));
+
+    let screen = harness
+        .press(&[KeyCode::Char(':')])
+        .press(&typed("tab nowhere"))
+        .press(&[KeyCode::Enter])
+        .render();
+
+    assert!(screen.contains("Unknown tab 'nowhere'"));
+}
+
+#[test]
+fn help_overlay_lists_every_stage() {
+    let mut harness = Harness::new(cluster());
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char('?')])
+        .assert_snapshot("help");
+
+    let screen = harness.press(&typed("cancel")).render();
+
+    assert!(screen.contains("Streams Browser - Search"));
+    assert!(!screen.contains("Scroll up"));
+
+    let screen = harness.press(&[KeyCode::Esc]).render();
+
+    assert!(!screen.contains("Streams Browser - Search"));
+}
+
+#[test]
+fn long_streams_are_paginated() {
+    let backend = cluster();
+
+    for idx in 0..1200 {
+        backend.append("big", "Tick", json!({ "idx": idx }));
+    }
+
+    let mut harness = Harness::new(backend);
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char(':')])
+        .press(&typed("stream big"))
+        .press(&[KeyCode::Enter]);
+
+    assert!(harness.render().contains("revision 700–1199 of 1200"));
+
+    harness.press(&[KeyCode::PageDown, KeyCode::PageDown]);
+    assert!(harness.render().contains("revision 0–199 of 1200"));
+
+    harness.press(&[KeyCode::Home]);
+    assert!(harness.render().contains("revision 0–499 of 1200"));
+
+    harness
+        .press(&[KeyCode::Char('g')])
+        .press(&typed("1000"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness.render().contains("revision 1000–1199 of 1200"));
+
+    // Scrolling up past the first event loads the previous page.
+    harness.press(&[KeyCode::Up]);
+    assert!(harness.render().contains("revision 500–999 of 1200"));
+
+    harness.press(&[KeyCode::End]);
+    assert!(harness.render().contains("revision 700–1199 of 1200"));
+}
+
+#[test]
+fn tail_appends_new_events_until_paused() {
+    let backend = cluster();
+    let mut harness = Harness::new(backend.clone());
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Down, KeyCode::Enter])
+        .press(&[KeyCode::Char('t')])
+        .wait_for("Tailing 'orders-1' | 2 events");
+
+    assert!(harness.render().contains("Live |"));
+
+    backend.append("orders-1", "OrderRefunded", json!({ "id": 1 }));
+    harness.wait_for("OrderRefunded").wait_for("| 3 events");
+
+    harness.press(&[KeyCode::Char('p')]);
+    backend.append("orders-1", "OrderClosed", json!({ "id": 1 }));
+    harness.wait_for("paused (1 new)");
+    assert!(!harness.render().contains("OrderClosed"));
+
+    harness.press(&[KeyCode::Char('p')]).wait_for("OrderClosed");
+
+    harness.press(&[KeyCode::Char('t')]);
+    let screen = harness.render();
+    assert!(screen.contains("Event Stream 'orders-1'"));
+    assert!(!screen.contains("Live |"));
+
+    // Events written since the displayed ones were read aren't missed.
+    backend.append("orders-1", "OrderArchived", json!({ "id": 1 }));
+    harness
+        .press(&[KeyCode::Char('t')])
+        .wait_for("OrderArchived")
+        .wait_for("| 5 events");
+}
+
+#[test]
+fn filtered_all_is_tailed_from_its_newest_event() {
+    let backend = cluster();
+    let mut harness = Harness::new(backend.clone());
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char('/')])
+        .press(&typed("$all"))
+        .press(&[
+            KeyCode::Enter,
+            KeyCode::Char('g'),
+            KeyCode::Right,
+            KeyCode::Right,
+        ])
+        .press(&[KeyCode::Down])
+        .press(&typed("orders-"))
+        .press(&[KeyCode::Enter])
+        .wait_for("1@orders-1");
+
+    harness
+        .press(&[KeyCode::Char('t')])
+        .wait_for("Tailing '$all'");
+
+    // The page is read forwards, it's neither reversed nor tailed again.
+    let screen = harness.render();
+    assert_eq!(screen.matches("1@orders-1").count(), 1);
+    assert!(screen.find("0@orders-1") < screen.find("1@orders-1"));
+
+    // The server-side filter still applies.
+    assert!(!screen.contains("customers-7"));
+    backend.append("customers-8", "CustomerCreated", json!({ "name": "Bob" }));
+    backend.append("orders-2", "OrderPlaced", json!({ "id": 2 }));
+
+    let screen = harness.wait_for("0@orders-2").render();
+    assert!(screen.contains("| 3 events"));
+    assert!(!screen.contains("customers-8"));
+}
+
+#[test]
+fn event_preview_shows_metadata_and_system_panes() {
+    let backend = cluster();
+    backend.append_with_metadata(
+        "payments-3",
+        "PaymentCaptured",
+        json!({ "amount": 42 }),
+        json!({ "$correlationId": "c0ffee", "$causationId": "beef", "user": "ada" }),
+    );
+
+    let mut harness = Harness::new(backend);
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char(':')])
+        .press(&typed("stream payments-3"))
+        .press(&[KeyCode::Enter, KeyCode::Enter]);
+    assert!(harness.render().contains("\"amount\": 42"));
+
+    harness.press(&[KeyCode::Right]);
+    assert!(harness.render().contains("\"user\": \"ada\""));
+
+    harness
+        .press(&[KeyCode::Right])
+        .assert_snapshot("streams_preview_system");
+
+    // Links show where they were written along with the event they point to.
+    harness
+        .press(&[KeyCode::Esc, KeyCode::Esc, KeyCode::Char(':')])
+        .press(&typed("stream $streams"))
+        .press(&[KeyCode::Enter, KeyCode::Enter]);
+
+    let screen = harness.render();
+    assert!(screen.contains("Link"));
+    assert!(screen.contains("$streams"));
+}
+
+#[test]
+fn stream_metadata_is_edited_with_the_expected_revision() {
+    let backend = cluster();
+    let mut harness = Harness::new(backend.clone());
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Down, KeyCode::Enter])
+        .press(&[KeyCode::Char('m')]);
+    assert!(harness
+        .render()
+        .contains("Stream metadata 'orders-1' | not set"));
+
+    harness
+        .press(&[KeyCode::Char('e')])
+        .press(&typed("1h"))
+        .press(&[KeyCode::Down])
+        .press(&typed("10"))
+        .press(&[KeyCode::Down, KeyCode::Down, KeyCode::Down])
+        .press(&typed("$admins, ops"))
+        .press(&[KeyCode::Up])
+        .press(&typed("abc"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness.render().contains("Invalid duration 'abc'"));
+
+    harness
+        .press(&[KeyCode::Backspace, KeyCode::Backspace, KeyCode::Backspace])
+        .press(&[KeyCode::Enter])
+        .assert_snapshot("streams_metadata");
+
+    // Written meanwhile by someone else.
+    let metadata = StreamMetadata::default();
+    futures::executor::block_on(backend.set_stream_metadata(
+        "orders-1".to_string(),
+        ExpectedRevision::Any,
+        metadata,
+    ))
+    .unwrap();
+
+    harness
+        .press(&[KeyCode::Char('e'), KeyCode::Down])
+        .press(&typed("5"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness
+        .render()
+        .contains("The metadata changed since it was loaded"));
+
+    harness.press(&[KeyCode::Esc]);
+    assert!(harness.render().contains("| revision 1"));
+}
+
+#[test]
+fn append_dialog_validates_and_reports_the_revision() {
+    let mut harness = Harness::new(cluster());
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Down, KeyCode::Enter])
+        .press(&[KeyCode::Char('a')])
+        .press(&typed("OrderRefunded"))
+        .press(&[KeyCode::Enter, KeyCode::Enter])
+        .press(&[KeyCode::Right, KeyCode::Right, KeyCode::Right])
+        .press(&typed("0"))
+        .press(&[KeyCode::Down])
+        .press(&typed("{\"id\": 1,"))
+        .press(&[ctrl('s')]);
+    assert!(harness.render().contains("Data isn't valid JSON"));
+
+    harness
+        .press(&[KeyCode::Backspace])
+        .press(&typed("}"))
+        .press(&[ctrl('s')]);
+    assert!(harness
+        .render()
+        .contains("Wrong expected revision, the stream is at revision 1"));
+
+    harness
+        .press(&[KeyCode::Up, KeyCode::Backspace])
+        .press(&typed("1"))
+        .press(&[ctrl('s')])
+        .assert_snapshot("streams_append");
+
+    harness.press(&[KeyCode::Esc]);
+    assert!(harness.render().contains("2@orders-1"));
+}
+
+#[test]
+fn append_dialog_keeps_non_ascii_text() {
+    let backend = cluster();
+    let mut harness = Harness::new(backend.clone());
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Down, KeyCode::Enter])
+        .press(&[KeyCode::Char('a')])
+        .press(&typed("NoteAdded"))
+        .press(&[KeyCode::Enter, KeyCode::Enter, KeyCode::Enter])
+        .press(&typed("{\"note\": \"café 名前\"}"))
+        .press(&[ctrl('s')])
+        .wait_for("Appended at revision 2");
+
+    let events = futures::executor::block_on(
+        backend.read_stream("orders-1".to_string(), Default::default()),
+    )
+    .unwrap();
+    let data = &events[2].get_original_event().data;
+
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(data).unwrap(),
+        json!({ "note": "café 名前" })
+    );
+}
+
+#[test]
+fn streams_are_deleted_once_their_name_is_typed() {
+    let backend = cluster();
+    let mut harness = Harness::new(backend.clone());
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Down, KeyCode::Enter])
+        .press(&[KeyCode::Delete])
+        .press(&typed("orders"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness
+        .render()
+        .contains("The typed name doesn't match the stream"));
+
+    harness
+        .press(&typed("-1"))
+        .press(&[
+            KeyCode::Down,
+            KeyCode::Right,
+            KeyCode::Right,
+            KeyCode::Right,
+        ])
+        .press(&typed("0"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness
+        .render()
+        .contains("Wrong expected revision, the stream is at revision 1"));
+
+    harness
+        .press(&[KeyCode::Backspace])
+        .press(&typed("1"))
+        .press(&[KeyCode::Enter])
+        .assert_snapshot("streams_delete");
+
+    let read = backend.read_stream("orders-1".to_string(), Default::default());
+    assert!(matches!(
+        futures::executor::block_on(read),
+        Err(eventstore::Error::ResourceNotFound)
+    ));
+
+    // Appending again undeletes the stream, without its previous events.
+    backend.append("orders-1", "OrderPlaced", json!({ "id": 3 }));
+    harness.press(&[KeyCode::Esc, KeyCode::Char('a'), KeyCode::Esc]);
+    assert!(harness.render().contains("2@orders-1"));
+    assert!(!harness.render().contains("1@orders-1"));
+
+    harness
+        .press(&[KeyCode::Esc, KeyCode::Enter])
+        .press(&[KeyEvent::new(KeyCode::Delete, KeyModifiers::SHIFT)])
+        .press(&typed("customers-7"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness.render().contains("Tombstoned"));
+
+    harness.press(&[KeyCode::Esc]);
+    assert!(harness.render().contains("customers-7 [tombstoned]"));
+}
+
+#[test]
+fn stream_events_are_filtered_by_type_text_or_json_path() {
+    let backend = cluster();
+    backend.append(
+        "orders-1",
+        "OrderLineAdded",
+        json!({ "sku": "A-1", "quantity": 3 }),
+    );
+    backend.append(
+        "orders-1",
+        "OrderCancelled",
+        json!({ "reason": "Out of stock" }),
+    );
+
+    let mut harness = Harness::new(backend);
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Down, KeyCode::Enter])
+        .press(&[KeyCode::Char('f')])
+        .press(&typed("$.quantity >"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness
+        .render()
+        .contains("Invalid value '', strings are quoted"));
+
+    harness.press(&typed(" 2")).press(&[KeyCode::Enter]);
+    assert!(harness.render().contains("2@orders-1"));
+    assert!(!harness.render().contains("0@orders-1"));
+
+    harness
+        .press(&[KeyCode::Char('f')])
+        .press(&[KeyCode::Backspace; 14])
+        .press(&typed("out of STOCK"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness.render().contains("3@orders-1"));
+    assert!(!harness.render().contains("2@orders-1"));
+
+    harness
+        .press(&[KeyCode::Char('f')])
+        .press(&[KeyCode::Backspace; 12])
+        .press(&typed("type:*c*ed"))
+        .press(&[KeyCode::Enter])
+        .assert_snapshot("streams_filter");
+
+    // Opening the event previews the matching one.
+    harness.press(&[KeyCode::Down, KeyCode::Enter]);
+    assert!(harness.render().contains("Event '0@orders-1'"));
+}
+
+#[test]
+fn all_is_browsed_with_server_side_filters() {
+    let backend = cluster();
+    let mut harness = Harness::new(backend.clone());
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char('/')])
+        .press(&typed("$all"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness.render().contains("$ProjectionUpdated"));
+
+    harness
+        .press(&[
+            KeyCode::Char('g'),
+            KeyCode::Right,
+            KeyCode::Right,
+            KeyCode::Down,
+        ])
+        .press(&typed("orders-"))
+        .press(&[KeyCode::Enter])
+        .assert_snapshot("streams_all_filtered");
+
+    // Filtered reads aren't refreshed, moving between pages reads them again.
+    backend.append("orders-2", "OrderPlaced", json!({ "id": 2 }));
+    harness
+        .press(&[KeyCode::Char(':')])
+        .press(&typed("refresh"))
+        .press(&[KeyCode::Enter]);
+    assert!(!harness.render().contains("0@orders-2"));
+
+    harness.press(&[KeyCode::Home]);
+    assert!(harness.render().contains("0@orders-2"));
+
+    harness
+        .press(&[KeyCode::Char('g'), KeyCode::Up])
+        .press(&typed("C:0/P:0"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness.render().contains("1@orders-1"));
+    assert!(!harness.render().contains("0@orders-1"));
+
+    harness.press(&[KeyCode::Char('g'), KeyCode::Left, KeyCode::Enter]);
+    assert!(harness.render().contains("0@customers-7"));
+    assert!(!harness.render().contains("$ProjectionUpdated"));
+
+    harness.press(&[
+        KeyCode::Char('g'),
+        KeyCode::Up,
+        KeyCode::Backspace,
+        KeyCode::Enter,
+    ]);
+    assert!(harness.render().contains("Invalid position 'C:0/P:'"));
+
+    harness.press(&[KeyCode::Esc, KeyCode::End]);
+    assert!(harness.render().contains("$ProjectionUpdated"));
+}
+
+#[test]
+fn filtered_all_pages_cut_short_are_flagged() {
+    let backend = cluster();
+    backend.set_slow_scans(true);
+
+    let screen = Harness::new(backend)
+        .press(&[KeyCode::Tab, KeyCode::Char('/')])
+        .press(&typed("$all"))
+        .press(&[
+            KeyCode::Enter,
+            KeyCode::Char('g'),
+            KeyCode::Right,
+            KeyCode::Right,
+        ])
+        .press(&[KeyCode::Down])
+        .press(&typed("orders-"))
+        .press(&[KeyCode::Enter])
+        .wait_for("0@orders-1")
+        .render();
+
+    assert!(screen.contains("partial, the server was still scanning"));
+    assert!(!screen.contains("1@orders-1"));
+}
+
+#[test]
+fn stream_ranges_are_exported_to_ndjson() {
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("esdb-tui-export-{}.ndjson", std::process::id()));
+    let path_all = dir.join(format!("esdb-tui-export-all-{}.ndjson", std::process::id()));
+    let mut harness = Harness::new(cluster());
+
+    harness
+        .press(&[
+            KeyCode::Tab,
+            KeyCode::Down,
+            KeyCode::Enter,
+            KeyCode::Char('x'),
+        ])
+        .assert_snapshot("streams_export");
+
+    // Only the shipment was written after 05:20:01.
+    harness
+        .press(&vec![KeyCode::Backspace; "orders-1.ndjson".len()])
+        .press(&typed(path.to_str().unwrap()))
+        .press(&[KeyCode::Down, KeyCode::Right, KeyCode::Down])
+        .press(&typed("2022-04-15T05:20:01Z"))
+        .press(&[KeyCode::Enter])
+        .wait_for("Exported 1 events");
+
+    let exported = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines = exported.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1);
+
+    let event = serde_json::from_str::<serde_json::Value>(lines[0]).unwrap();
+    assert_eq!(event["stream"], "orders-1");
+    assert_eq!(event["type"], "OrderShipped");
+    assert_eq!(event["revision"], 1);
+    assert_eq!(event["position"], json!({ "commit": 200, "prepare": 200 }));
+    assert_eq!(event["created"], "2022-04-15T05:20:02+00:00");
+    assert_eq!(event["data"], json!({ "id": 1 }));
+
+    // Links aren't JSON, their data is base64 encoded.
+    harness
+        .press(&[KeyCode::Esc, KeyCode::Esc, KeyCode::Char('/')])
+        .press(&typed("$all"))
+        .press(&[
+            KeyCode::Enter,
+            KeyCode::Char('x'),
+            KeyCode::Down,
+            KeyCode::Down,
+        ])
+        .press(&typed("C:200"))
+        .press(&[KeyCode::Down])
+        .press(&typed("C:100"))
+        .press(&[KeyCode::Enter]);
+    assert!(harness.render().contains("'From' is past 'To'"));
+
+    harness
+        .press(&[
+            KeyCode::Up,
+            KeyCode::Backspace,
+            KeyCode::Backspace,
+            KeyCode::Backspace,
+        ])
+        .press(&typed("100"))
+        .press(&[KeyCode::Up, KeyCode::Up])
+        .press(&vec![KeyCode::Backspace; "all.ndjson".len()])
+        .press(&typed(path_all.to_str().unwrap()))
+        .press(&[KeyCode::Enter])
+        .wait_for("Exported 1 events");
+
+    let exported = std::fs::read_to_string(&path_all).unwrap();
+    std::fs::remove_file(&path_all).unwrap();
+
+    let event = serde_json::from_str::<serde_json::Value>(exported.trim()).unwrap();
+    assert_eq!(event["stream"], "$streams");
+    assert_eq!(event["data_base64"], "MEBvcmRlcnMtMQ==");
+    assert!(event.get("data").is_none());
+}
+
+#[test]
+fn ndjson_exports_are_imported_once_checked() {
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("esdb-tui-import-{}.ndjson", std::process::id()));
+    let invalid = dir.join(format!(
+        "esdb-tui-import-invalid-{}.ndjson",
+        std::process::id()
+    ));
+    let line = |id: &str, content_type: &str, payload: (&str, serde_json::Value)| {
+        let mut line = json!({
+            "id": id,
+            "stream": "orders-1",
+            "type": "OrderPlaced",
+            "revision": 0,
+            "position": { "commit": 0, "prepare": 0 },
+            "created": "2022-04-15T05:20:00+00:00",
+            "content_type": content_type,
+        });
+
+        line[payload.0] = payload.1;
+        line.to_string()
+    };
+
+    let first = "00000000-0000-0000-0000-00000000002a";
+    let second = "00000000-0000-0000-0000-00000000002b";
+    let lines = [
+        line(first, "application/json", ("data", json!({ "id": 42 }))),
+        line(
+            second,
+            "application/octet-stream",
+            ("data_base64", json!("MEBvcmRlcnMtMQ==")),
+        ),
+    ];
+
+    std::fs::write(&path, lines.join("\n")).unwrap();
+    std::fs::write(
+        &invalid,
+        format!("{}\n{}", lines[0], line("nope", "", ("data", json!(1)))),
+    )
+    .unwrap();
+
+    let backend = cluster();
+    let mut harness = Harness::new(backend.clone());
+
+    harness
+        .press(&[
+            KeyCode::Tab,
+            KeyCode::Down,
+            KeyCode::Enter,
+            KeyCode::Char('i'),
+        ])
+        .assert_snapshot("streams_import");
+
+    // Nothing is appended when a line is invalid.
+    harness
+        .press(&[KeyCode::Esc, KeyCode::Esc, KeyCode::Char('i')])
+        .press(&typed(invalid.to_str().unwrap()))
+        .press(&[KeyCode::Down])
+        .press(&typed("imported-1"))
+        .press(&[KeyCode::Enter])
+        .wait_for("Line 2: Invalid event id 'nope'");
+
+    let read = |backend: &InMemoryBackend| {
+        futures::executor::block_on(
+            backend.read_stream("imported-1".to_string(), Default::default()),
+        )
+    };
+    assert!(read(&backend).is_err());
+
+    harness
+        .press(&[KeyCode::Up])
+        .press(&vec![KeyCode::Backspace; invalid.to_str().unwrap().len()])
+        .press(&typed(path.to_str().unwrap()))
+        .press(&[KeyCode::Down, KeyCode::Down, KeyCode::Right, KeyCode::Enter])
+        .wait_for("Dry run: 2 valid events, nothing was written");
+    assert!(read(&backend).is_err());
+
+    // Ids are kept, importing again doesn't duplicate the events.
+    harness
+        .press(&[KeyCode::Left, KeyCode::Enter])
+        .wait_for("Imported 2 events into 'imported-1'")
+        .press(&[KeyCode::Enter])
+        .wait_for("Imported 2 events into 'imported-1'");
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&invalid).unwrap();
+
+    let events = read(&backend).unwrap();
+    let events = events
+        .iter()
+        .map(|e| e.get_original_event())
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].id.to_string(), first);
+    assert!(events[0].is_json);
+    assert_eq!(events[1].id.to_string(), second);
+    assert!(!events[1].is_json);
+    assert_eq!(events[1].data.as_ref(), b"0@orders-1");
+}
+
+#[test]
+fn payloads_are_decoded_by_event_type() {
+    use prost::Message;
+    use prost_types::field_descriptor_proto::{Label, Type};
+    use prost_types::{
+        DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+        FileDescriptorProto, FileDescriptorSet,
+    };
+
+    let field = |name: &str, number: i32, kind: Type, label: Label, type_name: Option<&str>| {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            label: Some(label as i32),
+            r#type: Some(kind as i32),
+            type_name: type_name.map(str::to_string),
+            ..Default::default()
+        }
+    };
+
+    let set = FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("payments.proto".to_string()),
+            package: Some("shop".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("PaymentCaptured".to_string()),
+                field: vec![
+                    field("id", 1, Type::String, Label::Optional, None),
+                    field("amount", 2, Type::Int64, Label::Optional, None),
+                    field(
+                        "status",
+                        3,
+                        Type::Enum,
+                        Label::Optional,
+                        Some(".shop.Status"),
+                    ),
+                    field("items", 4, Type::Int32, Label::Repeated, None),
+                ],
+                ..Default::default()
+            }],
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("Status".to_string()),
+                value: ["PENDING", "PAID"]
+                    .iter()
+                    .enumerate()
+                    .map(|(number, name)| EnumValueDescriptorProto {
+                        name: Some(name.to_string()),
+                        number: Some(number as i32),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+    };
+
+    // Removes the descriptor set even when an assertion fails.
+    struct TempFile(std::path::PathBuf);
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    let descriptors = TempFile(
+        std::env::temp_dir().join(format!("esdb-tui-decoders-{}.binpb", std::process::id())),
+    );
+    std::fs::write(&descriptors.0, set.encode_to_vec()).unwrap();
+
+    let config: Config = toml::from_str(
+        format!(
+            "[decoders]\ndescriptor_sets = [{:?}]\n\n[decoders.event_types]\n\
+             PaymentCaptured = \"protobuf:shop.PaymentCaptured\"\n",
+            descriptors.0.to_str().unwrap(),
+        )
+        .as_str(),
+    )
+    .unwrap();
+
+    let backend = cluster();
+    let append = |data: &[u8]| {
+        let event = NewEvent {
+            id: None,
+            event_type: "PaymentCaptured".to_string(),
+            data: data.to_vec(),
+            metadata: Vec::new(),
+            is_json: false,
+        };
+
+        futures::executor::block_on(backend.append_to_stream(
+            "payments-3".to_string(),
+            ExpectedRevision::Any,
+            vec![event],
+        ))
+        .unwrap();
+    };
+
+    // id "p-1", amount 4200, status PAID and packed items [1, 2].
+    append(&[
+        0x0a, 0x03, b'p', b'-', b'1', 0x10, 0xe8, 0x20, 0x18, 0x01, 0x22, 0x02, 0x01, 0x02,
+    ]);
+    append(&[0x0a, 0x09, 0xff]);
+
+    let mut harness = Harness::with_config(backend.clone(), config);
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char(':')])
+        .press(&typed("stream payments-3"))
+        .press(&[KeyCode::Enter, KeyCode::Enter]);
+
+    // Payloads the registered decoder can't read are still displayed.
+    let screen = harness.render();
+    assert!(screen.contains("Not decoded as protobuf 'shop.PaymentCaptured': Truncated payload"));
+    assert!(screen.contains("Shown as hex dump"));
+
+    harness
+        .press(&[KeyCode::Esc, KeyCode::Down, KeyCode::Enter])
+        .assert_snapshot("streams_preview_protobuf");
+}
+
+#[test]
+fn large_binary_payloads_are_scrolled() {
+    let backend = cluster();
+
+    // Over 1 MiB, its hex dump has more lines than `u16` counts.
+    let event = NewEvent {
+        id: None,
+        event_type: "Blob".to_string(),
+        data: vec![0; 1024 * 1024 + 16],
+        metadata: Vec::new(),
+        is_json: false,
+    };
+
+    futures::executor::block_on(backend.append_to_stream(
+        "blobs-1".to_string(),
+        ExpectedRevision::Any,
+        vec![event],
+    ))
+    .unwrap();
+
+    let mut harness = Harness::new(backend);
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char(':')])
+        .press(&typed("stream blobs-1"))
+        .press(&[KeyCode::Enter, KeyCode::Enter]);
+    assert!(harness.render().contains("00000000  00 00"));
+
+    let screen = harness.press(&[KeyCode::Down; 5]).render();
+    assert!(!screen.contains("00000000  00 00"));
+    assert!(screen.contains("00000030  00 00"));
+}
+
+#[test]
+fn json_payloads_fold_search_and_scroll() {
+    let backend = cluster().with_projection_output(
+        "orders-by-customer",
+        json!({ "customers": { "ada": { "orders": 2 } } }),
+        json!({ "total": 42 }),
+    );
+    backend.append(
+        "carts-9",
+        "CartUpdated",
+        json!({
+            "items": [
+                { "sku": "apple", "quantity": 3 },
+                { "sku": "pear", "quantity": 1 }
+            ],
+            "note": format!("{}end of note", "-".repeat(120)),
+        }),
+    );
+
+    let mut harness = Harness::new(backend);
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char(':')])
+        .press(&typed("stream carts-9"))
+        .press(&[KeyCode::Enter, KeyCode::Enter]);
+    assert!(harness.render().contains("\"quantity\": 3"));
+
+    // Folds the items array, the cursor starts on the root object.
+    harness.press(&[KeyCode::Down, KeyCode::Char(' ')]);
+    let screen = harness.render();
+    assert!(screen.contains("\"items\": […], 2 items"));
+    assert!(!screen.contains("\"quantity\": 3"));
+
+    // Matches inside folded nodes unfold them.
+    harness
+        .press(&[KeyCode::Char('/')])
+        .press(&typed("pear"))
+        .press(&[KeyCode::Enter]);
+    let screen = harness.render();
+    assert!(screen.contains("Match 1 of 1 for 'pear'"));
+    assert!(screen.contains("\"sku\": \"pear\""));
+
+    harness
+        .press(&[KeyCode::Char('/')])
+        .press(&typed("sku"))
+        .press(&[KeyCode::Enter, KeyCode::Char('n')]);
+    assert!(harness.render().contains("Match 2 of 2 for 'sku'"));
+
+    assert!(!harness.render().contains("end of note"));
+    harness.press(&[KeyCode::Char('>'); 4]);
+    harness.assert_snapshot("streams_preview_json_tree");
+
+    // Projection state and result use the same tree.
+    harness
+        .press(&[KeyCode::Esc, KeyCode::Esc, KeyCode::Esc, KeyCode::Tab])
+        .press(&[KeyCode::Enter, KeyCode::Right])
+        .wait_for("\"customers\"");
+
+    harness
+        .press(&[KeyCode::Down, KeyCode::Char(' ')])
+        .assert_snapshot("projections_state");
+
+    harness.press(&[KeyCode::Right]).wait_for("\"total\": 42");
+}
+
+#[test]
+fn marked_events_are_diffed() {
+    let backend = cluster();
+    backend.append(
+        "carts-9",
+        "CartCreated",
+        json!({ "items": [{ "sku": "apple", "quantity": 1 }], "note": "gift", "owner": "ada" }),
+    );
+    backend.append(
+        "carts-9",
+        "CartUpdated",
+        json!({
+            "items": [{ "sku": "apple", "quantity": 3 }, { "sku": "pear", "quantity": 1 }],
+            "owner": "ada",
+            "coupon": "SPRING"
+        }),
+    );
+
+    let mut harness = Harness::new(backend);
+
+    harness
+        .press(&[KeyCode::Tab, KeyCode::Char(':')])
+        .press(&typed("stream carts-9"))
+        .press(&[KeyCode::Enter, KeyCode::Char(' ')]);
+    assert!(harness.render().contains("[marked]"));
+
+    // A single mark is compared with the selected event, the older one is on the left.
+    harness
+        .press(&[KeyCode::Down, KeyCode::Char('d')])
+        .assert_snapshot("streams_diff_changes");
+
+    harness
+        .press(&[KeyCode::Right])
+        .assert_snapshot("streams_diff_side_by_side");
+
+    // Marks are kept once the diff is closed, the same event isn't compared with itself.
+    harness.press(&[KeyCode::Esc, KeyCode::Up, KeyCode::Char('d')]);
+    assert!(harness.render().contains("[marked]"));
+    assert!(!harness.render().contains("Side by side"));
+
+    harness.press(&[KeyCode::Char(' ')]);
+    assert!(!harness.render().contains("[marked]"));
+}
@@ -0,0 +1,285 @@
+use tui::style::{Color, Modifier, Style};
+
+/// Styles every view draws with, one per role.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// Regular content.
+    pub text: Style,
+    /// Text of table header cells.
+    pub header: Style,
+    /// Whole header row of tables, behind the `header` text.
+    pub normal: Style,
+    /// Selected row.
+    pub selected: Style,
+    /// Tab titles.
+    pub tab: Style,
+    /// Current tab, and the current pane of the event preview and diff.
+    pub selected_tab: Style,
+    /// Tabs block, footer and chart backgrounds.
+    pub panel: Style,
+    /// Keys in the footer.
+    pub key: Style,
+    /// Popup blocks, like forms, dialogs and the command line.
+    pub popup: Style,
+    /// Selected entry of a popup.
+    pub highlight: Style,
+    /// Chart axes.
+    pub emphasis: Style,
+    /// Labels of detail tables.
+    pub label: Style,
+    /// Error messages and the error popup.
+    pub error: Style,
+    /// Destructive actions, tombstoned streams and unresolved links.
+    pub warning: Style,
+    /// Names of JSON object members.
+    pub json_key: Style,
+    /// JSON string values.
+    pub json_string: Style,
+    /// JSON numbers.
+    pub json_number: Style,
+    /// `true`, `false` and `null`.
+    pub json_literal: Style,
+    /// Lines and paths only in the newer event of a diff.
+    pub diff_added: Style,
+    /// Lines and paths only in the older event of a diff.
+    pub diff_removed: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+// Names used in the configuration file.
+pub static THEME_NAMES: &[&str] = &["dark", "light", "high-contrast"];
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            text: Style::default().fg(Color::Gray),
+            header: Style::default().fg(Color::Green),
+            normal: Style::default().add_modifier(Modifier::REVERSED),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            tab: Style::default().fg(Color::LightGreen),
+            selected_tab: Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            panel: Style::default().bg(Color::DarkGray),
+            key: Style::default().fg(Color::Green),
+            popup: Style::default().add_modifier(Modifier::REVERSED),
+            highlight: Style::default().fg(Color::Green),
+            emphasis: Style::default().fg(Color::White),
+            label: Style::default().add_modifier(Modifier::BOLD),
+            error: Style::default().bg(Color::Black).fg(Color::Red),
+            warning: Style::default().bg(Color::Black).fg(Color::Yellow),
+            json_key: Style::default().fg(Color::LightBlue),
+            json_string: Style::default().fg(Color::LightGreen),
+            json_number: Style::default().fg(Color::LightYellow),
+            json_literal: Style::default().fg(Color::LightMagenta),
+            diff_added: Style::default().fg(Color::Green),
+            diff_removed: Style::default().fg(Color::Red),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            text: Style::default().fg(Color::Black),
+            header: Style::default().fg(Color::Blue),
+            normal: Style::default().add_modifier(Modifier::BOLD),
+            selected: Style::default().bg(Color::Blue).fg(Color::White),
+            tab: Style::default().fg(Color::Blue),
+            selected_tab: Style::default()
+                .bg(Color::Blue)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            panel: Style::default().bg(Color::White).fg(Color::Black),
+            key: Style::default().fg(Color::Magenta),
+            popup: Style::default().bg(Color::Gray).fg(Color::Black),
+            highlight: Style::default().fg(Color::Blue),
+            emphasis: Style::default().fg(Color::Black),
+            label: Style::default().add_modifier(Modifier::BOLD),
+            error: Style::default().bg(Color::White).fg(Color::Red),
+            warning: Style::default().bg(Color::White).fg(Color::Magenta),
+            json_key: Style::default().fg(Color::Blue),
+            json_string: Style::default().fg(Color::Green),
+            json_number: Style::default().fg(Color::Magenta),
+            json_literal: Style::default().fg(Color::Red),
+            diff_added: Style::default().fg(Color::Green),
+            diff_removed: Style::default().fg(Color::Red),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            text: Style::default().fg(Color::White),
+            header: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            normal: Style::default().add_modifier(Modifier::UNDERLINED),
+            selected: Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            tab: Style::default().fg(Color::White),
+            selected_tab: Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            panel: Style::default().bg(Color::Black).fg(Color::White),
+            key: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            popup: Style::default().bg(Color::Black).fg(Color::White),
+            highlight: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            emphasis: Style::default().fg(Color::White),
+            label: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            error: Style::default()
+                .bg(Color::Black)
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+            warning: Style::default()
+                .bg(Color::Black)
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            json_key: Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+            json_string: Style::default().fg(Color::LightGreen),
+            json_number: Style::default().fg(Color::Yellow),
+            json_literal: Style::default().fg(Color::LightMagenta),
+            diff_added: Style::default()
+                .fg(Color::LightGreen)
+                .add_modifier(Modifier::BOLD),
+            diff_removed: Style::default()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Same theme without any color, selections still stand out through modifiers.
+    pub fn monochrome(self) -> Self {
+        let strip = |style: Style| Style {
+            fg: None,
+            bg: None,
+            ..style
+        };
+
+        Self {
+            text: strip(self.text),
+            header: strip(self.header).add_modifier(Modifier::BOLD),
+            normal: strip(self.normal),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            tab: strip(self.tab),
+            selected_tab: Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            panel: strip(self.panel),
+            key: strip(self.key).add_modifier(Modifier::BOLD),
+            popup: Style::default().add_modifier(Modifier::REVERSED),
+            highlight: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            emphasis: strip(self.emphasis),
+            label: strip(self.label),
+            error: strip(self.error).add_modifier(Modifier::BOLD),
+            warning: strip(self.warning).add_modifier(Modifier::BOLD),
+            json_key: strip(self.json_key).add_modifier(Modifier::BOLD),
+            json_string: strip(self.json_string),
+            json_number: strip(self.json_number),
+            json_literal: strip(self.json_literal),
+            diff_added: strip(self.diff_added).add_modifier(Modifier::BOLD),
+            diff_removed: strip(self.diff_removed).add_modifier(Modifier::CROSSED_OUT),
+        }
+    }
+
+    /// Role by its configuration name.
+    pub fn role_mut(&mut self, name: &str) -> Option<&mut Style> {
+        let style = match name {
+            "text" => &mut self.text,
+            "header" => &mut self.header,
+            "normal" => &mut self.normal,
+            "selected" => &mut self.selected,
+            "tab" => &mut self.tab,
+            "selected-tab" => &mut self.selected_tab,
+            "panel" => &mut self.panel,
+            "key" => &mut self.key,
+            "popup" => &mut self.popup,
+            "highlight" => &mut self.highlight,
+            "emphasis" => &mut self.emphasis,
+            "label" => &mut self.label,
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "json-key" => &mut self.json_key,
+            "json-string" => &mut self.json_string,
+            "json-number" => &mut self.json_number,
+            "json-literal" => &mut self.json_literal,
+            "diff-added" => &mut self.diff_added,
+            "diff-removed" => &mut self.diff_removed,
+            _ => return None,
+        };
+
+        Some(style)
+    }
+}
+
+/// Parses color names like `dark-gray`, hex values like `#ff8800` and 256 colors indexes.
+pub fn parse_color(input: &str) -> Result<Color, String> {
+    let color = match input.to_lowercase().replace('_', "-").as_str() {
+        "reset" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "dark-gray" | "dark-grey" => Color::DarkGray,
+        "light-red" => Color::LightRed,
+        "light-green" => Color::LightGreen,
+        "light-yellow" => Color::LightYellow,
+        "light-blue" => Color::LightBlue,
+        "light-magenta" => Color::LightMagenta,
+        "light-cyan" => Color::LightCyan,
+        "white" => Color::White,
+        value => {
+            if let Some(hex) = value.strip_prefix('#') {
+                match u32::from_str_radix(hex, 16) {
+                    Ok(rgb) if hex.len() == 6 => {
+                        Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+                    }
+                    _ => return Err(format!("Invalid color '{}'", input)),
+                }
+            } else {
+                match value.parse::<u8>() {
+                    Ok(idx) => Color::Indexed(idx),
+                    Err(_) => return Err(format!("Unknown color '{}'", input)),
+                }
+            }
+        }
+    };
+
+    Ok(color)
+}
+
+pub fn parse_modifier(input: &str) -> Result<Modifier, String> {
+    let modifier = match input.to_lowercase().as_str() {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "underlined" => Modifier::UNDERLINED,
+        "reversed" => Modifier::REVERSED,
+        "crossed-out" => Modifier::CROSSED_OUT,
+        _ => return Err(format!("Unknown modifier '{}'", input)),
+    };
+
+    Ok(modifier)
+}